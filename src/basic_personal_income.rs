@@ -2,11 +2,44 @@
 //! The Basic Personal Amount (BPA) is a non-refundable tax credit that all individuals can claim in Canada. It provides a full reduction from federal income tax for individuals with taxable income below the BPA and a partial reduction for those with taxable income above it. 
 //! It's important to note that the BPA is adjusted annually due to inflation and government policy.
 
+use crate::error::PayrollError;
+use crate::province::Province;
 use crate::utils;
-use crate::year::v2025;
+use crate::year::{v2025, YearConstants};
+
+/** Prescribed zone of residence, for the northern residents deduction.
+*
+*   Employees living in a prescribed northern zone claim the full residency deduction; those in a prescribed intermediate zone claim half of it.
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ZoneType {
+    #[default]
+    NotPrescribed,
+    Northern,
+    Intermediate,
+}
+
+/** Annual deduction for living in a prescribed zone (HD)
+*
+* Given:
+*
+*   zone: The prescribed zone the employee lives in
+*
+*   days: The number of days in the year the employee lives in the zone
+*/
+pub fn prescribed_zone_deduction(zone: ZoneType, days: i64) -> f64 {
+    let daily_amount: f64 = match zone {
+        ZoneType::NotPrescribed => 0.0,
+        ZoneType::Northern => v2025::NORTHERN_RESIDENCY_DAILY_AMT,
+        ZoneType::Intermediate => v2025::NORTHERN_RESIDENCY_DAILY_AMT / 2.0,
+    };
+    utils::round(daily_amount * days.max(0) as f64)
+}
 
 /** Calculate Federal Basic Personal Amount.
 *
+*   The basic personal amount of the year up to the fourth federal income threshold, phased out evenly to the reduced amount at the fifth. Returns `PayrollError::NonFiniteInput` if the net income is not a number, and `PayrollError::UnsupportedYear` if the year's federal rates table has fewer than five brackets.
+*
 *
 * Given:
 *
@@ -14,6 +47,8 @@ use crate::year::v2025;
 *
 *   HD: Annual deduction for living in a prescribed zone, as shown on Form TD1
 *
+*   year: The constants for the year of the calculation
+*
 * Where:
 *
 *   NI: Net Income
@@ -21,29 +56,59 @@ use crate::year::v2025;
 *   NI = A + HD
 */
 #[allow(non_snake_case)]
-pub fn BPAF(A: f64, HD: f64) -> Result<f64, f64> {
-    let mut BPAF: f64 = 0.0;
+pub fn BPAF(A: f64, HD: f64, year: &YearConstants) -> Result<f64, PayrollError> {
+    let (threshold_4, threshold_5) = match (year.federal_brackets.get(3), year.federal_brackets.get(4)) {
+        (Some(fourth), Some(fifth)) => (fourth.threshold, fifth.threshold),
+        _ => return Err(PayrollError::UnsupportedYear(year.year)),
+    };
+    let maximum = year.federal_basic_personal_amount;
+    let reduced = year.federal_reduced_basic_personal_amount;
+    let BPAF: f64;
     let NI = A+HD;
 
-    if NI <= v2025::INCOME_THRESHOLD_4 {
-        BPAF = v2025::MINIMUM_BASIC_AMT;
+    if NI <= threshold_4 {
+        BPAF = maximum;
     } else
-    if v2025::INCOME_THRESHOLD_4 < NI && NI < v2025::INCOME_THRESHOLD_5 {
-        BPAF = v2025::MINIMUM_BASIC_AMT - (NI*-v2025::INCOME_THRESHOLD_4) * (1591.0 / 75532.0);
+    if threshold_4 < NI && NI < threshold_5 {
+        BPAF = maximum - (NI - threshold_4) * ((maximum - reduced) / (threshold_5 - threshold_4));
     } else
-    if NI > v2025::INCOME_THRESHOLD_5 {
-        BPAF = v2025::MAXIMUM_BASIC_AMT;
-    }
-
-    if BPAF == 0.0 {
-        return Err(0.0)
+    if NI >= threshold_5 {
+        BPAF = reduced;
+    } else {
+        return Err(PayrollError::NonFiniteInput("A"))
     }
 
     Ok(utils::round(BPAF))
 }
 
+/// Annual taxable income (A), and whether the tax for the pay period is only the additional tax L.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[allow(non_snake_case)]
+pub struct AnnualTaxableIncome {
+    /// A: Annual taxable income, rounded to the cent; negative when the deductions exceed the income
+    pub annual_taxable_income: f64,
+    /// The deductions exceed the income, so the tax for the pay period (T) is only L
+    pub tax_floored_to_L: bool,
+}
+
+impl AnnualTaxableIncome {
+    /** The tax for the pay period: L when the annual taxable income is negative, otherwise T.
+    *
+    * Given:
+    *
+    *   T: Estimated federal and provincial or territorial tax deductions for the pay period
+    *
+    *   L: Additional tax deductions for the pay period requested by the employee or pensioner as shown on Form TD1
+    */
+    #[allow(non_snake_case)]
+    pub fn tax(&self, T: f64, L: f64) -> f64 {
+        if self.tax_floored_to_L { L } else { T }
+    }
+}
+
 /** Calculate Non-Commissionable Income Tax.
 *
+*   When the annual taxable income is negative, the tax for the pay period is L; see `AnnualTaxableIncome::tax`.
 *
 * Given:
 *
@@ -66,19 +131,12 @@ pub fn BPAF(A: f64, HD: f64) -> Result<f64, f64> {
 *   HD: Annual deduction for living in a prescribed zone, as shown on Form TD1
 *
 *   F1: Annual deductions such as child care expenses and support payments requested by an employee or pensioner and authorized by a tax services office or tax centre
-*
-*   T: Estimated federal and provincial or territorial tax deductions for the pay period
-*
-*   L: Additional tax deductions for the pay period requested by the employee or pensioner as shown on Form TD1
 */
 #[allow(non_snake_case)]
-pub fn A(P: i64, I: f64, F: f64, F2: f64, F5A: f64, U1: f64, HD: f64, F1: f64, mut T: f64, L: f64) -> (f64, f64) {
+pub fn A(P: i64, I: f64, F: f64, F2: f64, F5A: f64, U1: f64, HD: f64, F1: f64) -> AnnualTaxableIncome {
     let a: f64;
     a = P as f64 * (I - F - F2 -F5A -U1) - HD - F1;
-    if a.is_sign_negative() {
-        T = L
-    }
-    (utils::round(a), T)
+    AnnualTaxableIncome { annual_taxable_income: utils::round(a), tax_floored_to_L: a.is_sign_negative() }
 }
 
 /** Calculate Non-Commissionable Income Tax
@@ -113,11 +171,7 @@ pub fn A(P: i64, I: f64, F: f64, F2: f64, F5A: f64, U1: f64, HD: f64, F1: f64, m
 */
 #[allow(non_snake_case)]
 pub fn A_grad(S1: f64, I: f64, F: f64, F1: f64, F2: f64, F4: f64, F5A: f64, F5B: f64, U1: f64, B1: f64, HD: f64) -> f64 {
-    let a: f64 = (S1 * (I - F - F2 - F5A - U1)) + (B1 - F4 - F5B) - HD - F1;
-    if a.is_sign_negative() {
-        return 0.0;
-    }
-    a
+    utils::floor_zero((S1 * (I - F - F2 - F5A - U1)) + (B1 - F4 - F5B) - HD - F1)
 }
 
 /** Annualizing factor
@@ -129,25 +183,186 @@ pub fn A_grad(S1: f64, I: f64, F: f64, F1: f64, F2: f64, F4: f64, F5A: f64, F5B:
 */
 #[allow(non_snake_case)]
 pub fn S1(total_pay_periods: i64, current_pay_period: i64) -> f64 {
-    (total_pay_periods / current_pay_period) as f64
+    total_pay_periods as f64 / current_pay_period as f64
+}
+
+/** Annualized income from year-to-date earnings
+*
+*   Projects the income for the year from the earnings to date, including the current pay period, at the average rate earned so far (S1 times the earnings to date).
+*
+* Given:
+*
+*   ytd_income: Year-to-date (before the pay period) income
+*
+*   current_period_income: Income for the current pay period
+*
+*   periods_elapsed: The number of pay periods to date, including the current pay period; treated as 1 if less
+*
+*   periods_total: The number of pay periods in the year
+*/
+pub fn annualize_ytd(ytd_income: f64, current_period_income: f64, periods_elapsed: i64, periods_total: i64) -> f64 {
+    utils::round(S1(periods_total, periods_elapsed.max(1)) * (ytd_income + current_period_income))
+}
+
+/// The total claim amount for a claim code, from the highest claim amount of each code from 1 to 10.
+fn claim_amount(code: u8, ranges: &[f64; 10]) -> Result<f64, PayrollError> {
+    match code {
+        0 => Ok(0.0),
+        1 => Ok(ranges[0]),
+        2..=10 => Ok((ranges[code as usize - 2] + ranges[code as usize - 1]) / 2.0),
+        _ => Err(PayrollError::InvalidClaimCode(code)),
+    }
+}
+
+/** Federal total claim amount (TC) for a TD1 claim code
+*
+*   Code 0 is no claim amount, for the most tax withheld; code 1 is the basic personal amount; codes 2 to 10 are the middle of the code's range of claim amounts. Returns `PayrollError::InvalidClaimCode` for a code above 10, and `PayrollError::UnsupportedYear` for a year without claim code tables.
+*
+* Given:
+*
+*   code: The claim code on Form TD1
+*
+*   year: The constants for the year of the calculation
+*/
+pub fn federal_claim_amount_from_code(code: u8, year: &YearConstants) -> Result<f64, PayrollError> {
+    match year.year {
+        2025 => claim_amount(code, &v2025::FEDERAL_CLAIM_CODES),
+        _ => Err(PayrollError::UnsupportedYear(year.year)),
+    }
+}
+
+/** Provincial or territorial total claim amount (TCP) for a TD1 claim code
+*
+*   As for `federal_claim_amount_from_code`, from the province's claim code table. Returns `PayrollError::UnsupportedProvince` for a province or territory without a table for the year.
+*
+* Given:
+*
+*   code: The claim code on the provincial or territorial Form TD1
+*
+*   province: Province or territory of employment
+*
+*   year: The constants for the year of the calculation
+*/
+pub fn claim_amount_from_code(code: u8, province: Province, year: &YearConstants) -> Result<f64, PayrollError> {
+    match (province, year.year) {
+        (Province::Ontario, 2025) => claim_amount(code, &v2025::ONTARIO_CLAIM_CODES),
+        (Province::Alberta, 2025) => claim_amount(code, &v2025::ALBERTA_CLAIM_CODES),
+        _ => Err(PayrollError::UnsupportedProvince(province)),
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    use crate::year::v2024;
+
+    #[test]
+    fn test_claim_amount_from_code() {
+        let year = &v2025::CONSTANTS;
+        assert_eq!(federal_claim_amount_from_code(0, year), Ok(0.0));
+        assert_eq!(federal_claim_amount_from_code(1, year), Ok(v2025::MINIMUM_BASIC_AMT));
+        assert_eq!(federal_claim_amount_from_code(2, year), Ok(17690.0));
+        assert_eq!(federal_claim_amount_from_code(10, year), Ok(42666.0));
+        assert_eq!(federal_claim_amount_from_code(11, year), Err(PayrollError::InvalidClaimCode(11)));
+
+        assert_eq!(claim_amount_from_code(0, Province::Ontario, year), Ok(0.0));
+        assert_eq!(claim_amount_from_code(1, Province::Ontario, year), Ok(v2025::ONTARIO_BASIC_AMT));
+        assert_eq!(claim_amount_from_code(1, Province::Alberta, year), Ok(v2025::ALBERTA_BASIC_AMT));
+        assert_eq!(claim_amount_from_code(12, Province::Alberta, year), Err(PayrollError::InvalidClaimCode(12)));
+        assert_eq!(
+            claim_amount_from_code(1, Province::Manitoba, year),
+            Err(PayrollError::UnsupportedProvince(Province::Manitoba))
+        );
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_A_with_positive_income_keeps_T() {
+        let annual = A(26, 2000.0, 100.0, 0.0, 10.0, 20.0, 0.0, 500.0);
+        assert_eq!(annual.annual_taxable_income, 26.0 * 1870.0 - 500.0);
+        assert!(!annual.tax_floored_to_L);
+        assert_eq!(annual.tax(150.0, 25.0), 150.0);
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_A_with_negative_income_floors_tax_to_L() {
+        let annual = A(26, 100.0, 150.0, 0.0, 0.0, 0.0, 0.0, 0.0);
+        assert_eq!(annual.annual_taxable_income, -1300.0);
+        assert!(annual.tax_floored_to_L);
+        assert_eq!(annual.tax(150.0, 25.0), 25.0);
+    }
+
     #[test]
     #[allow(non_snake_case)]
     fn test_BPAF_minimum_amt() {
-        let result = BPAF(10000.0, 0.0);
+        let result = BPAF(10000.0, 0.0, &v2025::CONSTANTS);
         assert_eq!(result.unwrap(), v2025::MINIMUM_BASIC_AMT);
     }
 
     #[test]
     #[allow(non_snake_case)]
     fn test_BPAF_maximum_amt() {
-        let result = BPAF(253414.01, 0.0);
+        let result = BPAF(253414.01, 0.0, &v2025::CONSTANTS);
         assert_eq!(result.unwrap(), v2025::MAXIMUM_BASIC_AMT);
+        assert_eq!(BPAF(v2025::INCOME_THRESHOLD_5, 0.0, &v2025::CONSTANTS), Ok(v2025::MAXIMUM_BASIC_AMT));
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_BPAF_phase_out_uses_net_income() {
+        // Halfway through the phase-out
+        assert_eq!(BPAF(215648.0, 0.0, &v2025::CONSTANTS), Ok(15333.5));
+        assert_eq!(BPAF(215648.0 - 8030.0, 8030.0, &v2025::CONSTANTS), Ok(15333.5));
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_BPAF_uses_the_amounts_and_thresholds_of_the_year() {
+        let year = &v2024::CONSTANTS;
+        assert_eq!(BPAF(100000.0, 0.0, year), Ok(15705.0));
+        // Halfway between the 2024 thresholds of 173,205 and 246,752
+        assert_eq!(BPAF(209978.5, 0.0, year), Ok(14930.5));
+        assert_eq!(BPAF(300000.0, 0.0, year), Ok(14156.0));
+        assert_eq!(BPAF(f64::NAN, 0.0, year), Err(PayrollError::NonFiniteInput("A")));
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_S1_is_not_truncated() {
+        assert_eq!(S1(26, 1), 26.0);
+        assert_eq!(S1(26, 4), 6.5);
+        assert_eq!(S1(26, 26), 1.0);
+    }
+
+    #[test]
+    fn test_annualize_ytd_even_income() {
+        assert_eq!(annualize_ytd(0.0, 2000.0, 1, 26), 52000.0);
+        assert_eq!(annualize_ytd(18000.0, 2000.0, 10, 26), 52000.0);
+    }
+
+    #[test]
+    fn test_annualize_ytd_uneven_income() {
+        // A big first period of 10,000, then 2,000 a period
+        assert_eq!(annualize_ytd(0.0, 10000.0, 1, 26), 260000.0);
+        assert_eq!(annualize_ytd(10000.0, 2000.0, 2, 26), 156000.0);
+        assert_eq!(annualize_ytd(10000.0 + 2000.0 * 11.0, 2000.0, 13, 26), 68000.0);
+        // The big period averages out by the end of the year
+        assert_eq!(annualize_ytd(10000.0 + 2000.0 * 24.0, 2000.0, 26, 26), 60000.0);
+    }
+
+    #[test]
+    fn test_annualize_ytd_before_first_period() {
+        assert_eq!(annualize_ytd(0.0, 2000.0, 0, 12), 24000.0);
+    }
+
+    #[test]
+    fn test_prescribed_zone_deduction() {
+        assert_eq!(prescribed_zone_deduction(ZoneType::NotPrescribed, 365), 0.0);
+        assert_eq!(prescribed_zone_deduction(ZoneType::Northern, 365), 8030.0);
+        assert_eq!(prescribed_zone_deduction(ZoneType::Intermediate, 365), 4015.0);
+        assert_eq!(prescribed_zone_deduction(ZoneType::Northern, 0), 0.0);
     }
 
 }
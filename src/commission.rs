@@ -0,0 +1,156 @@
+//! # Commission Income Tax
+//! Tax for commission employees who file Form TD1X, Statement of Commission Income and Expenses for Payroll Tax Deductions.
+//!
+//! The tax is calculated on the employee's estimate of their net income for the year, rather than by annualizing each
+//! pay period, and each commission payment is withheld at the share of that annual tax it represents.
+
+use crate::error::PayrollError;
+use crate::payroll::{self, FactorOverrides, PayrollContext, PayrollInput};
+use crate::province::Province;
+use crate::provincial_income_tax::provincial_income_tax;
+use crate::utils;
+use crate::year::YearConstants;
+
+/// The estimates for the year from an employee's Form TD1X.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Td1x {
+    /// Estimated commission income for the year
+    pub estimated_commission: f64,
+    /// E: Estimated commission expenses for the year
+    pub estimated_expenses: f64,
+    /// Salary and other employment income from the employer for the year
+    pub other_income: f64,
+    /// F1: Annual deductions, such as child care expenses and support payments, authorized by a tax services office or tax centre
+    pub deductions: f64,
+}
+
+impl Td1x {
+    /// I1: Total remuneration for the year, the commission and other employment income
+    #[allow(non_snake_case)]
+    pub fn I1(&self) -> f64 {
+        self.estimated_commission + self.other_income
+    }
+}
+
+/** Annual taxable income for a commission employee (Factor A, commission)
+*
+*   A = I1 − E − F1, where I1 is the commission and other employment income for the year; never negative.
+*
+* Given:
+*
+*   estimated_commission: Estimated commission income for the year, from Form TD1X
+*
+*   estimated_expenses: E: Estimated commission expenses for the year, from Form TD1X
+*
+*   other_income: Salary and other employment income from the employer for the year
+*
+*   deductions: F1: Annual deductions authorized by a tax services office or tax centre
+*/
+pub fn annual_taxable_income(estimated_commission: f64, estimated_expenses: f64, other_income: f64, deductions: f64) -> f64 {
+    utils::round(utils::floor_zero(estimated_commission + other_income - estimated_expenses - deductions))
+}
+
+/** Base CPP contributions and EI premiums tax credits for a commission employee (K2 or K2P, commission)
+*
+*   The credits on the contributions and premiums for the year's total remuneration, up to the annual maximums, at the lowest federal or provincial rate.
+*
+* Given:
+*
+*   lowest_rate: Lowest federal tax rate for K2, or the lowest provincial or territorial tax rate for K2P
+*
+*   I1: Total remuneration for the year
+*
+*   province: Province of employment; Quebec employees pay EI at the Quebec rate
+*
+*   year: The constants for the year of the calculation
+*/
+#[allow(non_snake_case)]
+pub fn K2_commission(lowest_rate: f64, I1: f64, province: Province, year: &YearConstants) -> f64 {
+    let total_rate = year.cpp_base_rate + year.cpp_first_additional_rate;
+    let cpp = utils::clamp(total_rate * (I1 - year.cpp_basic_exemption), 0.0, year.cpp_total_max_contributions);
+    let (ei_rate, ei_max) = match province {
+        Province::Quebec => (year.ei_quebec_rate, year.ei_quebec_max_contributions),
+        _ => (year.ei_rate, year.ei_max_contributions),
+    };
+    let ei = utils::clamp(ei_rate * I1, 0.0, ei_max);
+
+    utils::round(lowest_rate * (cpp * (year.cpp_base_rate / total_rate) + ei))
+}
+
+/** Tax to withhold from a commission payment
+*
+*   T = (T1 + T2) × G / I1 + L, where T1 and T2 are the annual federal and provincial tax on the TD1X annual taxable income (see `annual_taxable_income`), with the commission CPP and EI credits (see `K2_commission`). The claim amounts, other credits and L are from `input`; its pay-period earnings are not used.
+*
+*   Returns `PayrollError::UnsupportedProvince` for a province or territory without tables for the year.
+*
+* Given:
+*
+*   G: Gross remuneration for the pay period, including the commission
+*
+*   td1x: The estimates for the year from the employee's Form TD1X
+*
+*   ctx: The settings for the payroll run
+*
+*   input: The employee's claims for the pay period
+*/
+#[allow(non_snake_case)]
+pub fn period_tax(G: f64, td1x: &Td1x, ctx: &PayrollContext, input: &PayrollInput) -> Result<f64, PayrollError> {
+    let L = input.additional_tax;
+    let I1 = td1x.I1();
+    if I1 <= 0.0 {
+        return Ok(utils::round(L));
+    }
+
+    let A = annual_taxable_income(td1x.estimated_commission, td1x.estimated_expenses, td1x.other_income, td1x.deductions);
+    let provincial_rate = provincial_income_tax::lowest_rate(ctx.province, ctx.year);
+    let overrides = FactorOverrides {
+        k2: Some(K2_commission(ctx.year.federal_brackets[0].rate, I1, ctx.province, ctx.year)),
+        k2p: Some(K2_commission(provincial_rate, I1, ctx.province, ctx.year)),
+        ..input.overrides
+    };
+    let input = PayrollInput { overrides, ..input.clone() };
+
+    let T1 = payroll::federal_tax(ctx, &input, A, 0.0, 0.0);
+    let T2 = payroll::provincial_tax(ctx, &input, A, 0.0, 0.0)?;
+    Ok(utils::round((T1 + T2) * G / I1 + L))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures;
+
+    #[test]
+    fn test_annual_taxable_income_nets_expenses() {
+        assert_eq!(annual_taxable_income(50000.0, 10000.0, 10000.0, 0.0), 50000.0);
+        assert_eq!(annual_taxable_income(50000.0, 10000.0, 10000.0, 2500.0), 47500.0);
+        assert_eq!(annual_taxable_income(5000.0, 8000.0, 0.0, 0.0), 0.0);
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_K2_commission_on_the_year_estimate() {
+        let (ctx, _) = test_fixtures::ontario_biweekly_employee();
+        // 15% × (4.95% × (60,000 − 3,500) + 1.64% × 60,000)
+        assert_eq!(K2_commission(0.15, 60000.0, Province::Ontario, ctx.year), 567.11);
+        // At the maximums
+        assert_eq!(K2_commission(0.15, 200000.0, Province::Ontario, ctx.year), utils::round(0.15 * (3356.1 + 1077.48)));
+    }
+
+    #[test]
+    fn test_period_tax_for_a_td1x_commission_payment() {
+        // A worked example of the TD1X formula: $50,000 of commission, a $10,000 salary and $10,000 of expenses, so
+        // A = 50,000, and a $3,000 commission payment, 5% of I1
+        let (ctx, input) = test_fixtures::ontario_biweekly_employee();
+        let td1x = Td1x { estimated_commission: 50000.0, estimated_expenses: 10000.0, other_income: 10000.0, deductions: 0.0 };
+
+        // T3 = 15% × 50,000 − 2,419.35 (K1) − 567.11 (K2) − 220.65 (K4) = 4,292.89
+        // T4 = 5.05% × 50,000 − 643.72 (K1P) − 190.93 (K2P) = 1,690.35; with the $600 health premium, T2 = 2,290.35
+        assert_eq!(period_tax(3000.0, &td1x, &ctx, &input), Ok(utils::round((4292.89 + 2290.35) * 0.05)));
+        assert_eq!(period_tax(3000.0, &td1x, &ctx, &input), Ok(329.16));
+
+        let input = PayrollInput { additional_tax: 20.0, ..input };
+        assert_eq!(period_tax(3000.0, &td1x, &ctx, &input), Ok(349.16));
+        assert_eq!(period_tax(3000.0, &Td1x::default(), &ctx, &input), Ok(20.0));
+    }
+}
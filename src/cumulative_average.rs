@@ -0,0 +1,401 @@
+//! # Cumulative Average Calculation
+//! Runs the deductions for an employee pay period by pay period under Option 2 (cumulative averaging), carrying the year-to-date amounts the `_grad` factors need from one period to the next.
+
+use crate::basic_personal_income;
+use crate::error::PayrollError;
+use crate::federal_income_tax;
+use crate::income_tax;
+use crate::other_deductions;
+use crate::payroll::{self, FactorOverrides, PayeeType, PayrollContext, PayrollInput, PayrollResult};
+use crate::provincial_income_tax::provincial_income_tax;
+use crate::utils;
+
+/// Deductions for one pay period under the cumulative average calculation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CumulativePeriod {
+    /// Deductions for the pay period; `tax` is the tax on the periodic earnings only
+    pub result: PayrollResult,
+    /// TB: Tax on the non-periodic payment in the pay period
+    pub bonus_tax: f64,
+}
+
+/// How the tax for a pay period is calculated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Method {
+    /// Option 1: the pay period's earnings annualized; see `payroll::calculate`
+    Periodic,
+    /// Option 2: the year-to-date earnings annualized; see `CumulativeAverage::process`
+    Cumulative,
+    /// Calculate both and use the one the employer prefers
+    Auto(Preference),
+}
+
+/// Which method's tax `Method::Auto` uses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Preference {
+    /// The method that withholds less tax in the pay period
+    LessWithheld,
+    /// The method that withholds more tax in the pay period, leaving less to pay on the return
+    MoreWithheld,
+}
+
+/// Deductions for one pay period, and the method used for its tax: `Method::Periodic` or `Method::Cumulative`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MethodPeriod {
+    pub period: CumulativePeriod,
+    pub method: Method,
+}
+
+/** Year-to-date amounts for one employee with one employer, updated as each pay period is processed.
+*
+*   All amounts are before the next pay period to be processed.
+*/
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct CumulativeAverage {
+    /// Number of pay periods processed
+    pub periods: i64,
+    /// IYTD: Gross remuneration, not including non-periodic payments
+    pub ytd_gross: f64,
+    /// FYTD: RPP, RRSP, PRPP or RCA contributions and other pre-tax deductions
+    pub ytd_retirement_contributions: f64,
+    /// F2YTD: Alimony or maintenance payments
+    pub ytd_support_payments: f64,
+    /// U1YTD: Union dues
+    pub ytd_union_dues: f64,
+    /// F5AYTD: Additional CPP contributions deducted from periodic income
+    pub ytd_periodic_additional_cpp: f64,
+    /// F5BYTD: Additional CPP contributions deducted from non-periodic payments
+    pub ytd_non_periodic_additional_cpp: f64,
+    /// B1: Non-periodic payments
+    pub ytd_bonuses: f64,
+    /// D: CPP contributions
+    pub ytd_cpp: f64,
+    /// D2: Second additional CPP contributions
+    pub ytd_cpp2: f64,
+    /// D1: EI premiums
+    pub ytd_ei: f64,
+    /// DQPIP: QPIP premiums, in Quebec
+    pub ytd_qpip: f64,
+    /// PI_YTD: Pensionable earnings, including non-periodic payments
+    pub ytd_pensionable: f64,
+    /// PEYTD: Pensionable earnings, the gross income plus taxable benefits, not including non-periodic payments
+    pub ytd_periodic_pensionable: f64,
+    /// IEYTD: Insurable earnings, including insurable taxable benefits, not including non-periodic payments
+    pub ytd_insurable: f64,
+    /// M: Tax deducted on periodic earnings, not including L
+    pub accumulated_tax: f64,
+    /// M1: Tax deducted on non-periodic payments
+    pub accumulated_bonus_tax: f64,
+}
+
+impl CumulativeAverage {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /** Calculate the deductions for the next pay period and add them to the year-to-date amounts.
+    *
+    *   The year-to-date amounts in `input` are ignored in favour of the accumulated ones, and the rounding stage is not used. The CPP and EI credits are K2_grad and K2P_grad on PEYTD and IEYTD, unless overridden.
+    *
+    * Given:
+    *
+    *   ctx: The settings for the payroll run
+    *
+    *   input: The employee's earnings and claims for the pay period
+    *
+    *   B: Non-periodic payment in the pay period, such as a bonus
+    */
+    #[allow(non_snake_case)]
+    pub fn process(&mut self, ctx: &PayrollContext, input: &PayrollInput, B: f64) -> Result<CumulativePeriod, PayrollError> {
+        payroll::check_finite(input)?;
+        if !B.is_finite() {
+            return Err(PayrollError::NonFiniteInput("B"));
+        }
+
+        let P = ctx.periods_per_year();
+        let S1 = basic_personal_income::S1(P, self.periods + 1);
+        let PM = input.cpp_contribution_months(ctx.year.year);
+        let I = input.gross();
+        let PI = input.pensionable_earnings() + B;
+
+        let (C, C2, EI) = match ctx.payee_type {
+            PayeeType::Pensioner => (0.0, 0.0, 0.0),
+            PayeeType::Employee if input.cpp_stopped() => {
+                (0.0, 0.0, other_deductions::EI(self.ytd_ei, input.insurable_earnings() + B, ctx.province, ctx.year))
+            }
+            PayeeType::Employee => {
+                let W = other_deductions::W(self.ytd_pensionable, ctx.year.ympe, PM);
+                (
+                    payroll::pension_contributions(ctx, PM, self.ytd_cpp, PI),
+                    other_deductions::C2(PM, self.ytd_cpp2, self.ytd_pensionable, PI, W, ctx.year),
+                    other_deductions::EI(self.ytd_ei, input.insurable_earnings() + B, ctx.province, ctx.year),
+                )
+            }
+        };
+
+        let QPIP = payroll::qpip(ctx, self.ytd_qpip, input.insurable_earnings() + B);
+
+        let F5 = payroll::additional_contributions(ctx, C, C2);
+        let F5A = if PI > 0.0 { federal_income_tax::F5A(F5, PI, B) } else { 0.0 };
+        let F5B = utils::round(F5 - F5A);
+
+        let HD = basic_personal_income::prescribed_zone_deduction(input.prescribed_zone, input.prescribed_zone_days);
+        let A = basic_personal_income::A_grad(
+            S1,
+            self.ytd_gross + I,
+            self.ytd_retirement_contributions + input.pre_tax_deductions(),
+            input.annual_deductions,
+            self.ytd_support_payments + input.support_payments,
+            0.0,
+            self.ytd_periodic_additional_cpp + F5A,
+            self.ytd_non_periodic_additional_cpp,
+            self.ytd_union_dues + input.union_dues,
+            self.ytd_bonuses,
+            HD,
+        );
+        let A = utils::round(A);
+
+        // Pensioners and employees who have stopped contributing have no CPP credit to annualize
+        let input = &match ctx.payee_type {
+            PayeeType::Employee if !input.cpp_stopped() => {
+                let PE = self.ytd_periodic_pensionable + input.pensionable_earnings();
+                let IE = self.ytd_insurable + input.insurable_earnings();
+                let rate = provincial_income_tax::lowest_rate(ctx.province, ctx.year);
+                let overrides = FactorOverrides {
+                    k2: input.overrides.k2.or(Some(federal_income_tax::K2_grad(S1, PE, self.ytd_bonuses, IE, ctx.year))),
+                    k2p: input.overrides.k2p.or(Some(provincial_income_tax::K2P_grad(rate, PE, S1, self.ytd_bonuses, IE, ctx.year))),
+                    ..input.overrides
+                };
+                PayrollInput { overrides, ..input.clone() }
+            }
+            _ => input.clone(),
+        };
+
+        let T1 = payroll::federal_tax(ctx, input, A, C, EI);
+        let T2 = payroll::provincial_tax(ctx, input, A, C, EI)?;
+        let L = input.additional_tax;
+        let T = income_tax::T_grad(T1, T2, self.accumulated_bonus_tax, S1, self.accumulated_tax, L);
+
+        let TB = if B > 0.0 {
+            let A_with_bonus = utils::round(A + B - F5B);
+            let T1_with_bonus = payroll::federal_tax(ctx, input, A_with_bonus, C, EI);
+            let T2_with_bonus = payroll::provincial_tax(ctx, input, A_with_bonus, C, EI)?;
+            utils::round(utils::floor_zero(T1_with_bonus + T2_with_bonus - T1 - T2))
+        } else {
+            0.0
+        };
+
+        let net = input.cash_income + B
+            - C
+            - C2
+            - EI
+            - QPIP
+            - T
+            - TB
+            - input.pre_tax_deductions()
+            - input.support_payments
+            - input.union_dues
+            - input.post_tax_deductions();
+
+        self.periods += 1;
+        self.ytd_gross += I;
+        self.ytd_retirement_contributions += input.pre_tax_deductions();
+        self.ytd_support_payments += input.support_payments;
+        self.ytd_union_dues += input.union_dues;
+        self.ytd_periodic_additional_cpp += F5A;
+        self.ytd_non_periodic_additional_cpp += F5B;
+        self.ytd_bonuses += B;
+        self.ytd_cpp = utils::round(self.ytd_cpp + C);
+        self.ytd_cpp2 = utils::round(self.ytd_cpp2 + C2);
+        self.ytd_ei = utils::round(self.ytd_ei + EI);
+        self.ytd_qpip = utils::round(self.ytd_qpip + QPIP);
+        self.ytd_pensionable += PI;
+        self.ytd_periodic_pensionable += input.pensionable_earnings();
+        self.ytd_insurable += input.insurable_earnings();
+        self.accumulated_tax = utils::round(self.accumulated_tax + utils::floor_zero(T - L));
+        self.accumulated_bonus_tax = utils::round(self.accumulated_bonus_tax + TB);
+
+        Ok(CumulativePeriod {
+            result: PayrollResult {
+                gross: utils::round(I + B),
+                cpp: C,
+                cpp2: C2,
+                ei: EI,
+                qpip: QPIP,
+                annual_taxable_income: A,
+                basic_personal_amount: basic_personal_income::BPAF(A, HD, ctx.year)?,
+                federal_tax: T1,
+                provincial_tax: T2,
+                tax: T,
+                net: utils::round(net),
+            },
+            bonus_tax: TB,
+        })
+    }
+
+    /** Calculate the deductions for the next pay period with the given method, and add them to the year-to-date amounts.
+    *
+    *   The CPP and EI are the same with either method. For `Method::Periodic`, the tax is that of `payroll::calculate` on the pay period's earnings, and the tax on a non-periodic payment that of `payroll::bonus_method_tax`. The tax actually withheld is added to M and M1, so a later cumulative pay period accounts for it. `Method::Auto` compares the tax plus the non-periodic payment's tax of each.
+    *
+    * Given:
+    *
+    *   ctx: The settings for the payroll run
+    *
+    *   input: The employee's earnings and claims for the pay period
+    *
+    *   B: Non-periodic payment in the pay period, such as a bonus
+    *
+    *   method: How to calculate the tax
+    */
+    #[allow(non_snake_case)]
+    pub fn process_with_method(&mut self, ctx: &PayrollContext, input: &PayrollInput, B: f64, method: Method) -> Result<MethodPeriod, PayrollError> {
+        let before = self.clone();
+        let cumulative = self.process(ctx, input, B)?;
+        if method == Method::Cumulative {
+            return Ok(MethodPeriod { period: cumulative, method });
+        }
+
+        let input = PayrollInput {
+            ytd_cpp: before.ytd_cpp,
+            ytd_cpp2: before.ytd_cpp2,
+            ytd_ei: before.ytd_ei,
+            ytd_qpip: before.ytd_qpip,
+            ytd_pensionable: before.ytd_pensionable,
+            ..input.clone()
+        };
+        let T = payroll::calculate(ctx, &input)?.tax;
+        let TB = if B > 0.0 { payroll::bonus_method_tax(ctx, &input, B)? } else { 0.0 };
+
+        let periodic_is_used = match method {
+            Method::Auto(Preference::LessWithheld) => T + TB < cumulative.result.tax + cumulative.bonus_tax,
+            Method::Auto(Preference::MoreWithheld) => T + TB > cumulative.result.tax + cumulative.bonus_tax,
+            _ => true,
+        };
+        if !periodic_is_used {
+            return Ok(MethodPeriod { period: cumulative, method: Method::Cumulative });
+        }
+
+        let L = input.additional_tax;
+        self.accumulated_tax = utils::round(before.accumulated_tax + utils::floor_zero(T - L));
+        self.accumulated_bonus_tax = utils::round(before.accumulated_bonus_tax + TB);
+        let net = cumulative.result.net + cumulative.result.tax + cumulative.bonus_tax - T - TB;
+        Ok(MethodPeriod {
+            period: CumulativePeriod {
+                result: PayrollResult { tax: T, net: utils::round(net), ..cumulative.result },
+                bonus_tax: TB,
+            },
+            method: Method::Periodic,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures;
+
+    #[test]
+    fn test_accumulated_tax_is_running_sum_of_prior_tax() {
+        let (ctx, input) = test_fixtures::ontario_biweekly_employee();
+        let input = PayrollInput { additional_tax: 25.0, ..input };
+        let mut year = CumulativeAverage::new();
+        let mut prior_tax = 0.0;
+
+        for period in 0..6 {
+            assert_eq!(year.accumulated_tax, utils::round(prior_tax), "period {}", period);
+            let result = year.process(&ctx, &input, 0.0).unwrap().result;
+            prior_tax += result.tax - input.additional_tax;
+        }
+        assert_eq!(year.periods, 6);
+        assert_eq!(year.accumulated_tax, utils::round(prior_tax));
+        assert_eq!(year.accumulated_bonus_tax, 0.0);
+    }
+
+    #[test]
+    fn test_steady_pay_matches_option_1() {
+        let (ctx, input) = test_fixtures::ontario_biweekly_employee();
+        let periodic = payroll::calculate(&ctx, &input).unwrap();
+
+        let mut year = CumulativeAverage::new();
+        for _ in 0..4 {
+            let result = year.process(&ctx, &input, 0.0).unwrap().result;
+            assert_eq!(result.cpp, periodic.cpp);
+            assert_eq!(result.ei, periodic.ei);
+            assert!((result.tax - periodic.tax).abs() <= 0.01, "{} {}", result.tax, periodic.tax);
+        }
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_bonus_tax_accumulates_in_M1() {
+        let (ctx, input) = test_fixtures::ontario_biweekly_employee();
+        let mut year = CumulativeAverage::new();
+        year.process(&ctx, &input, 0.0).unwrap();
+
+        let with_bonus = year.process(&ctx, &input, 5000.0).unwrap();
+        assert!(with_bonus.bonus_tax > 0.0);
+        assert_eq!(year.accumulated_bonus_tax, with_bonus.bonus_tax);
+        assert_eq!(year.ytd_bonuses, 5000.0);
+
+        let after = year.process(&ctx, &input, 0.0).unwrap();
+        assert_eq!(after.bonus_tax, 0.0);
+        assert_eq!(year.accumulated_bonus_tax, with_bonus.bonus_tax);
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_PEYTD_accumulates_gross_and_benefits() {
+        let (ctx, input) = test_fixtures::ontario_biweekly_employee();
+        let input = PayrollInput { taxable_benefits: 150.0, non_insurable_benefits: 50.0, ..input };
+        let mut year = CumulativeAverage::new();
+
+        for period in 1..=4 {
+            let result = year.process(&ctx, &input, 0.0).unwrap().result;
+            assert_eq!(year.ytd_periodic_pensionable, period as f64 * (input.cash_income + 150.0));
+            assert_eq!(year.ytd_insurable, period as f64 * (input.cash_income + 100.0));
+
+            // K2 is annualized from the earnings to date, including this period
+            let S1 = basic_personal_income::S1(ctx.periods_per_year(), period);
+            let K2 = federal_income_tax::K2_grad(S1, year.ytd_periodic_pensionable, 0.0, year.ytd_insurable, ctx.year);
+            let overrides = FactorOverrides { k2: Some(K2), ..Default::default() };
+            let with_K2 = PayrollInput { overrides, ..input.clone() };
+            assert_eq!(result.federal_tax, payroll::federal_tax(&ctx, &with_K2, result.annual_taxable_income, result.cpp, result.ei));
+        }
+
+        // Non-periodic payments are only in PI_YTD
+        year.process(&ctx, &input, 2000.0).unwrap();
+        assert_eq!(year.ytd_periodic_pensionable, 5.0 * (input.cash_income + 150.0));
+        assert_eq!(year.ytd_pensionable, 5.0 * (input.cash_income + 150.0) + 2000.0);
+    }
+
+    #[test]
+    fn test_auto_method_uses_the_preferred_withholding() {
+        let (ctx, input) = test_fixtures::ontario_biweekly_employee();
+        let low = PayrollInput { cash_income: 1000.0, ..input.clone() };
+        let high = PayrollInput { cash_income: 5000.0, ..input };
+        let periodic_tax = payroll::calculate(&ctx, &high).unwrap().tax;
+
+        // After a low period, averaging a high one withholds less than annualizing it alone
+        let mut less = CumulativeAverage::new();
+        less.process_with_method(&ctx, &low, 0.0, Method::Cumulative).unwrap();
+        let mut cumulative_only = less.clone();
+        let cumulative = cumulative_only.process(&ctx, &high, 0.0).unwrap();
+        assert!(cumulative.result.tax < periodic_tax);
+
+        let chosen = less.process_with_method(&ctx, &high, 0.0, Method::Auto(Preference::LessWithheld)).unwrap();
+        assert_eq!(chosen.method, Method::Cumulative);
+        assert_eq!(chosen.period, cumulative);
+        assert_eq!(less, cumulative_only);
+
+        let mut more = CumulativeAverage::new();
+        more.process_with_method(&ctx, &low, 0.0, Method::Cumulative).unwrap();
+        let accumulated = more.accumulated_tax;
+        let chosen = more.process_with_method(&ctx, &high, 0.0, Method::Auto(Preference::MoreWithheld)).unwrap();
+        assert_eq!(chosen.method, Method::Periodic);
+        assert_eq!(chosen.period.result.tax, periodic_tax);
+        assert_eq!(chosen.period.result.cpp, cumulative.result.cpp);
+        assert_eq!(chosen.period.result.net, utils::round(cumulative.result.net + cumulative.result.tax - periodic_tax));
+        // M holds the tax actually withheld
+        assert_eq!(more.accumulated_tax, utils::round(accumulated + periodic_tax));
+    }
+}
@@ -0,0 +1,88 @@
+//! Payroll Calculation Errors
+
+use std::fmt;
+
+use crate::province::Province;
+
+#[derive(Debug, Clone, PartialEq)]
+#[allow(non_snake_case)]
+pub enum PayrollError {
+    /// There is no implementation for the provincial or territorial tax of this province.
+    UnsupportedProvince(Province),
+    /// An input was NaN or infinite; names the offending input.
+    NonFiniteInput(&'static str),
+    /// Province segments had negative months, or more than 12 months in total.
+    InvalidSegments,
+    /// The pay periods left in the year (PR) must be at least 1 and at most the pay periods in the year (P).
+    InvalidPeriodsRemaining { P: i64, PR: i64 },
+    /// There are no constants for this year.
+    UnsupportedYear(u16),
+    /// An amount that cannot be negative was; names the offending input.
+    NegativeInput(&'static str),
+    /// PM must be between 0 and 12, and at least 1 for an employee.
+    InvalidContributionMonths(i64),
+    /// The number of pay periods in the year must be positive.
+    InvalidPayPeriods(i64),
+    /// A tax rate was outside 0 to 25%, such as a percentage passed where a decimal was expected.
+    ImplausibleRate(f64),
+    /// TD1 claim codes are 0 to 10.
+    InvalidClaimCode(u8),
+}
+
+impl fmt::Display for PayrollError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PayrollError::UnsupportedProvince(province) => {
+                write!(f, "provincial tax is not implemented for {:?}", province)
+            }
+            PayrollError::NonFiniteInput(input) => write!(f, "{} is not a finite number", input),
+            PayrollError::InvalidSegments => write!(f, "province segments must cover at most 12 months"),
+            PayrollError::InvalidPeriodsRemaining { P, PR } => {
+                write!(f, "{} pay periods left is not between 1 and {}", PR, P)
+            }
+            PayrollError::UnsupportedYear(year) => write!(f, "{} is not a supported year", year),
+            PayrollError::NegativeInput(input) => write!(f, "{} cannot be negative", input),
+            PayrollError::InvalidContributionMonths(months) => write!(f, "{} is not a valid number of contribution months", months),
+            PayrollError::InvalidPayPeriods(periods) => write!(f, "{} is not a valid number of pay periods", periods),
+            PayrollError::ImplausibleRate(rate) => write!(f, "{} is not a plausible tax rate; rates are decimals, such as 0.0505", rate),
+            PayrollError::InvalidClaimCode(code) => write!(f, "{} is not a claim code from 0 to 10", code),
+        }
+    }
+}
+
+impl std::error::Error for PayrollError {}
+
+/// Errors loading year constants from a configuration file; see `year::config`.
+#[cfg(feature = "config")]
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConfigError {
+    /// The file could not be read or written.
+    Io(std::io::ErrorKind),
+    /// A line is neither `key = value` nor a `[section]` header.
+    Syntax { line: usize },
+    /// A key that is not a year constant, such as a misspelling, or a province not in `Province`.
+    UnknownKey { line: usize, key: String },
+    /// A value is not a number, or not an array of the right shape, for its key.
+    InvalidValue { line: usize, key: String },
+    /// A constant is missing from the file.
+    MissingKey(&'static str),
+    /// The file is for a year without compiled-in constants; the provincial and territorial tables are not in the file format.
+    UnsupportedYear(u16),
+}
+
+#[cfg(feature = "config")]
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Io(kind) => write!(f, "could not access the config file: {}", kind),
+            ConfigError::Syntax { line } => write!(f, "line {} is not `key = value` or a `[section]`", line),
+            ConfigError::UnknownKey { line, key } => write!(f, "line {}: {} is not a year constant", line, key),
+            ConfigError::InvalidValue { line, key } => write!(f, "line {}: the value of {} is not valid", line, key),
+            ConfigError::MissingKey(key) => write!(f, "{} is missing", key),
+            ConfigError::UnsupportedYear(year) => write!(f, "{} has no compiled-in provincial tables to load constants for", year),
+        }
+    }
+}
+
+#[cfg(feature = "config")]
+impl std::error::Error for ConfigError {}
@@ -1,12 +1,15 @@
 //! # Income Tax Calculations.
 //!
 
+use crate::error::PayrollError;
 use crate::utils;
-use crate::year::v2025;
+use crate::year::{self, v2025, YearConstants};
 
 /** Calculate Annual Deductions.
 *
-* If F1 amount is implemented after the first pay period of the year, it must be calculated.
+* If F1 amount is implemented after the first pay period of the year, it must be calculated. From the first pay period (PR equal to P), it is F1 unchanged.
+*
+* Returns `PayrollError::InvalidPeriodsRemaining` unless 0 < PR <= P.
 *
 *
 * Given:
@@ -18,8 +21,14 @@ use crate::year::v2025;
 *   PR: number of pay periods left in the year (including the current pay period)
 */
 #[allow(non_snake_case)]
-pub fn F1(P: i64, PR: i64, F1: f64) -> f64 {
-    utils::round((P as f64 * F1) / PR as f64)
+pub fn F1(P: i64, PR: i64, F1: f64) -> Result<f64, PayrollError> {
+    if PR <= 0 || PR > P {
+        return Err(PayrollError::InvalidPeriodsRemaining { P, PR });
+    }
+    if PR == P {
+        return Ok(F1);
+    }
+    Ok(utils::round((P as f64 * F1) / PR as f64))
 }
 
 /** Deductions for Canada Pension Plan additional contributions for the pay period.
@@ -33,13 +42,36 @@ pub fn F1(P: i64, PR: i64, F1: f64) -> f64 {
 *    C: Canada (or Quebec) Pension Plan contributions for the pay period
 *
 *   C2: Second additional Canada (or Quebec) Pension Plan contributions for the pay period
+*
+*   year: The constants for the year of the calculation, for the first additional rate's share of the CPP rate
+*/
+#[allow(non_snake_case)]
+pub fn F5(C: f64, C2: f64, year: &YearConstants) -> f64 {
+    if C == 0.0 && C2 == 0.0 {
+        return 0.0
+    }
+    let rate = year.cpp_base_rate + year.cpp_first_additional_rate;
+    utils::round(C * (year.cpp_first_additional_rate / rate) + C2)
+}
+
+/** Deductions for Quebec Pension Plan additional contributions for the pay period (F5Q)
+*
+*   As F5, with the first additional contributions the share of the QPP rate above the base QPP rate.
+*
+* Given:
+*
+*   C: Quebec Pension Plan contributions for the pay period
+*
+*   C2: Second additional Quebec Pension Plan contributions for the pay period
+*
+*   year: The constants for the year of the calculation
 */
 #[allow(non_snake_case)]
-pub fn F5(C: f64, C2: f64) -> f64 {
+pub fn F5Q(C: f64, C2: f64, year: &YearConstants) -> f64 {
     if C == 0.0 && C2 == 0.0 {
         return 0.0
     }
-    utils::round(C * (0.100/0.0595) + C2)
+    utils::round(C * ((year.qpp_rate - year.qpp_base_rate) / year.qpp_rate) + C2)
 }
 
 
@@ -93,11 +125,86 @@ pub fn F5A(F5: f64, PI: f64, B: f64) -> f64 {
 */
 #[allow(non_snake_case)]
 pub fn T3(R: f64, A: f64, K: f64, K1: f64, K2: f64, K3: f64, K4: f64) -> f64 {
-    let result: f64 = (R * A) - K - K1 - K2 - K3 - K4;
-    if result.is_sign_negative() {
-        return 0.0;
+    utils::round(utils::saturating_sub_credits(R * A, &[K, K1, K2, K3, K4]))
+}
+
+/** Federal non-refundable tax credits used in the calculation of T3
+*
+*   Build with `FederalCredits::default()` and the methods for each credit; a credit not set is zero.
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[allow(non_snake_case)]
+pub struct FederalCredits {
+    /// Federal non-refundable personal tax credit
+    pub K1: f64,
+    /// Base CPP contributions and EI premiums federal tax credits for the year
+    pub K2: f64,
+    /// Other federal non-refundable tax credits
+    pub K3: f64,
+    /// Federal non-refundable tax credit calculated using the Canada employment amount
+    pub K4: f64,
+}
+
+#[allow(non_snake_case)]
+impl FederalCredits {
+    /// Sets K1 from the total claim amount (TC) on Form TD1; see `K1`.
+    pub fn claim(self, TC: f64) -> Self {
+        FederalCredits { K1: K1(TC), ..self }
     }
-    utils::round(result)
+
+    /// Sets K1 from a total claim amount changed mid-year, with PR pay periods left when it took effect; see `K1_prorated`.
+    pub fn changed_claim(self, P: i64, PR: i64, TC_before: f64, TC: f64) -> Self {
+        FederalCredits { K1: K1_prorated(P, PR, TC_before, TC), ..self }
+    }
+
+    /// Sets K2 from the CPP contributions and EI premiums for the pay period, or each annual maximum once it is reached; see `K2`.
+    pub fn contributions(self, P: i64, PM: i64, C: f64, EI: f64, cpp_max_reached: bool, ei_max_reached: bool, year: &YearConstants) -> Self {
+        FederalCredits { K2: K2(P, PM, C, EI, cpp_max_reached, ei_max_reached, year), ..self }
+    }
+
+    /// Sets K2 from the QPP contributions, EI premiums and QPIP premiums for the pay period of an employee in Quebec; see `K2Q`.
+    pub fn quebec_contributions(
+        self,
+        P: i64,
+        PM: i64,
+        QC: f64,
+        EI: f64,
+        PPIP: f64,
+        qpp_max_reached: bool,
+        ei_max_reached: bool,
+        year: &YearConstants,
+    ) -> Self {
+        FederalCredits { K2: K2Q(P, PM, QC, EI, PPIP, qpp_max_reached, ei_max_reached, year), ..self }
+    }
+
+    /// Sets K3 to the other federal non-refundable tax credits authorized by a tax services office or tax centre.
+    pub fn other_credits(self, K3: f64) -> Self {
+        FederalCredits { K3, ..self }
+    }
+
+    /// Sets K4 from the annual taxable income and the Canada employment amount; see `K4`.
+    pub fn employment_amount(self, A: f64, CEA: f64) -> Self {
+        FederalCredits { K4: K4(A, CEA), ..self }
+    }
+}
+
+/** Annual basic federal tax, with the credits bundled
+*
+*   The same as T3 with each credit taken from `credits`.
+*
+* Given:
+*
+*   R: Federal tax rate that applies to the annual taxable income A
+*
+*   A: Annual taxable income
+*
+*   K: Federal constant
+*
+*   credits: Federal non-refundable tax credits K1 to K4
+*/
+#[allow(non_snake_case)]
+pub fn T3_credits(R: f64, A: f64, K: f64, credits: &FederalCredits) -> f64 {
+    T3(R, A, K, credits.K1, credits.K2, credits.K3, credits.K4)
 }
 
 /** Federal non-refundable personal tax credit (the lowest federal tax rate is used to calculate this credit)
@@ -109,7 +216,26 @@ pub fn T3(R: f64, A: f64, K: f64, K1: f64, K2: f64, K3: f64, K4: f64) -> f64 {
 */
 #[allow(non_snake_case)]
 pub fn K1(TC: f64) -> f64 {
-    0.15 * TC
+    v2025::LOWEST_FEDERAL_RATE * TC
+}
+
+/** Federal non-refundable personal tax credit after a mid-year change to the total claim amount
+*
+*   The change in the claim is prorated over the pay periods left in the year, so that the credit for the whole year reflects the new claim, as K3 is prorated. With PR equal to P, the same as K1.
+*
+* Given:
+*
+*   P: The number of pay periods in the year
+*
+*   PR: The number of pay periods left in the year (including the current pay period) when the new Form TD1 took effect
+*
+*   TC_before: Total claim amount on the federal Form TD1 before the change
+*
+*   TC: Total claim amount on the new federal Form TD1
+*/
+#[allow(non_snake_case)]
+pub fn K1_prorated(P: i64, PR: i64, TC_before: f64, TC: f64) -> f64 {
+    K1(TC_before + (TC - TC_before) * P as f64 / PR as f64)
 }
 
 /** Base Canada Pension Plan contributions and employment insurance premiums federal tax credits for the year
@@ -124,26 +250,70 @@ pub fn K1(TC: f64) -> f64 {
 *   C: Canada (or Quebec) Pension Plan contributions for the pay period
 *
 *   EI: Employment insurance premiums for the pay period
+*
+*   cpp_max_reached: The employee has contributed the maximum CPP for the year with the employer, in this or an earlier pay period. The CPP part of the credit for the rest of the year is then calculated on the maximum base CPP contribution, rather than on C annualized
+*
+*   ei_max_reached: The employee has paid the maximum EI premium for the year with the employer; the EI part of the credit is then on the maximum EI premium, rather than on EI annualized. An employee earning more than the maximum insurable earnings but less than the YMPE reaches the EI maximum first
+*
+*   year: The constants for the year of the calculation
 */
 #[allow(non_snake_case)]
-pub fn K2(P: i64, PM: i64, C: f64, mut EI: f64) -> f64 {
-
-    if EI > v2025::EI_MAX_CONTRIBUTIONS {
-        EI = v2025::EI_MAX_CONTRIBUTIONS;
-    }
-
-    let mut result = 0.15 * (P as f64 * C * (0.0495 / 0.0595));
-    //TODO: check if the `result` is anywhere near CPP_MAX_CONTRIBUTIONS; not sure if I've writen
-    //this correctly
-    if result > v2025::CPP_MAX_CONTRIBUTIONS {
-        result = v2025::CPP_MAX_CONTRIBUTIONS;
-    }
+pub fn K2(P: i64, PM: i64, C: f64, EI: f64, cpp_max_reached: bool, ei_max_reached: bool, year: &YearConstants) -> f64 {
+    let base_share = year.cpp_base_rate / (year.cpp_base_rate + year.cpp_first_additional_rate);
+    let cpp = if cpp_max_reached {
+        year.cpp_max_contributions
+    } else {
+        utils::clamp(P as f64 * C * base_share, 0.0, year.cpp_max_contributions)
+    };
+    let ei = if ei_max_reached { year.ei_max_contributions } else { utils::clamp(P as f64 * EI, 0.0, year.ei_max_contributions) };
 
-    result = (result * (PM/12) as f64) + (0.15 * (P as f64 * EI));
+    let rate = year.federal_brackets[0].rate;
+    let result = (rate * cpp * (PM as f64 / 12.0)) + (rate * ei);
 
     utils::round(result)
 }
 
+/** Base Quebec Pension Plan contributions, employment insurance premiums and Quebec Parental Insurance Plan premiums federal tax credits for the year (K2Q)
+*
+*   For employees working in Quebec (replaces K2). The base QPP contributions are the base rate's share of the QPP contributions, up to the maximum base QPP contribution prorated for PM, and the EI premiums are at the Quebec rate.
+*
+*
+* Given:
+*
+*   P: The number of pay periods in the year
+*
+*   PM: The total number of months during which CPP and/or QPP contributions are required to be deducted
+*
+*   QC: Quebec Pension Plan contributions for the pay period
+*
+*   EI: Employment insurance premiums for the pay period
+*
+*   PPIP: Quebec Parental Insurance Plan premiums for the pay period
+*
+*   qpp_max_reached: The employee has contributed the maximum QPP for the year with the employer; the QPP part of the credit is then on the maximum base QPP contribution, as for K2
+*
+*   ei_max_reached: The employee has paid the maximum EI premium for the year with the employer; the EI and QPIP parts of the credit are then on the Quebec EI and QPIP maximums, as for K2
+*
+*   year: The constants for the year of the calculation
+*/
+#[allow(non_snake_case)]
+pub fn K2Q(P: i64, PM: i64, QC: f64, EI: f64, PPIP: f64, qpp_max_reached: bool, ei_max_reached: bool, year: &YearConstants) -> f64 {
+    let base_share = year.qpp_base_rate / year.qpp_rate;
+    let qpp_max = year.qpp_max_contributions * base_share;
+    let qpp = if qpp_max_reached { qpp_max } else { utils::clamp(P as f64 * QC * base_share, 0.0, qpp_max) };
+    let (ei, qpip) = if ei_max_reached {
+        (year.ei_quebec_max_contributions, year.qpip_max_contributions)
+    } else {
+        (
+            utils::clamp(P as f64 * EI, 0.0, year.ei_quebec_max_contributions),
+            utils::clamp(P as f64 * PPIP, 0.0, year.qpip_max_contributions),
+        )
+    };
+
+    let rate = year.federal_brackets[0].rate;
+    utils::round(rate * qpp * (PM as f64 / 12.0) + rate * (ei + qpip))
+}
+
 /** Base Canada Pension Plan contributions and employment insurance premiums federal tax credits for the year
 *
 *   Using Cumulative Average Calculation
@@ -156,38 +326,16 @@ pub fn K2(P: i64, PM: i64, C: f64, mut EI: f64) -> f64 {
 *
 *   B1: Gross bonuses, retroactive pay increases, vacation pay when vacation is not taken, accumulated overtime payments or other non-periodic payments year-to-date (before the pay period)
 *
-*   C: Canada (or Quebec) Pension Plan contributions for the pay period
+*   IE: Insurable earnings for the pay period, including insurable taxable benefits for the pay period, plus IEYTD
 *
-*   EI: Insurable earnings for the pay period, including insurable taxable benefits for the pay period, plus IEYTD
+*   year: The constants for the year of the calculation
 */
 #[allow(non_snake_case)]
-pub fn K2_grad(S1: f64, PE: i64, B1: f64, EI: f64) -> f64 {
-    let mut cpp: f64;
-
-    cpp = (S1 * PE as f64) + B1 - 3500.0;
-    if cpp.is_sign_negative() {
-        cpp = 0.0;
-    }
-
-    if cpp > v2025::CPP_MAX_CONTRIBUTIONS {
-        cpp = v2025::CPP_MAX_CONTRIBUTIONS;
-    }
-
-    let mut result: f64;
+pub fn K2_grad(S1: f64, PE: f64, B1: f64, IE: f64, year: &YearConstants) -> f64 {
+    let cpp: f64 = utils::clamp(year.cpp_base_rate * ((S1 * PE) + B1 - year.cpp_basic_exemption), 0.0, year.cpp_max_contributions);
+    let ei: f64 = utils::clamp(year.ei_rate * ((S1 * IE) + B1), 0.0, year.ei_max_contributions);
 
-    result = 0.15 * 0.0495 * cpp;
-
-    let mut ei: f64;
-
-    ei = (S1 * EI) + B1;
-
-    if ei > v2025::EI_MAX_CONTRIBUTIONS {
-        ei = v2025::EI_MAX_CONTRIBUTIONS;
-    }
-
-    result += 0.15 * 0.0164 * ei;
-
-    utils::round(result)
+    utils::round(year.federal_brackets[0].rate * (cpp + ei))
 }
 
 /** Base Canada Pension Plan contributions and employment insurance premiums federal tax credits for the year
@@ -208,30 +356,73 @@ pub fn K2_grad(S1: f64, PE: i64, B1: f64, EI: f64) -> f64 {
 *   D1: Employee’s year-to-date (before the pay period) employment insurance premium with the employer
 *
 *   EI: Employment insurance premiums for the pay period
+*
+*   year: The constants for the year of the calculation
 */
 #[allow(non_snake_case)]
-pub fn K2_YTD(PM: i64, PR: i64, C: f64, D: f64, D1: f64, EI: f64) -> f64 {
-    let mut result: f64 = 0.15;
-    let cpp_ftc1: f64 = v2025::CPP_MAX_CONTRIBUTIONS * (PM/12) as f64;
-    let cpp_ftc2: f64 = (D * (0.0495/0.0595)) + (PR as f64 * C * (0.0495/0.0595));
+pub fn K2_YTD(PM: i64, PR: i64, C: f64, D: f64, D1: f64, EI: f64, year: &YearConstants) -> f64 {
+    let rate: f64 = year.federal_brackets[0].rate;
+    let base_share: f64 = year.cpp_base_rate / (year.cpp_base_rate + year.cpp_first_additional_rate);
+    let mut result: f64 = rate;
+    let cpp_ftc1: f64 = year.cpp_max_contributions * (PM as f64 / 12.0);
+    let cpp_ftc2: f64 = (D * base_share) + (PR as f64 * C * base_share);
     if cpp_ftc1 > cpp_ftc2 {
         result *= cpp_ftc2
     } else {
         result *= cpp_ftc1
     }
 
-    let ei_ftc: f64;
-    let y: f64 = D1 + (PR as f64 * EI);
-    if y > v2025::EI_MAX_CONTRIBUTIONS {
-        ei_ftc = v2025::EI_MAX_CONTRIBUTIONS;
-    } else {
-        ei_ftc = y;
-    }
+    let ei_ftc: f64 = utils::clamp(D1 + (PR as f64 * EI), 0.0, year.ei_max_contributions);
 
-    result += 0.15 * ei_ftc;
+    result += rate * ei_ftc;
     utils::round(result)
 }
 
+/** Base Canada Pension Plan contributions and employment insurance premiums federal tax credits for the year
+*
+*   For employees transferred from Quebec to a location outside Quebec (replaces K2)
+*
+*   Contributions made in Quebec earlier in the year are credited alongside the projected contributions for the rest of the year:
+*
+*   - the base portion of the QPP contributions (0.054 of the 0.0640 rate in 2025), plus the base portion of the CPP contributions for the remaining pay periods, up to the maximum base CPP contribution
+*
+*   - the EI premiums deducted at the Quebec rate, plus the EI premiums for the remaining pay periods, up to the maximum EI premium
+*
+*   - the QPIP premiums deducted in Quebec, up to the maximum QPIP premium
+*
+*   Each is credited at the lowest federal rate.
+*
+*
+* Given:
+*
+*   PR: The number of pay periods left in the year (including the current pay period)
+*
+*   C: Canada Pension Plan contributions for the pay period
+*
+*   EI: Employment insurance premiums for the pay period
+*
+*   DQ: Employee’s year-to-date Quebec Pension Plan contributions deducted while employed in Quebec
+*
+*   D1: Employee’s year-to-date employment insurance premiums, including those deducted at the Quebec rate
+*
+*   DQPIP: Employee’s year-to-date Quebec Parental Insurance Plan premiums
+*
+*   year: The constants for the year of the calculation
+*/
+#[allow(non_snake_case)]
+pub fn K2R(PR: i64, C: f64, EI: f64, DQ: f64, D1: f64, DQPIP: f64, year: &YearConstants) -> f64 {
+    let cpp_base_share: f64 = year.cpp_base_rate / (year.cpp_base_rate + year.cpp_first_additional_rate);
+    let pension: f64 = utils::clamp(
+        (DQ * (year.qpp_base_rate / year.qpp_rate)) + (PR as f64 * C * cpp_base_share),
+        0.0,
+        year.cpp_max_contributions,
+    );
+    let ei: f64 = utils::clamp(D1 + (PR as f64 * EI), 0.0, year.ei_max_contributions);
+    let qpip: f64 = utils::clamp(DQPIP, 0.0, year.qpip_max_contributions);
+
+    utils::round(year.federal_brackets[0].rate * (pension + ei + qpip))
+}
+
 /** Other federal non-refundable tax credits
 *
 *
@@ -248,6 +439,16 @@ pub fn K3(P: i64, PR: i64, K3: f64) -> f64 {
     (P as f64 * K3) / PR as f64
 }
 
+/** Canada employment amount (CEA) for the year
+*
+* Given:
+*
+*   year: The constants for the year of the calculation
+*/
+pub fn canada_employment_amount(year: &YearConstants) -> f64 {
+    year.canada_employment_amount
+}
+
 /** Federal non-refundable tax credit calculated using the Canada employment amount (the lowest federal tax rate is used to calculate this credit)
 *
 *
@@ -255,12 +456,12 @@ pub fn K3(P: i64, PR: i64, K3: f64) -> f64 {
 *
 *   A: Annual taxable income
 *
-*   CEA: Canada Employment Amount, a non-refundable tax credit used in the calculation for K4 and K4P
+*   CEA: Canada Employment Amount, a non-refundable tax credit used in the calculation for K4 and K4P; see `canada_employment_amount`
 */
 #[allow(non_snake_case)]
 pub fn K4(A: f64, CEA: f64) -> f64 {
-    let k41: f64 = 0.15 * A;
-    let k42: f64 = 0.15 * CEA;
+    let k41: f64 = v2025::LOWEST_FEDERAL_RATE * A;
+    let k42: f64 = v2025::LOWEST_FEDERAL_RATE * CEA;
     if k41 > k42 {
         return utils::round(k42);
     } else {
@@ -291,10 +492,24 @@ pub fn T1(T3: f64, P: i64, LCF: f64, is_outside_city_limits: bool) -> f64 {
         t1 = T3 - (P as f64 * LCF);
     }
 
-    if t1.is_sign_negative() {
-        return 0.0;
-    }
-    utils::round(t1)
+    utils::round(utils::floor_zero(t1))
+}
+
+/** Annual federal tax deduction for an employee in Quebec
+*
+*   Quebec administers its own programs, so federal tax is reduced by the refundable Quebec abatement: 16.5% of T3 (`v2025::QUEBEC_ABATEMENT_RATE`).
+*
+* Given:
+*
+*   T3: Annual basic federal tax
+*
+*   P: The number of pay periods in the year
+*
+*   LCF: Federal labour-sponsored funds tax credit
+*/
+#[allow(non_snake_case)]
+pub fn T1_quebec(T3: f64, P: i64, LCF: f64) -> f64 {
+    utils::round(utils::floor_zero(T3 - (P as f64 * LCF) - (v2025::QUEBEC_ABATEMENT_RATE * T3)))
 }
 
 /** Annual federal tax deduction
@@ -320,26 +535,383 @@ pub fn T1_grad(T3: f64, LCF: f64, is_outside_city_limits: bool) -> f64 {
         t1 = T3 - LCF;
     }
 
-    if t1.is_sign_negative() {
-        return 0.0;
-    }
-    utils::round(t1)
+    utils::round(utils::floor_zero(t1))
 }
 
 /** Federal labour-sponsored funds tax credit
 *
+*   The lesser of $750 and 15% of the amount deducted for the acquisition of shares in the year.
 *
 * Given:
 *
-*   acquisition_pay_loss: Fifteen percent of the amount deducted or withheld for the pay period for the acquisition, by the employee, of approved shares of the capital stock of a prescribed labour-sponsored venture capital corporation
+*   acquisition_pay_loss: The amount deducted or withheld during the year for the acquisition, by the employee, of approved shares of the capital stock of a prescribed labour-sponsored venture capital corporation
 */
 #[allow(non_snake_case)]
 pub fn LCF(acquisition_pay_loss: f64) -> f64 {
     let lcf: f64 = 0.15 * acquisition_pay_loss;
     if 750.0 > lcf {
-        return utils::round(acquisition_pay_loss);
+        return utils::round(lcf);
     } else {
         return 750.0;
     }
 }
 
+/** Federal labour-sponsored funds tax credit for the pay period
+*
+*   The annual credit (see `LCF`) on the pay period's acquisition annualized, divided by P, so that T1 (which deducts P × LCF) never credits more than the $750 annual maximum. Not rounded, as the rounding would be multiplied by P.
+*
+* Given:
+*
+*   P: The number of pay periods in the year
+*
+*   acquisition_pay_loss: The amount deducted or withheld for the pay period for the acquisition of approved labour-sponsored venture capital shares
+*/
+#[allow(non_snake_case)]
+pub fn LCF_period(P: i64, acquisition_pay_loss: f64) -> f64 {
+    LCF(P as f64 * acquisition_pay_loss) / P as f64
+}
+
+
+/** Marginal federal tax rate
+*
+*   The federal rate R that applies to the next dollar of annual taxable income.
+*
+*
+* Given:
+*
+*   A: Annual taxable income
+*
+*   year: The constants for the year of the calculation
+*/
+#[allow(non_snake_case)]
+pub fn marginal_rate(A: f64, year: &YearConstants) -> f64 {
+    year::bracket(year.federal_brackets, A).rate
+}
+
+/** Federal constant (K) for the annual taxable income
+*
+*   Computed from the year's rate schedule; matches the constant published with the federal rates.
+*
+*
+* Given:
+*
+*   A: Annual taxable income
+*
+*   year: The constants for the year of the calculation
+*/
+#[allow(non_snake_case)]
+pub fn federal_constant(A: f64, year: &YearConstants) -> f64 {
+    year::constant(year.federal_brackets, A)
+}
+
+/** Effective federal tax rate
+*
+*   The annual federal tax deduction as a share of annual taxable income; zero when there is no taxable income.
+*
+*
+* Given:
+*
+*   A: Annual taxable income
+*
+*   T1: Annual federal tax deduction
+*/
+#[allow(non_snake_case)]
+pub fn effective_rate(A: f64, T1: f64) -> f64 {
+    if A <= 0.0 {
+        return 0.0;
+    }
+    T1 / A
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::year::{v2023, v2024};
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_T3_when_credits_exceed_the_tax() {
+        // 15% of $14,000 is $2,100, less than the basic personal credit alone
+        assert_eq!(T3(0.15, 14000.0, 0.0, K1(16129.0), 0.0, 0.0, 0.0), 0.0);
+        assert_eq!(T3(0.15, 20000.0, 0.0, 2000.0, 500.0, 0.0, 0.0), 500.0);
+        assert_eq!(T3(0.15, 20000.0, 0.0, 2000.0, 500.0, 600.0, 300.0), 0.0);
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_T3_credits_matches_positional_call() {
+        let A = 65000.0;
+        let CEA = canada_employment_amount(&v2025::CONSTANTS);
+        let credits = FederalCredits::default()
+            .claim(16129.0)
+            .contributions(26, 12, 150.0, 40.0, false, false, &v2025::CONSTANTS)
+            .other_credits(250.0)
+            .employment_amount(A, CEA);
+
+        assert_eq!(credits.K1, K1(16129.0));
+        assert_eq!(credits.K2, K2(26, 12, 150.0, 40.0, false, false, &v2025::CONSTANTS));
+        assert_eq!(credits.K3, 250.0);
+        assert_eq!(credits.K4, K4(A, CEA));
+        assert_eq!(
+            T3_credits(0.205, A, 3156.0, &credits),
+            T3(0.205, A, 3156.0, K1(16129.0), K2(26, 12, 150.0, 40.0, false, false, &v2025::CONSTANTS), 250.0, K4(A, CEA))
+        );
+
+        let without_k3 = FederalCredits { K3: 0.0, ..credits };
+        assert_eq!(T3_credits(0.205, A, 3156.0, &without_k3) - T3_credits(0.205, A, 3156.0, &credits), 250.0);
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_K1_prorated_over_the_periods_left() {
+        assert_eq!(K1_prorated(26, 26, 10000.0, 15000.0), K1(15000.0));
+        assert_eq!(K1_prorated(26, 13, 10000.0, 15000.0), K1(20000.0));
+        assert_eq!(K1_prorated(26, 13, 15000.0, 15000.0), K1(15000.0));
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_credits_use_the_lowest_federal_rate() {
+        assert_eq!(v2025::LOWEST_FEDERAL_RATE, v2025::FEDERAL_BRACKETS[0].rate);
+        assert_eq!(K1(10000.0), v2025::LOWEST_FEDERAL_RATE * 10000.0);
+        assert_eq!(K4(100000.0, 1471.0), utils::round(v2025::LOWEST_FEDERAL_RATE * 1471.0));
+        assert_eq!(K2(12, 12, 0.0, 50.0, false, false, &v2025::CONSTANTS), utils::round(v2025::LOWEST_FEDERAL_RATE * 600.0));
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_LCF_is_fifteen_percent_up_to_the_maximum() {
+        assert_eq!(LCF(2000.0), 300.0);
+        assert_eq!(LCF(5000.0), 750.0);
+        assert_eq!(LCF(10000.0), 750.0);
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_LCF_period_respects_the_annual_maximum() {
+        // $500 a month is $6,000 a year; 15% would be $900
+        assert_eq!(LCF_period(12, 500.0), 62.5);
+        assert_eq!(T1(5000.0, 12, LCF_period(12, 500.0), false), 4250.0);
+
+        // Under the maximum, the credit is 15% of each acquisition
+        assert_eq!(T1(5000.0, 12, LCF_period(12, 200.0), false), 4640.0);
+
+        // Biweekly: 26 × (750 / 26) is still $750
+        assert_eq!(T1(5000.0, 26, LCF_period(26, 500.0), false), 4250.0);
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_F1_from_first_pay_period_is_not_prorated() {
+        assert_eq!(F1(26, 26, 1000.0), Ok(1000.0));
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_F1_mid_year_is_prorated() {
+        assert_eq!(F1(26, 13, 1000.0), Ok(2000.0));
+        assert_eq!(F1(12, 5, 1000.0), Ok(2400.0));
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_F1_rejects_invalid_periods_remaining() {
+        assert_eq!(F1(26, 0, 1000.0), Err(PayrollError::InvalidPeriodsRemaining { P: 26, PR: 0 }));
+        assert_eq!(F1(26, 27, 1000.0), Err(PayrollError::InvalidPeriodsRemaining { P: 26, PR: 27 }));
+        assert_eq!(F1(26, -1, 1000.0), Err(PayrollError::InvalidPeriodsRemaining { P: 26, PR: -1 }));
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_K2_once_the_maximums_are_reached() {
+        // Past the maximums C and EI are zero, but the credit is still on the full annual maximums
+        let maximum = utils::round(0.15 * (v2025::CPP_MAX_CONTRIBUTIONS + v2025::EI_MAX_CONTRIBUTIONS));
+        assert_eq!(K2(26, 12, 0.0, 0.0, false, false, &v2025::CONSTANTS), 0.0);
+        assert_eq!(K2(26, 12, 0.0, 0.0, true, true, &v2025::CONSTANTS), maximum);
+        assert_eq!(K2(26, 12, 50.0, 10.0, true, true, &v2025::CONSTANTS), maximum);
+        assert_eq!(K2(26, 6, 0.0, 0.0, true, true, &v2025::CONSTANTS), utils::round(0.15 * (v2025::CPP_MAX_CONTRIBUTIONS / 2.0 + v2025::EI_MAX_CONTRIBUTIONS)));
+
+        // Above the MIE but below the YMPE, only the EI maximum is reached
+        let cpp = 26.0 * 150.0 * (0.0495 / 0.0595);
+        assert_eq!(K2(26, 12, 150.0, 0.0, false, true, &v2025::CONSTANTS), utils::round(0.15 * (cpp + v2025::EI_MAX_CONTRIBUTIONS)));
+        assert_eq!(K2(26, 12, 0.0, 40.0, true, false, &v2025::CONSTANTS), utils::round(0.15 * (v2025::CPP_MAX_CONTRIBUTIONS + 26.0 * 40.0)));
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_K2_uses_the_maximums_of_the_year() {
+        let v2024 = &crate::year::v2024::CONSTANTS;
+        let v2023 = &crate::year::v2023::CONSTANTS;
+        assert_eq!(K2(26, 12, 0.0, 0.0, true, true, v2024), utils::round(0.15 * (3217.5 + 1049.12)));
+        assert_eq!(K2(26, 12, 0.0, 0.0, true, true, v2023), utils::round(0.15 * 3123.45 + 0.15 * 1002.45));
+        assert_eq!(K2(26, 12, 400.0, 100.0, false, false, v2024), K2(26, 12, 0.0, 0.0, true, true, v2024));
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_K2_caps_contributions_at_annual_maximums() {
+        let maximum = utils::round(0.15 * (v2025::CPP_MAX_CONTRIBUTIONS + v2025::EI_MAX_CONTRIBUTIONS));
+        for P in [13, 22, 26] {
+            assert_eq!(K2(P, 12, 400.0, 100.0, false, false, &v2025::CONSTANTS), maximum, "P = {}", P);
+        }
+        // Below the maximums, P times the contributions for the pay period
+        assert_eq!(K2(13, 12, 200.0, 50.0, false, false, &v2025::CONSTANTS), utils::round(0.15 * (13.0 * 200.0 * (0.0495 / 0.0595) + 13.0 * 50.0)));
+        assert_eq!(K2(22, 12, 150.0, 40.0, false, false, &v2025::CONSTANTS), utils::round(0.15 * (22.0 * 150.0 * (0.0495 / 0.0595) + 22.0 * 40.0)));
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_K4_uses_the_years_CEA() {
+        assert_eq!(canada_employment_amount(&v2025::CONSTANTS), 1471.0);
+        assert_eq!(canada_employment_amount(&v2024::CONSTANTS), 1433.0);
+
+        // Capped at 15% of the CEA once A exceeds it
+        assert_eq!(K4(60000.0, canada_employment_amount(&v2025::CONSTANTS)), 220.65);
+        assert_eq!(K4(60000.0, canada_employment_amount(&v2024::CONSTANTS)), 214.95);
+        assert_eq!(K4(1000.0, canada_employment_amount(&v2025::CONSTANTS)), 150.0);
+    }
+
+    #[test]
+    fn test_federal_constant_matches_published_constants() {
+        for year in [&v2023::CONSTANTS, &v2024::CONSTANTS, &v2025::CONSTANTS] {
+            for b in year.federal_brackets {
+                assert_eq!(federal_constant(b.threshold + 1.0, year), b.constant, "{} {}", year.year, b.threshold);
+            }
+        }
+        assert_eq!(federal_constant(0.0, &v2025::CONSTANTS), 0.0);
+        assert_eq!(federal_constant(114750.0, &v2025::CONSTANTS), 3156.0);
+        assert_eq!(federal_constant(114750.01, &v2025::CONSTANTS), 9467.0);
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_K2R_six_months_qpp_six_months_cpp() {
+        // $5,000 a month: six months in Quebec, then six months in Ontario.
+        let DQ = 6.0 * utils::round(v2025::QPP_RATE * (5000.0 - 3500.0 / 12.0));
+        let D1 = 6.0 * utils::round(0.0131 * 5000.0);
+        let DQPIP = 6.0 * utils::round(v2025::QPIP_RATE * 5000.0);
+        let C = utils::round(0.0595 * (5000.0 - 3500.0 / 12.0));
+        let EI = utils::round(0.0164 * 5000.0);
+
+        let expected = 0.15 * (DQ * (0.054 / 0.0640) + 6.0 * C * (0.0495 / 0.0595))
+            + 0.15 * (D1 + 6.0 * EI)
+            + 0.15 * DQPIP;
+        assert_eq!(K2R(6, C, EI, DQ, D1, DQPIP, &v2025::CONSTANTS), utils::round(expected));
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_credits_and_tax_are_clamped() {
+        assert_eq!(K2(26, 12, 129.3, 37.85, false, false, &v2025::CONSTANTS), 567.13);
+        assert_eq!(K2_grad(13.0, 20000.0, 5000.0, 20000.0, &v2025::CONSTANTS), 665.04);
+        // 15% × (4.95% × (2 × 20,000 − 3,500) + 1.64% × 2 × 20,000)
+        assert_eq!(K2_grad(2.0, 20000.0, 0.0, 20000.0, &v2025::CONSTANTS), 369.41);
+        assert_eq!(K2_YTD(12, 10, 129.3, 200.0, 50.0, 37.85, &v2025::CONSTANTS), 250.59);
+        assert_eq!(T3(0.205, 60000.0, 3156.0, 2419.35, 500.0, 0.0, 220.65), 6004.0);
+        assert_eq!(T3(0.15, 10000.0, 0.0, 2419.35, 0.0, 0.0, 0.0), 0.0);
+        assert_eq!(T1(5000.0, 26, 10.0, true), 7140.0);
+        assert_eq!(T1(100.0, 26, 10.0, false), 0.0);
+        assert_eq!(T1_quebec(5000.0, 26, 10.0), 3915.0);
+        assert_eq!(T1_quebec(100.0, 26, 10.0), 0.0);
+    }
+
+    #[test]
+    fn test_marginal_rate_jumps_at_each_bracket() {
+        let year = &v2025::CONSTANTS;
+        for pair in year.federal_brackets.windows(2) {
+            assert_eq!(marginal_rate(pair[1].threshold, year), pair[0].rate);
+            assert_eq!(marginal_rate(pair[1].threshold + 0.01, year), pair[1].rate);
+        }
+        assert_eq!(marginal_rate(0.0, year), 0.15);
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_effective_rate_below_marginal_rate() {
+        let year = &v2025::CONSTANTS;
+        let A = 150000.0;
+        let b = year::bracket(year.federal_brackets, A);
+        let T1 = T1(T3(b.rate, A, b.constant, K1(16129.0), 0.0, 0.0, 0.0), 26, 0.0, false);
+
+        assert!(effective_rate(A, T1) < marginal_rate(A, year));
+        assert!(effective_rate(A, T1) > 0.15);
+        assert_eq!(effective_rate(0.0, 0.0), 0.0);
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_K2R_caps_at_annual_maximums() {
+        let result = K2R(6, 1000.0, 500.0, 4000.0, 900.0, 1000.0, &v2025::CONSTANTS);
+        let expected = 0.15 * (v2025::CPP_MAX_CONTRIBUTIONS + v2025::EI_MAX_CONTRIBUTIONS + v2025::QPIP_MAX_CONTRIBUTIONS);
+        assert_eq!(result, utils::round(expected));
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_K2Q_credits_the_base_QPP_EI_and_QPIP() {
+        let year = &v2025::CONSTANTS;
+        let expected = 0.15 * (26.0 * 139.08 * (0.054 / 0.064)) + 0.15 * (26.0 * 30.23) + 0.15 * (26.0 * 11.4);
+        assert_eq!(K2Q(26, 12, 139.08, 30.23, 11.4, false, false, year), utils::round(expected));
+
+        // At the maximums: 0.054 × (71,300 − 3,500), and the Quebec EI and QPIP maximums
+        let expected = 0.15 * 3661.2 + 0.15 * (v2025::EI_QUEBEC_MAX_CONTRIBUTIONS + v2025::QPIP_MAX_CONTRIBUTIONS);
+        assert_eq!(K2Q(26, 12, 139.08, 30.23, 11.4, true, true, year), utils::round(expected));
+        assert_eq!(K2Q(26, 12, 1000.0, 100.0, 100.0, false, false, year), utils::round(expected));
+
+        // Only the EI maximum reached: the QPP part is still on this pay period's contributions
+        let expected = 0.15 * (26.0 * 139.08 * (0.054 / 0.064)) + 0.15 * (v2025::EI_QUEBEC_MAX_CONTRIBUTIONS + v2025::QPIP_MAX_CONTRIBUTIONS);
+        assert_eq!(K2Q(26, 12, 139.08, 0.0, 0.0, false, true, year), utils::round(expected));
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_F5Q_is_the_first_additional_share_of_QPP() {
+        // 1.00% of the 6.40% QPP rate
+        assert_eq!(F5Q(139.08, 0.0, &v2025::CONSTANTS), 21.73);
+        assert_eq!(F5Q(0.0, 0.0, &v2025::CONSTANTS), 0.0);
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_F5_is_the_first_additional_share_of_C() {
+        // 1.00% of the 5.95% contribution rate, not 10%
+        assert_eq!(F5(129.3, 0.0, &v2025::CONSTANTS), 21.73);
+        assert_eq!(F5(129.3, 5.0, &v2025::CONSTANTS), 26.73);
+        assert_eq!(F5(0.0, 0.0, &v2025::CONSTANTS), 0.0);
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_F5_for_2024() {
+        // The same 1.00% of 5.95% in 2024, from the year's rates
+        assert_eq!(F5(129.3, 5.0, &v2024::CONSTANTS), 26.73);
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_K2_grad_for_2024() {
+        // 15% × (4.95% × (2 × 20,000 − 3,500) + 1.66% × 2 × 20,000)
+        assert_eq!(K2_grad(2.0, 20000.0, 0.0, 20000.0, &v2024::CONSTANTS), utils::round(0.15 * (1806.75 + 664.0)));
+        // Capped at the 2024 maximum base CPP contribution and EI premium
+        assert_eq!(K2_grad(26.0, 5000.0, 0.0, 5000.0, &v2024::CONSTANTS), utils::round(0.15 * (3217.5 + 1049.12)));
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_K2_YTD_for_2024() {
+        // Prorated 2024 maximum base CPP contribution, and the 2024 maximum EI premium
+        let expected = 0.15 * 3217.5 * (6.0 / 12.0) + 0.15 * 1049.12;
+        assert_eq!(K2_YTD(6, 10, 500.0, 1000.0, 1000.0, 100.0, &v2024::CONSTANTS), utils::round(expected));
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_K2R_for_2024() {
+        let result = K2R(6, 1000.0, 500.0, 4000.0, 900.0, 1000.0, &v2024::CONSTANTS);
+        assert_eq!(result, utils::round(0.15 * (3217.5 + 1049.12 + 464.36)));
+
+        let expected = 0.15 * (2000.0 * (0.054 / 0.064) + 6.0 * 100.0 * (0.0495 / 0.0595)) + 0.15 * (300.0 + 6.0 * 30.0) + 0.15 * 200.0;
+        assert_eq!(K2R(6, 100.0, 30.0, 2000.0, 300.0, 200.0, &v2024::CONSTANTS), utils::round(expected));
+    }
+}
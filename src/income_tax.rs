@@ -1,6 +1,20 @@
 //! Formulas to calculate the estimated federal and provincial or territorial tax deductions (T) for the pay period
 
+use crate::error::PayrollError;
+use crate::province::Province;
 use crate::utils;
+use crate::year::{self, v2025};
+
+/** When the tax for the pay period is rounded to the cent.
+*
+*   CRA rounds the tax for the pay period once, after the annual federal and provincial tax have been divided by P and L added (`AnnualOnly`). Some reference implementations round the federal and provincial tax for the pay period separately before adding them (`EachFactor`), which can differ by a cent.
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RoundingStage {
+    #[default]
+    AnnualOnly,
+    EachFactor,
+}
 
 /** Estimated federal and provincial or territorial tax deductions for the pay period
 *       (Non-Commissionable earnings)
@@ -21,6 +35,54 @@ pub fn T(T1: f64, T2: f64, P: i64, L: f64) -> f64 {
     utils::round(((T1 + T2) / P as f64) + L)
 }
 
+/** Estimated federal and provincial or territorial tax deductions for the pay period, rounded at the given stage
+*       (Non-Commissionable earnings)
+*
+*   With `RoundingStage::AnnualOnly`, the same as T.
+*
+*
+* Given:
+*
+*   T1: Annual federal tax deduction
+*
+*   T2: Annual provincial or territorial tax deduction (except Quebec)
+*
+*   P: The number of pay periods in the year
+*
+*   L: Additional tax deductions for the pay period requested by the employee or pensioner as shown on Form TD1
+*
+*   rounding: When the tax for the pay period is rounded
+*/
+#[allow(non_snake_case)]
+pub fn T_rounded(T1: f64, T2: f64, P: i64, L: f64, rounding: RoundingStage) -> f64 {
+    match rounding {
+        RoundingStage::AnnualOnly => T(T1, T2, P, L),
+        RoundingStage::EachFactor => {
+            utils::round(utils::round(T1 / P as f64) + utils::round(T2 / P as f64) + utils::round(L))
+        }
+    }
+}
+
+/** Difference between the tax for the pay period rounded at each factor and rounded once (T)
+*
+*   T1 and T2 are annual amounts in cents, so T1 / P and T2 / P are each at most half a cent from their rounded values; with L in cents, the two ways of rounding differ by at most a cent.
+*
+*
+* Given:
+*
+*   T1: Annual federal tax deduction
+*
+*   T2: Annual provincial or territorial tax deduction (except Quebec)
+*
+*   P: The number of pay periods in the year
+*
+*   L: Additional tax deductions for the pay period requested by the employee or pensioner as shown on Form TD1
+*/
+#[allow(non_snake_case)]
+pub fn rounding_difference(T1: f64, T2: f64, P: i64, L: f64) -> f64 {
+    utils::round(T_rounded(T1, T2, P, L, RoundingStage::EachFactor) - T(T1, T2, P, L))
+}
+
 /** Estimated Federal and Provincial or Territorial Tax Deductions for the Pay Period
 *
 *   Uses Cumulative Average Calculation
@@ -63,3 +125,111 @@ pub fn T_grad(T1_grad: f64, T2: f64, M1: f64, S1: f64, M: f64, L: f64) -> f64 {
     utils::round(t + L)
 }
 
+/** Additional tax to deduct in each remaining pay period to recover an annual under-deduction
+*
+*   Added to L for the rest of the year, rounded to the cent.
+*
+*
+* Given:
+*
+*   annual_shortfall: Tax for the year that was not deducted in the earlier pay periods
+*
+*   periods_remaining: Number of pay periods left in the year to recover the shortfall over
+*/
+pub fn spread_over_remaining(annual_shortfall: f64, periods_remaining: i64) -> Result<f64, PayrollError> {
+    if periods_remaining <= 0 {
+        return Err(PayrollError::InvalidPayPeriods(periods_remaining));
+    }
+    Ok(utils::round(annual_shortfall / periods_remaining as f64))
+}
+
+/** Flat-rate withholding on a lump-sum payment, such as a retiring allowance or an RRSP withdrawal
+*
+*   The whole payment is withheld at the rate of its tier, rather than annualized with T: 10% up to and including $5,000, 20% up to and including $15,000 and 30% above. In Quebec the federal rates are 5%, 10% and 15%, and Quebec tax is withheld separately.
+*
+*
+* Given:
+*
+*   amount: The lump-sum payment
+*
+*   province: Province or territory of employment
+*/
+pub fn lump_sum_withholding(amount: f64, province: Province) -> f64 {
+    let tiers: &[year::Bracket] = match province {
+        Province::Quebec => &v2025::QUEBEC_LUMP_SUM_TIERS,
+        _ => &v2025::LUMP_SUM_TIERS,
+    };
+    utils::round(utils::floor_zero(amount) * year::bracket(tiers, amount).rate)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_T_rounded_annual_only_is_T() {
+        assert_eq!(T_rounded(4500.0, 2100.0, 26, 0.0, RoundingStage::AnnualOnly), T(4500.0, 2100.0, 26, 0.0));
+        assert_eq!(T_rounded(4500.0, 2100.0, 26, 0.0, RoundingStage::default()), 253.85);
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_T_rounded_stages_can_differ_by_a_cent() {
+        // 100 / 26 = 3.846, so each factor rounds up while the total rounds down
+        assert_eq!(T_rounded(100.0, 100.0, 26, 0.0, RoundingStage::AnnualOnly), 7.69);
+        assert_eq!(T_rounded(100.0, 100.0, 26, 0.0, RoundingStage::EachFactor), 7.70);
+
+        // Same when the factors divide evenly
+        assert_eq!(T_rounded(2600.0, 1300.0, 26, 10.0, RoundingStage::EachFactor), 160.0);
+        assert_eq!(T_rounded(2600.0, 1300.0, 26, 10.0, RoundingStage::AnnualOnly), 160.0);
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_rounding_difference_is_at_most_a_cent() {
+        assert_eq!(rounding_difference(100.0, 100.0, 26, 0.0), 0.01);
+        assert_eq!(rounding_difference(2600.0, 1300.0, 26, 10.0), 0.0);
+
+        for cents in (0..2_000_000).step_by(977) {
+            let annual = cents as f64 / 100.0;
+            for P in [12, 22, 24, 26, 52] {
+                let difference = rounding_difference(annual, annual * 0.37, P, 12.34);
+                assert!(difference.abs() <= 0.01, "{} over {} differs by {}", annual, P, difference);
+            }
+        }
+    }
+
+    #[test]
+    fn test_spread_over_remaining_three_periods() {
+        assert_eq!(spread_over_remaining(300.0, 3), Ok(100.0));
+        assert_eq!(spread_over_remaining(100.0, 3), Ok(33.33));
+
+        let catch_up = spread_over_remaining(450.0, 3).unwrap();
+        assert_eq!(T(2600.0, 1300.0, 26, catch_up), 300.0);
+    }
+
+    #[test]
+    fn test_spread_over_remaining_needs_a_period() {
+        assert_eq!(spread_over_remaining(300.0, 0), Err(PayrollError::InvalidPayPeriods(0)));
+        assert_eq!(spread_over_remaining(300.0, -2), Err(PayrollError::InvalidPayPeriods(-2)));
+    }
+
+    #[test]
+    fn test_lump_sum_withholding_in_each_tier() {
+        assert_eq!(lump_sum_withholding(1000.0, Province::Ontario), 100.0);
+        assert_eq!(lump_sum_withholding(5000.0, Province::Ontario), 500.0);
+        assert_eq!(lump_sum_withholding(5000.01, Province::Alberta), 1000.0);
+        assert_eq!(lump_sum_withholding(15000.0, Province::Alberta), 3000.0);
+        assert_eq!(lump_sum_withholding(20000.0, Province::Ontario), 6000.0);
+        assert_eq!(lump_sum_withholding(0.0, Province::Ontario), 0.0);
+    }
+
+    #[test]
+    fn test_lump_sum_withholding_in_quebec() {
+        assert_eq!(lump_sum_withholding(1000.0, Province::Quebec), 50.0);
+        assert_eq!(lump_sum_withholding(5000.0, Province::Quebec), 250.0);
+        assert_eq!(lump_sum_withholding(10000.0, Province::Quebec), 1000.0);
+        assert_eq!(lump_sum_withholding(20000.0, Province::Quebec), 3000.0);
+    }
+}
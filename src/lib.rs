@@ -1,7 +1,28 @@
+// The factor formulas mirror the CRA's T4127 layout: one parameter per factor, with explicit
+// returns for each branch of the formula.
+#![allow(
+    clippy::too_many_arguments,
+    clippy::needless_return,
+    clippy::needless_late_init,
+    clippy::suspicious_else_formatting,
+    clippy::module_inception
+)]
+
 pub mod basic_personal_income;
+pub mod commission;
+pub mod cumulative_average;
 pub mod federal_income_tax;
 pub mod utils;
 pub mod provincial_income_tax;
 pub mod income_tax;
 pub mod year;
 pub mod other_deductions;
+pub mod error;
+pub mod pay_calendar;
+pub mod pay_period;
+pub mod payroll;
+pub mod province;
+pub mod quebec;
+pub mod t4_slip;
+#[cfg(any(test, feature = "testing"))]
+pub mod test_fixtures;
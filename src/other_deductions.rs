@@ -1,15 +1,60 @@
 //! Canadian Pension Plan and Employee Insurance Deductions
 
+use crate::province::Province;
 use crate::utils;
-use crate::year::v2025;
+use crate::year::YearConstants;
 
 //
 // Canada Pension Plan Calculations:
 //
 
+/// The CPP contribution rates for a year, as employee rates; the employer contributes the same.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CppRates {
+    /// Base rate, on pensionable earnings above the basic exemption up to the YMPE
+    pub base: f64,
+    /// First additional rate, on the same earnings as the base rate
+    pub first_additional: f64,
+    /// Rate deducted as C: the base plus the first additional rate
+    pub employee: f64,
+    /// Second additional rate, deducted as C2 on pensionable earnings between the YMPE and the YAMPE
+    pub second_additional: f64,
+}
+
+/** The CPP contribution rates for a year, by component
+*
+* Given:
+*
+*   year: The constants for the year
+*/
+pub fn cpp_rate_components(year: &YearConstants) -> CppRates {
+    CppRates {
+        base: year.cpp_base_rate,
+        first_additional: year.cpp_first_additional_rate,
+        employee: year.cpp_base_rate + year.cpp_first_additional_rate,
+        second_additional: year.cpp_second_additional_rate,
+    }
+}
+
+/** Basic CPP exemption for the pay period
+*
+*   The annual basic exemption spread evenly over the pay periods in the year. For a partial year, the exemption is only applied in the pay periods in which contributions are required, so the exemption claimed over the year is prorated in the same way as the maximum contribution (PM / 12).
+*
+* Given:
+*
+*   P: The number of pay periods in the year
+*
+*   year: The constants for the year of the calculation
+*/
+#[allow(non_snake_case)]
+pub fn cpp_basic_exemption(P: i64, year: &YearConstants) -> f64 {
+    year.cpp_basic_exemption / P as f64
+}
 
 /** Canada (or Quebec) Pension Plan contributions for the pay period (Non-Commissionable Earnings)
 *
+*   The lesser of the prorated annual maximum less D (the remaining room), and the contribution on the pay period's pensionable earnings, as in T4127: C = lesser of (maximum × PM / 12 − D) and (rate × (PI − basic exemption / P)). A low earner far from the maximum pays only the contribution on their earnings. The prorated maximum is rounded to the cent, so D plus the contribution never exceeds it. When the remaining room equals the contribution on the earnings, the lesser of the two is that amount, and the pay period deducts exactly the last of the room.
+*
 * Given:
 *
 *   PM: The total number of months during which CPP and/or QPP contributions are required to be deducted (used in the proration of maximum contribution).
@@ -19,20 +64,45 @@ use crate::year::v2025;
 *   PI: Pensionable earnings for the pay period, or the gross income plus any taxable benefits for the pay period, including bonuses and retroactive pay increases where applicable
 *
 *   P: The number of pay periods in the year
+*
+*   year: The constants for the year of the calculation, for the contribution rate, basic exemption and maximum contribution
 */
 #[allow(non_snake_case)]
-pub fn C(PM: i64, D: f64, PI: f64, P: i64) -> f64 {
-    let c1: f64 = 4034.1 * (PM/12) as f64 - D;
-    let c2: f64 = 0.0595 * (PI - (3500.0 / P as f64));
-    if c1 < c2 {
-        return utils::round(c2);
-    } else {
-        return utils::round(c1);
-    }
+pub fn C(PM: i64, D: f64, PI: f64, P: i64, year: &YearConstants) -> f64 {
+    let rate: f64 = year.cpp_base_rate + year.cpp_first_additional_rate;
+    let c1: f64 = utils::round(year.cpp_total_max_contributions * (PM as f64 / 12.0)) - D;
+    let c2: f64 = rate * (PI - cpp_basic_exemption(P, year));
+    let c: f64 = if c1 <= c2 { c1 } else { c2 };
+    utils::round(utils::floor_zero(c))
+}
+
+/** Canada Pension Plan contributions for the pay period, split into the base and first additional contributions
+*
+*   Returns (base, first_additional), which add up to C. The base is C at the base rate's share of the total rate (0.0495 / 0.0595), rounded to the cent; the first additional contribution is the rest.
+*
+* Given:
+*
+*   PM: The total number of months during which CPP and/or QPP contributions are required to be deducted (used in the proration of maximum contribution).
+*
+*   D: Employee’s year-to-date (before the pay period) Canada Pension Plan contribution with the employer
+*
+*   PI: Pensionable earnings for the pay period
+*
+*   P: The number of pay periods in the year
+*
+*   year: The constants for the year of the calculation
+*/
+#[allow(non_snake_case)]
+pub fn cpp_breakdown(PM: i64, D: f64, PI: f64, P: i64, year: &YearConstants) -> (f64, f64) {
+    let c: f64 = C(PM, D, PI, P, year);
+    let base: f64 = utils::round(c * (year.cpp_base_rate / (year.cpp_base_rate + year.cpp_first_additional_rate)));
+    (base, utils::round(c - base))
 }
 
 /** Second additional Canada (or Quebec) Pension Plan contributions for the pay period
 *
+*   The lesser of the prorated annual maximum less D2 (the remaining room), and the contribution on the pay period's earnings above W; never negative. The annual maximum is the second additional rate on the earnings between the YMPE and the YAMPE, so there is none before 2024.
+*
 * Given:
 *
 *   PM: The total number of months during which CPP and/or QPP contributions are required to be deducted (used in the proration of maximum contribution).
@@ -44,22 +114,32 @@ pub fn C(PM: i64, D: f64, PI: f64, P: i64) -> f64 {
 *   PI: Pensionable earnings for the pay period, or the gross income plus any taxable benefits for the pay period, including bonuses and retroactive pay increases where applicable
 *
 *   W: The greater of year-to-date (before the pay period) pensionable earnings (PIYTD or GYTD) and employee’s Year’s Maximum Pensionable Earnings (YMPE).
+*
+*   year: The constants for the year of the calculation
 */
 #[allow(non_snake_case)]
-pub fn C2(PM: i64, D2: f64, PI_YTD: f64, PI: f64, W: f64) -> f64 {
-    let c21: f64 = 396.0 * (PM/12) as f64 - D2;
-    let c22: f64 = (PI_YTD + PI - W) * 0.04;
-    let mut c2: f64;
-    if c21 < c22 {
-        c2 = c21;
-    } else {
-        c2 = c22;
-    }
-    if c2.is_sign_negative() {
-        c2 = 0.0;
-    }
+pub fn C2(PM: i64, D2: f64, PI_YTD: f64, PI: f64, W: f64, year: &YearConstants) -> f64 {
+    let maximum: f64 = utils::round(year.cpp_second_additional_rate * (year.yampe - year.ympe));
+    let c21: f64 = maximum * (PM as f64 / 12.0) - D2;
+    let c22: f64 = (PI_YTD + PI - W) * year.cpp_second_additional_rate;
+    let c2: f64 = if c21 <= c22 { c21 } else { c22 };
+    utils::round(utils::floor_zero(c2))
+}
 
-    utils::round(c2)
+/** Pensionable earnings for the pay period (PI)
+*
+*   The definition of PI used for C, C2 and F5A.
+*
+* Given:
+*
+*   gross: Gross remuneration for the pay period, including bonuses and retroactive pay increases
+*
+*   pensionable_benefits: Taxable benefits for the pay period that are pensionable
+*
+*   non_pensionable: The part of the gross remuneration that is not pensionable
+*/
+pub fn pensionable_earnings(gross: f64, pensionable_benefits: f64, non_pensionable: f64) -> f64 {
+    utils::round(utils::floor_zero(gross + pensionable_benefits - non_pensionable))
 }
 
 /** Year-to-Date Pensionable Earnings (PI_YTD) (or employee's Year's Maximum Pensionable Earnings (YMPE))
@@ -74,7 +154,7 @@ pub fn C2(PM: i64, D2: f64, PI_YTD: f64, PI: f64, W: f64) -> f64 {
 */
 #[allow(non_snake_case)]
 pub fn W(PI_YTD: f64, YMPE: f64, PM: i64) -> f64 {
-    let w1: f64 = YMPE * (PM/12) as f64;
+    let w1: f64 = YMPE * (PM as f64 / 12.0);
 
     if w1 > PI_YTD {
         return utils::round(w1);
@@ -82,28 +162,479 @@ pub fn W(PI_YTD: f64, YMPE: f64, PM: i64) -> f64 {
     PI_YTD
 }
 
+/** Annual pensionable earnings above which the second additional CPP contributions (C2) apply: the YMPE
+*
+* Given:
+*
+*   year: The constants for the year
+*/
+pub fn cpp2_threshold_income(year: &YearConstants) -> f64 {
+    year.ympe
+}
+
+/** The part of the annual pensionable earnings in the second additional CPP band, between the YMPE and the YAMPE
+*
+*   The second additional contributions for the year are this amount at the second additional rate; see `cpp_rate_components`.
+*
+* Given:
+*
+*   pensionable_earnings: The annual pensionable earnings
+*
+*   year: The constants for the year
+*/
+pub fn cpp2_band_earnings(pensionable_earnings: f64, year: &YearConstants) -> f64 {
+    utils::round(utils::clamp(pensionable_earnings, year.ympe, year.yampe) - year.ympe)
+}
+
 
 //
 // Employee Insurance Calculations:
 //
 
 
+/** Insurable earnings for the pay period (IE)
+*
+*   Gross earnings less any non-insurable amounts, limited to the room left under the annual maximum insurable earnings.
+*
+* Given:
+*
+*   gross: Gross earnings for the pay period, including taxable benefits, bonuses and retroactive pay increases
+*
+*   non_insurable: The part of the gross earnings that is not insurable, such as non-cash taxable benefits
+*
+*   ytd_insurable: Year-to-date (before the pay period) insurable earnings with the employer
+*
+*   mie: Maximum insurable earnings for the year
+*/
+pub fn insurable_earnings(gross: f64, non_insurable: f64, ytd_insurable: f64, mie: f64) -> f64 {
+    let room: f64 = utils::floor_zero(mie - ytd_insurable);
+    utils::round(utils::clamp(gross - non_insurable, 0.0, room))
+}
+
 /** Employment insurance premiums for the pay period
 *
+*   The lesser of the annual maximum less D1 (the remaining room), and the premium on the pay period's insurable earnings; never negative.
+*
+* Given:
+*
+*   D1: Employee’s year-to-date (before the pay period) employment insurance premium with the employer
+*
+*   IE: Insurable earnings for the pay period, including insurable taxable benefits, bonuses, and retroactive pay increases; see `insurable_earnings`
+*
+*   province: Province of employment; Quebec employees pay the lower Quebec rate up to the lower Quebec maximum, as QPIP replaces part of EI's benefits
+*
+*   year: The constants for the year of the calculation
+*/
+#[allow(non_snake_case)]
+pub fn EI(D1: f64, IE: f64, province: Province, year: &YearConstants) -> f64 {
+    let (rate, max) = match province {
+        Province::Quebec => (year.ei_quebec_rate, year.ei_quebec_max_contributions),
+        _ => (year.ei_rate, year.ei_max_contributions),
+    };
+    let ei1: f64 = max - D1;
+    let ei2: f64 = rate * IE;
+    let ei: f64 = if ei1 <= ei2 { ei1 } else { ei2 };
+    utils::round(utils::floor_zero(ei))
+}
+
+/** Employment insurance premiums for the year of a self-employed person who opted into EI special benefits
+*
+*   The employee rate on the net self-employment income, up to the employee annual maximum (the rate on the maximum insurable earnings); there is no employer portion. Self-employed persons in Quebec pay the lower Quebec rate, which is not covered here.
+*
+* Given:
+*
+*   net_self_employment_income: Net self-employment income for the year
+*
+*   year: The constants for the year of the calculation
+*/
+pub fn self_employed_ei(net_self_employment_income: f64, year: &YearConstants) -> f64 {
+    utils::round(utils::clamp(year.ei_rate * net_self_employment_income, 0.0, year.ei_max_contributions))
+}
+
+/** Whether the CPP contributions for the pay period reach the annual maximum, prorated for PM, for the first time
+*
+* Given:
+*
+*   PM: The total number of months during which CPP and/or QPP contributions are required to be deducted
+*
+*   D: Employee’s year-to-date (before the pay period) Canada Pension Plan contribution with the employer
+*
+*   C: CPP contributions for the pay period; see `C`
+*
+*   year: The constants for the year of the calculation
+*/
+#[allow(non_snake_case)]
+pub fn reached_cpp_max(PM: i64, D: f64, C: f64, year: &YearConstants) -> bool {
+    let max: f64 = utils::round(year.cpp_total_max_contributions * (PM as f64 / 12.0));
+    D < max && utils::round(D + C) >= max
+}
+
+/** Whether the EI premiums for the pay period reach the annual maximum for the first time
+*
 * Given:
 *
 *   D1: Employee’s year-to-date (before the pay period) employment insurance premium with the employer
 *
-*   IE: Insurable earnings for the pay period, including insurable taxable benefits, bonuses, and retroactive pay increases
+*   EI: EI premiums for the pay period; see `EI`
+*
+*   province: Province of employment, for the Quebec maximum
+*
+*   year: The constants for the year of the calculation
 */
 #[allow(non_snake_case)]
-pub fn EI(D1: f64, IE: f64) -> f64 {
-    let ei1: f64 = v2025::EI_MAX_CONTRIBUTIONS - D1;
-    let ei2: f64 = 0.0164 * IE;
-    if ei1 < ei2 {
-        return utils::round(ei1);
-    } else {
-        return utils::round(ei2);
+pub fn reached_ei_max(D1: f64, EI: f64, province: Province, year: &YearConstants) -> bool {
+    let max: f64 = match province {
+        Province::Quebec => year.ei_quebec_max_contributions,
+        _ => year.ei_max_contributions,
+    };
+    D1 < max && utils::round(D1 + EI) >= max
+}
+
+/** Employer EI premium reduction category
+*
+*   Employers with a registered wage-loss replacement plan (such as an approved short-term disability plan) pay premiums at a reduced multiple of the employee premium, set by their reduction category.
+*/
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EiReductionCategory {
+    /// No reduction; the employer pays 1.4 times the employee premium
+    Standard,
+    /// Reduced rate; the employer pays `multiplier` times the employee premium
+    Reduced { multiplier: f64 },
+}
+
+impl EiReductionCategory {
+    /// Multiple of the employee premium paid by the employer
+    pub fn multiplier(&self) -> f64 {
+        match self {
+            EiReductionCategory::Standard => 1.4,
+            EiReductionCategory::Reduced { multiplier } => *multiplier,
+        }
     }
 }
 
+/** Employer employment insurance premiums for the pay period
+*
+* Given:
+*
+*   EI: Employee employment insurance premiums for the pay period
+*
+*   category: The employer's EI premium reduction category
+*/
+#[allow(non_snake_case)]
+pub fn employer_EI(EI: f64, category: EiReductionCategory) -> f64 {
+    utils::round(EI * category.multiplier())
+}
+
+/** Canada (or Quebec) Pension Plan contributions and employment insurance premiums left to deduct for the year
+*
+*   Returns (CPP, EI): the annual maximums less the year-to-date amounts, never negative. The pension maximum is the maximum CPP contribution (base and first additional) prorated for PM, as in `C`; in Quebec, the maximum QPP contribution prorated for PM and the maximum EI premium at the Quebec rate.
+*
+* Given:
+*
+*   ytd_cpp: Employee’s year-to-date Canada (or Quebec) Pension Plan contribution with the employer, not including second additional contributions
+*
+*   ytd_ei: Employee’s year-to-date employment insurance premium with the employer
+*
+*   PM: The total number of months during which CPP and/or QPP contributions are required to be deducted
+*
+*   province: Province of employment, for the Quebec maximums
+*
+*   year: The constants for the year of the calculation
+*/
+#[allow(non_snake_case)]
+pub fn remaining_contribution_room(ytd_cpp: f64, ytd_ei: f64, PM: i64, province: Province, year: &YearConstants) -> (f64, f64) {
+    let (pension_max, ei_max) = match province {
+        Province::Quebec => (year.qpp_max_contributions, year.ei_quebec_max_contributions),
+        _ => (year.cpp_total_max_contributions, year.ei_max_contributions),
+    };
+    let pension_max = utils::round(pension_max * (PM as f64 / 12.0));
+    (
+        utils::round(utils::floor_zero(pension_max - ytd_cpp)),
+        utils::round(utils::floor_zero(ei_max - ytd_ei)),
+    )
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::year::{v2023, v2024, v2025};
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_cpp2_band_between_the_ceilings() {
+        assert_eq!(cpp2_threshold_income(&v2025::CONSTANTS), v2025::YMPE);
+        assert_eq!(cpp2_band_earnings(75000.0, &v2025::CONSTANTS), 3700.0);
+        assert_eq!(cpp2_band_earnings(60000.0, &v2025::CONSTANTS), 0.0);
+        assert_eq!(cpp2_band_earnings(100000.0, &v2025::CONSTANTS), v2025::YAMPE - v2025::YMPE);
+        assert_eq!(cpp2_band_earnings(75000.0, &v2023::CONSTANTS), 0.0);
+
+        // A year paid monthly at 75,000 contributes C2 on the band
+        let mut D2 = 0.0;
+        for month in 0..12 {
+            let PI_YTD = 6250.0 * month as f64;
+            D2 += C2(12, D2, PI_YTD, 6250.0, W(PI_YTD, v2025::YMPE, 12), &v2025::CONSTANTS);
+        }
+        assert_eq!(utils::round(D2), utils::round(0.04 * 3700.0));
+    }
+
+    #[test]
+    fn test_cpp_rate_components() {
+        let rates = cpp_rate_components(&v2025::CONSTANTS);
+        assert_eq!(rates.employee, rates.base + rates.first_additional);
+        assert!((rates.employee - 0.0595).abs() < 1e-12);
+        assert_eq!(rates.second_additional, 0.04);
+
+        // The second additional contribution started in 2024
+        assert_eq!(cpp_rate_components(&v2023::CONSTANTS).second_additional, 0.0);
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_C_2024_and_2025_maximums() {
+        let D = v2024::CONSTANTS.cpp_total_max_contributions - 10.0;
+        assert_eq!(C(12, D, 3000.0, 26, &v2024::CONSTANTS), 10.0);
+        assert_eq!(C(12, D, 3000.0, 26, &v2025::CONSTANTS), 170.49);
+
+        assert_eq!(C(12, 3867.5, 3000.0, 26, &v2024::CONSTANTS), 0.0);
+        assert_eq!(C(12, 4034.1, 3000.0, 26, &v2025::CONSTANTS), 0.0);
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_C_full_year_reaches_each_years_maximum() {
+        for year in [&v2024::CONSTANTS, &v2025::CONSTANTS] {
+            let mut D = 0.0;
+            for _ in 0..26 {
+                D = utils::round(D + C(12, D, 5000.0, 26, year));
+            }
+            assert_eq!(D, year.cpp_total_max_contributions);
+        }
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_C_for_a_low_earner_whatever_PM() {
+        let year = &v2025::CONSTANTS;
+        // The prorated maximum is far above 0.0595 × (400 − 3500 / 26) even for one month
+        for PM in [1, 3, 6, 12] {
+            assert_eq!(C(PM, 0.0, 400.0, 26, year), 15.79, "PM {}", PM);
+            assert_eq!(C(PM, 50.0, 400.0, 26, year), 15.79, "PM {}", PM);
+        }
+        // The remaining room once it is smaller
+        assert_eq!(C(12, 4034.1 - 5.0, 400.0, 26, year), 5.0);
+        // Below the basic exemption for the pay period
+        assert_eq!(C(1, 0.0, 100.0, 26, year), 0.0);
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_C_at_the_ceiling() {
+        let year = &v2025::CONSTANTS;
+        let earnings_based = C(12, 0.0, 3000.0, 26, year);
+        assert_eq!(earnings_based, 170.49);
+
+        // The remaining room equals the contribution on the earnings: the lesser of the two is that amount, reaching the maximum
+        assert_eq!(C(12, 4034.1 - 170.49, 3000.0, 26, year), 170.49);
+        // A cent less room: the room is the lesser
+        assert_eq!(C(12, 4034.1 - 170.48, 3000.0, 26, year), 170.48);
+        // A cent more room: the contribution on the earnings is the lesser
+        assert_eq!(C(12, 4034.1 - 170.50, 3000.0, 26, year), 170.49);
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_C_never_exceeds_the_prorated_maximum() {
+        for PM in 1..=12 {
+            let maximum = utils::round(v2025::CONSTANTS.cpp_total_max_contributions * (PM as f64 / 12.0));
+            let mut D = 0.0;
+            for _ in 0..26 {
+                D = utils::round(D + C(PM, D, 7777.77, 26, &v2025::CONSTANTS));
+                assert!(D <= maximum, "PM {}: {} > {}", PM, D, maximum);
+            }
+            assert_eq!(D, maximum);
+        }
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_C_full_year_at_13_and_22_pay_periods() {
+        for P in [13, 22] {
+            let mut D = 0.0;
+            for _ in 0..P {
+                D = utils::round(D + C(12, D, 7000.0, P, &v2025::CONSTANTS));
+            }
+            assert_eq!(D, v2025::CONSTANTS.cpp_total_max_contributions, "P = {}", P);
+        }
+        assert_eq!(utils::round(cpp_basic_exemption(13, &v2025::CONSTANTS)), 269.23);
+        assert_eq!(utils::round(cpp_basic_exemption(22, &v2025::CONSTANTS)), 159.09);
+    }
+
+    #[test]
+    fn test_cpp_basic_exemption_by_pay_frequency() {
+        let weekly = cpp_basic_exemption(52, &v2025::CONSTANTS);
+        let monthly = cpp_basic_exemption(12, &v2025::CONSTANTS);
+        assert_eq!(utils::round(weekly), 67.31);
+        assert_eq!(utils::round(monthly), 291.67);
+        assert!((weekly * 52.0 - v2025::CPP_BASIC_EXEMPTION).abs() < 1e-9);
+        assert!((monthly * 12.0 - v2025::CPP_BASIC_EXEMPTION).abs() < 1e-9);
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_C_partial_year_is_prorated() {
+        // Contributions required for six months, e.g. the employee turned 18 in June.
+        let mut D = 0.0;
+        for _ in 0..6 {
+            D = utils::round(D + C(6, D, 10000.0, 12, &v2025::CONSTANTS));
+        }
+        assert_eq!(D, utils::round(4034.1 * 6.0 / 12.0));
+        assert_eq!(C(6, 0.0, 2000.0, 12, &v2025::CONSTANTS), utils::round(0.0595 * (2000.0 - 3500.0 / 12.0)));
+    }
+
+    #[test]
+    fn test_pensionable_earnings() {
+        // A pensionable benefit, such as employer-paid group life insurance
+        assert_eq!(pensionable_earnings(3000.0, 45.0, 0.0), 3045.0);
+        // A non-pensionable amount included in gross
+        assert_eq!(pensionable_earnings(3000.0, 0.0, 250.0), 2750.0);
+        assert_eq!(pensionable_earnings(3000.0, 45.0, 250.0), 2795.0);
+        assert_eq!(pensionable_earnings(100.0, 0.0, 250.0), 0.0);
+    }
+
+    #[test]
+    fn test_insurable_earnings_is_capped_at_remaining_room() {
+        assert_eq!(insurable_earnings(3000.0, 200.0, 0.0, v2025::MIE), 2800.0);
+        assert_eq!(insurable_earnings(3000.0, 0.0, 64000.0, v2025::MIE), 1700.0);
+        assert_eq!(insurable_earnings(3000.0, 0.0, v2025::MIE, v2025::MIE), 0.0);
+        assert_eq!(insurable_earnings(3000.0, 0.0, 70000.0, v2025::MIE), 0.0);
+        assert_eq!(insurable_earnings(100.0, 250.0, 0.0, v2025::MIE), 0.0);
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_employer_EI_standard_and_reduced() {
+        let EI = EI(0.0, 2307.69, Province::Ontario, &v2025::CONSTANTS);
+        assert_eq!(EI, 37.85);
+        assert_eq!(employer_EI(EI, EiReductionCategory::Standard), 52.99);
+        assert_eq!(employer_EI(EI, EiReductionCategory::Reduced { multiplier: 1.204 }), 45.57);
+        assert!(employer_EI(EI, EiReductionCategory::Reduced { multiplier: 1.204 }) < employer_EI(EI, EiReductionCategory::Standard));
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_EI_quebec_rate_is_lower_than_ontario() {
+        let year = &v2025::CONSTANTS;
+        assert_eq!(EI(0.0, 5000.0, Province::Ontario, year), 82.0);
+        assert_eq!(EI(0.0, 5000.0, Province::Quebec, year), 65.5);
+
+        // Each stops at its own maximum
+        assert_eq!(EI(v2025::EI_QUEBEC_MAX_CONTRIBUTIONS - 10.0, 5000.0, Province::Quebec, year), 10.0);
+        assert_eq!(EI(v2025::EI_QUEBEC_MAX_CONTRIBUTIONS, 5000.0, Province::Ontario, year), 82.0);
+        assert_eq!(EI(v2025::EI_QUEBEC_MAX_CONTRIBUTIONS, 5000.0, Province::Quebec, year), 0.0);
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_self_employed_ei_matches_employee_EI() {
+        let year = &v2025::CONSTANTS;
+        assert_eq!(self_employed_ei(v2025::MIE, year), EI(0.0, v2025::MIE, Province::Ontario, year));
+        assert_eq!(self_employed_ei(v2025::MIE, year), v2025::EI_MAX_CONTRIBUTIONS);
+        assert_eq!(self_employed_ei(120000.0, year), v2025::EI_MAX_CONTRIBUTIONS);
+        assert_eq!(self_employed_ei(30000.0, year), 492.0);
+        assert_eq!(self_employed_ei(0.0, year), 0.0);
+        assert_eq!(self_employed_ei(-5000.0, year), 0.0);
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_EI_uses_the_year_rate() {
+        assert_eq!(EI(0.0, 5000.0, Province::Ontario, &v2024::CONSTANTS), 83.0);
+        assert_eq!(EI(0.0, 5000.0, Province::Alberta, &v2023::CONSTANTS), 81.5);
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_cpp_breakdown_adds_up_to_C() {
+        let year = &v2025::CONSTANTS;
+        for PI in [500.0, 2307.69, 3958.33, 10000.0] {
+            let (base, first_additional) = cpp_breakdown(12, 0.0, PI, 26, year);
+            assert_eq!(utils::round(base + first_additional), C(12, 0.0, PI, 26, year));
+            assert!(first_additional < base);
+        }
+
+        let (base, first_additional) = cpp_breakdown(12, 0.0, 2307.69, 26, year);
+        assert_eq!(C(12, 0.0, 2307.69, 26, year), 129.3);
+        assert_eq!((base, first_additional), (107.57, 21.73));
+
+        assert_eq!(cpp_breakdown(12, 4034.1, 2307.69, 26, year), (0.0, 0.0));
+    }
+
+    #[test]
+    fn test_remaining_contribution_room() {
+        let year = &v2025::CONSTANTS;
+        let room = |ytd_cpp, ytd_ei| remaining_contribution_room(ytd_cpp, ytd_ei, 12, Province::Ontario, year);
+        assert_eq!(room(0.0, 0.0), (year.cpp_total_max_contributions, v2025::EI_MAX_CONTRIBUTIONS));
+        assert_eq!(room(3300.0, 1050.0), (734.1, 27.48));
+        assert_eq!(room(year.cpp_total_max_contributions, v2025::EI_MAX_CONTRIBUTIONS), (0.0, 0.0));
+        assert_eq!(room(4100.0, 1200.0), (0.0, 0.0));
+    }
+
+    #[test]
+    fn test_remaining_contribution_room_is_prorated_and_uses_the_quebec_maximums() {
+        let year = &v2025::CONSTANTS;
+        // Half of 4,034.10 for six months
+        assert_eq!(remaining_contribution_room(1000.0, 0.0, 6, Province::Ontario, year), (1017.05, v2025::EI_MAX_CONTRIBUTIONS));
+        assert_eq!(remaining_contribution_room(4000.0, 800.0, 12, Province::Quebec, year), (339.2, 60.67));
+        assert_eq!(remaining_contribution_room(0.0, 0.0, 6, Province::Quebec, year), (2169.6, v2025::EI_QUEBEC_MAX_CONTRIBUTIONS));
+    }
+
+    #[test]
+    fn test_reached_max_only_in_the_period_it_is_reached() {
+        let year = &v2025::CONSTANTS;
+        assert!(reached_cpp_max(12, 4000.0, 34.1, year));
+        assert!(!reached_cpp_max(12, 3900.0, 34.1, year));
+        assert!(!reached_cpp_max(12, 4034.1, 0.0, year));
+        assert!(reached_cpp_max(6, 1967.05, 50.0, year));
+
+        assert!(reached_ei_max(1050.0, 27.48, Province::Ontario, year));
+        assert!(!reached_ei_max(1000.0, 27.48, Province::Ontario, year));
+        assert!(!reached_ei_max(v2025::EI_MAX_CONTRIBUTIONS, 0.0, Province::Ontario, year));
+        assert!(reached_ei_max(850.0, 10.67, Province::Quebec, year));
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_final_period_deducts_the_last_dollar_of_room() {
+        assert_eq!(C(12, 4034.1 - 1.0, 3000.0, 26, &v2025::CONSTANTS), 1.0);
+        assert_eq!(C2(12, 396.0 - 1.0, 80000.0, 3000.0, 71300.0, &v2025::CONSTANTS), 1.0);
+        assert_eq!(EI(v2025::EI_MAX_CONTRIBUTIONS - 1.0, 3000.0, Province::Ontario, &v2025::CONSTANTS), 1.0);
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_deductions_are_never_negative_past_the_maximum() {
+        assert_eq!(C(12, 4034.1 + 5.0, 3000.0, 26, &v2025::CONSTANTS), 0.0);
+        assert_eq!(C2(12, 396.0 + 5.0, 80000.0, 3000.0, 71300.0, &v2025::CONSTANTS), 0.0);
+        assert_eq!(EI(v2025::EI_MAX_CONTRIBUTIONS + 5.0, 3000.0, Province::Ontario, &v2025::CONSTANTS), 0.0);
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_C2_is_clamped() {
+        assert_eq!(C2(12, 0.0, 75000.0, 3000.0, 71300.0, &v2025::CONSTANTS), 268.0);
+        assert_eq!(C2(12, 390.0, 80000.0, 3000.0, 71300.0, &v2025::CONSTANTS), 6.0);
+        assert_eq!(C2(12, 0.0, 50000.0, 3000.0, 71300.0, &v2025::CONSTANTS), 0.0);
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_C2_maximum_is_the_band_of_the_year() {
+        // 4% × (73,200 − 68,500) in 2024
+        assert_eq!(C2(12, 0.0, 80000.0, 3000.0, 68500.0, &v2024::CONSTANTS), 188.0);
+        assert_eq!(C2(12, 180.0, 80000.0, 3000.0, 68500.0, &v2024::CONSTANTS), 8.0);
+        assert_eq!(C2(12, 0.0, 70000.0, 3000.0, 68500.0, &v2024::CONSTANTS), 180.0);
+        assert_eq!(C2(12, 0.0, 80000.0, 3000.0, 66600.0, &v2023::CONSTANTS), 0.0);
+    }
+}
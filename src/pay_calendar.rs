@@ -0,0 +1,266 @@
+//! Pay Dates for a Calendar Year
+
+use crate::basic_personal_income;
+use crate::error::PayrollError;
+use crate::pay_period::PayPeriod;
+
+/** A calendar date, without a time or time zone.
+*
+*   Only what the pay calendar needs: construction, the year, month and day, and adding days.
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct NaiveDate {
+    year: i32,
+    month: u32,
+    day: u32,
+}
+
+impl NaiveDate {
+    /// The date, or `None` if the month or day does not exist.
+    pub fn from_ymd_opt(year: i32, month: u32, day: u32) -> Option<Self> {
+        if !(1..=12).contains(&month) || day < 1 || day > days_in_month(year, month) {
+            return None;
+        }
+        Some(NaiveDate { year, month, day })
+    }
+
+    pub fn year(&self) -> i32 {
+        self.year
+    }
+
+    pub fn month(&self) -> u32 {
+        self.month
+    }
+
+    pub fn day(&self) -> u32 {
+        self.day
+    }
+
+    /// The date `days` days later, or earlier if negative.
+    pub fn add_days(&self, days: i64) -> Self {
+        from_days(to_days(self) + days)
+    }
+}
+
+fn is_leap_year(year: i32) -> bool {
+    (year % 4 == 0 && year % 100 != 0) || year % 400 == 0
+}
+
+fn days_in_month(year: i32, month: u32) -> u32 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+// Days since 1970-01-01, counting from March so the leap day is last in the year
+fn to_days(date: &NaiveDate) -> i64 {
+    let year = date.year as i64 - if date.month <= 2 { 1 } else { 0 };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let month = date.month as i64;
+    let day_of_year = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + date.day as i64 - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146097 + day_of_era - 719468
+}
+
+fn from_days(days: i64) -> NaiveDate {
+    let days = days + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days - era * 146097;
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * month + 2) / 5 + 1) as u32;
+    let month = if month < 10 { month + 3 } else { month - 9 } as u32;
+    let year = (year_of_era + era * 400 + if month <= 2 { 1 } else { 0 }) as i32;
+    NaiveDate { year, month, day }
+}
+
+/** Pay dates in a calendar year
+*
+*   Weekly, biweekly and four-weekly pay dates repeat every 7, 14 and 28 days from the first pay date, which can be in an earlier year. Semi-monthly pay dates are the 15th and the last day of each month, and monthly pay dates the day of the month of the first pay date (or the last day of shorter months); both start in the month of the first pay date.
+*
+*   Returns `PayrollError::InvalidPayPeriods` for school-year and irregular pay frequencies, which have no fixed calendar.
+*
+* Given:
+*
+*   first_pay_date: The first pay date of the schedule
+*
+*   frequency: The pay frequency
+*
+*   year: The calendar year to list the pay dates of
+*/
+pub fn pay_calendar(first_pay_date: NaiveDate, frequency: PayPeriod, year: i32) -> Result<Vec<NaiveDate>, PayrollError> {
+    let in_year = |date: &NaiveDate| date.year == year && *date >= first_pay_date;
+
+    let dates: Vec<NaiveDate> = match frequency {
+        PayPeriod::Weekly | PayPeriod::BiWeekly | PayPeriod::FourWeekly => {
+            let step: i64 = match frequency {
+                PayPeriod::Weekly => 7,
+                PayPeriod::BiWeekly => 14,
+                _ => 28,
+            };
+            let start = to_days(&NaiveDate { year, month: 1, day: 1 });
+            let first = to_days(&first_pay_date);
+            // The first pay date on or after January 1
+            let skipped = if first < start { (start - first + step - 1) / step } else { 0 };
+            (0..)
+                .map(|period| from_days(first + (skipped + period) * step))
+                .take_while(|date| date.year <= year)
+                .filter(in_year)
+                .collect()
+        }
+        PayPeriod::SemiMonthly => (1..=12)
+            .flat_map(|month| [NaiveDate { year, month, day: 15 }, NaiveDate { year, month, day: days_in_month(year, month) }])
+            .filter(|date| *date >= first_pay_date)
+            .collect(),
+        PayPeriod::Monthly => (1..=12)
+            .map(|month| NaiveDate { year, month, day: first_pay_date.day.min(days_in_month(year, month)) })
+            .filter(in_year)
+            .collect(),
+        PayPeriod::SchoolYear | PayPeriod::Irregular { .. } => {
+            return Err(PayrollError::InvalidPayPeriods(frequency.periods_per_year()));
+        }
+    };
+    Ok(dates)
+}
+
+/** Number of pay dates in a calendar year
+*
+*   Usually the number of pay periods for the frequency; 27 biweekly or 53 weekly pay dates when the year starts and ends on a pay date (or, for biweekly in a leap year, on the day after). See `pay_calendar`.
+*
+* Given:
+*
+*   first_pay_date: The first pay date of the schedule
+*
+*   frequency: The pay frequency
+*
+*   year: The calendar year
+*/
+pub fn pay_periods(first_pay_date: NaiveDate, frequency: PayPeriod, year: i32) -> Result<i64, PayrollError> {
+    Ok(pay_calendar(first_pay_date, frequency, year)?.len() as i64)
+}
+
+/** Annualizing factor (S1) for a pay date, from the pay calendar of the year
+*
+*   The current pay period is the number of pay dates in the calendar on or before the pay date, and the total the number of pay dates in the calendar; see `basic_personal_income::S1`. Returns `None` for a pay date before the first one in the calendar.
+*
+* Given:
+*
+*   pay_date: The pay date being processed
+*
+*   calendar: The pay dates of the year, in order; see `pay_calendar`
+*/
+pub fn s1_from_dates(pay_date: NaiveDate, calendar: &[NaiveDate]) -> Option<f64> {
+    let current = calendar.iter().take_while(|date| **date <= pay_date).count() as i64;
+    if current == 0 {
+        return None;
+    }
+    Some(basic_personal_income::S1(calendar.len() as i64, current))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn test_from_ymd_opt_rejects_missing_dates() {
+        assert_eq!(NaiveDate::from_ymd_opt(2025, 2, 29), None);
+        assert_eq!(NaiveDate::from_ymd_opt(2025, 13, 1), None);
+        assert_eq!(NaiveDate::from_ymd_opt(2025, 4, 0), None);
+        assert!(NaiveDate::from_ymd_opt(2024, 2, 29).is_some());
+    }
+
+    #[test]
+    fn test_add_days_across_months_and_years() {
+        assert_eq!(date(2025, 1, 31).add_days(1), date(2025, 2, 1));
+        assert_eq!(date(2024, 2, 28).add_days(1), date(2024, 2, 29));
+        assert_eq!(date(2025, 12, 26).add_days(14), date(2026, 1, 9));
+        assert_eq!(date(2025, 1, 9).add_days(-14), date(2024, 12, 26));
+        assert_eq!(date(1970, 1, 1).add_days(0), date(1970, 1, 1));
+    }
+
+    #[test]
+    fn test_biweekly_has_26_or_27_pay_dates() {
+        let dates = pay_calendar(date(2025, 1, 3), PayPeriod::BiWeekly, 2025).unwrap();
+        assert_eq!(dates.len(), 26);
+        assert_eq!(dates[1], date(2025, 1, 17));
+        assert_eq!(dates[25], date(2025, 12, 19));
+
+        // January 1 and December 31 are both pay dates
+        let dates = pay_calendar(date(2027, 1, 1), PayPeriod::BiWeekly, 2027).unwrap();
+        assert_eq!(dates.len(), 27);
+        assert_eq!(dates[26], date(2027, 12, 31));
+
+        // A schedule that started the year before
+        let dates = pay_calendar(date(2025, 12, 19), PayPeriod::BiWeekly, 2026).unwrap();
+        assert_eq!(dates[0], date(2026, 1, 2));
+        assert_eq!(dates.len(), 26);
+    }
+
+    #[test]
+    fn test_pay_periods_detects_an_extra_pay_date() {
+        assert_eq!(pay_periods(date(2025, 1, 3), PayPeriod::BiWeekly, 2025), Ok(26));
+        assert_eq!(pay_periods(date(2025, 1, 1), PayPeriod::BiWeekly, 2025), Ok(27));
+        assert_eq!(pay_periods(date(2024, 1, 2), PayPeriod::BiWeekly, 2024), Ok(27));
+        assert_eq!(pay_periods(date(2025, 1, 1), PayPeriod::Weekly, 2025), Ok(53));
+        assert_eq!(pay_periods(date(2025, 1, 2), PayPeriod::Weekly, 2025), Ok(52));
+    }
+
+    #[test]
+    fn test_semi_monthly_always_has_24_pay_dates() {
+        for year in [2023, 2024, 2025, 2026] {
+            let dates = pay_calendar(date(year, 1, 15), PayPeriod::SemiMonthly, year).unwrap();
+            assert_eq!(dates.len(), 24);
+            assert_eq!(dates[3], date(year, 2, if year == 2024 { 29 } else { 28 }));
+        }
+    }
+
+    #[test]
+    fn test_semi_monthly_starts_on_the_first_pay_date() {
+        let dates = pay_calendar(date(2025, 1, 31), PayPeriod::SemiMonthly, 2025).unwrap();
+        assert_eq!(dates.len(), 23);
+        assert_eq!(dates[0], date(2025, 1, 31));
+        assert!(!dates.contains(&date(2025, 1, 15)));
+    }
+
+    #[test]
+    fn test_monthly_and_weekly_pay_dates() {
+        let dates = pay_calendar(date(2025, 1, 31), PayPeriod::Monthly, 2025).unwrap();
+        assert_eq!(dates.len(), 12);
+        assert_eq!(dates[1], date(2025, 2, 28));
+        assert_eq!(dates[2], date(2025, 3, 31));
+
+        assert_eq!(pay_calendar(date(2025, 1, 3), PayPeriod::Weekly, 2025).unwrap().len(), 52);
+        assert_eq!(pay_calendar(date(2025, 1, 3), PayPeriod::FourWeekly, 2025).unwrap().len(), 13);
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_s1_from_dates_matches_S1() {
+        let calendar = pay_calendar(date(2025, 1, 3), PayPeriod::BiWeekly, 2025).unwrap();
+        assert_eq!(calendar[6], date(2025, 3, 28));
+        assert_eq!(s1_from_dates(date(2025, 3, 28), &calendar), Some(basic_personal_income::S1(26, 7)));
+        assert!((s1_from_dates(date(2025, 3, 28), &calendar).unwrap() - 26.0 / 7.0).abs() < 1e-12);
+
+        // Between pay dates, the last pay date so far
+        assert_eq!(s1_from_dates(date(2025, 4, 1), &calendar), Some(26.0 / 7.0));
+        assert_eq!(s1_from_dates(date(2025, 12, 19), &calendar), Some(1.0));
+        assert_eq!(s1_from_dates(date(2025, 1, 2), &calendar), None);
+    }
+
+    #[test]
+    fn test_frequencies_without_a_calendar() {
+        assert_eq!(
+            pay_calendar(date(2025, 1, 3), PayPeriod::SchoolYear, 2025),
+            Err(PayrollError::InvalidPayPeriods(22))
+        );
+    }
+}
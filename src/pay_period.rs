@@ -0,0 +1,79 @@
+//! Pay Frequencies
+
+use crate::error::PayrollError;
+
+/** Pay frequency of an employee.
+*
+*   Used to determine P: the number of pay periods in the year.
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PayPeriod {
+    Weekly,
+    BiWeekly,
+    /// Every four weeks: 13 pay periods
+    FourWeekly,
+    SemiMonthly,
+    /// Ten months of the school year: 22 pay periods
+    SchoolYear,
+    Monthly,
+    /// Paid irregularly, such as casual or on-call work; each payment is annualized over the number of payments expected in the year
+    Irregular { assumed_periods: i64 },
+}
+
+impl PayPeriod {
+    /// P: The number of pay periods in the year
+    pub fn periods_per_year(&self) -> i64 {
+        match self {
+            PayPeriod::Weekly => 52,
+            PayPeriod::BiWeekly => 26,
+            PayPeriod::FourWeekly => 13,
+            PayPeriod::SemiMonthly => 24,
+            PayPeriod::SchoolYear => 22,
+            PayPeriod::Monthly => 12,
+            PayPeriod::Irregular { assumed_periods } => *assumed_periods,
+        }
+    }
+}
+
+/** PR: The number of pay periods left in the year, including the current pay period
+*
+*   Used to prorate F1, K3 and the year-to-date credits over the rest of the year. Returns `PayrollError::InvalidPeriodsRemaining` unless the current pay period is between 1 and the number of pay periods in the year.
+*
+* Given:
+*
+*   current_period: The pay period being processed, counting from 1
+*
+*   total_periods: P, the number of pay periods in the year
+*/
+pub fn periods_remaining(current_period: i64, total_periods: i64) -> Result<i64, PayrollError> {
+    let remaining = total_periods - current_period + 1;
+    if current_period < 1 || current_period > total_periods {
+        return Err(PayrollError::InvalidPeriodsRemaining { P: total_periods, PR: remaining });
+    }
+    Ok(remaining)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_periods_per_year() {
+        assert_eq!(PayPeriod::Weekly.periods_per_year(), 52);
+        assert_eq!(PayPeriod::BiWeekly.periods_per_year(), 26);
+        assert_eq!(PayPeriod::FourWeekly.periods_per_year(), 13);
+        assert_eq!(PayPeriod::SemiMonthly.periods_per_year(), 24);
+        assert_eq!(PayPeriod::SchoolYear.periods_per_year(), 22);
+        assert_eq!(PayPeriod::Monthly.periods_per_year(), 12);
+        assert_eq!(PayPeriod::Irregular { assumed_periods: 8 }.periods_per_year(), 8);
+    }
+
+    #[test]
+    fn test_periods_remaining() {
+        assert_eq!(periods_remaining(1, 26), Ok(26));
+        assert_eq!(periods_remaining(13, 26), Ok(14));
+        assert_eq!(periods_remaining(26, 26), Ok(1));
+        assert_eq!(periods_remaining(27, 26), Err(PayrollError::InvalidPeriodsRemaining { P: 26, PR: 0 }));
+        assert_eq!(periods_remaining(0, 26), Err(PayrollError::InvalidPeriodsRemaining { P: 26, PR: 27 }));
+    }
+}
@@ -0,0 +1,2624 @@
+//! # Payroll Deductions for a Pay Period
+//! Chains the individual CRA factor formulas into the tax, Canada Pension Plan and Employment Insurance deductions for one employee's pay period.
+//!
+//! Calculations follow Option 1 (non-cumulative) for periodic, non-commissionable earnings.
+
+use crate::basic_personal_income::{self, ZoneType};
+use crate::error::PayrollError;
+use crate::federal_income_tax::{self, FederalCredits};
+use crate::income_tax::{self, RoundingStage};
+use crate::other_deductions::{self, EiReductionCategory};
+use crate::pay_calendar::{self, NaiveDate};
+use crate::pay_period::PayPeriod;
+use crate::province::Province;
+use crate::provincial_income_tax::provincial_income_tax::{self, ProvincialCredits};
+use crate::quebec;
+use crate::utils;
+use crate::year::{self, v2025, Bracket, YearConstants};
+
+/// Whether the payments are employment income or pension income.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PayeeType {
+    Employee,
+    /// Pension income is neither pensionable nor insurable; no CPP or EI is deducted.
+    Pensioner,
+}
+
+/// Where the employee resides for tax purposes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Residency {
+    #[default]
+    Canada,
+    /// Outside Canada, or in Canada beyond the limits of any province or territory; the federal tax is increased by 48%, and there is no provincial or territorial tax.
+    OutsideCanada,
+}
+
+/** Settings shared by every employee in a payroll run.
+*
+*   Constructed once per run and passed by reference alongside each employee's `PayrollInput`.
+*/
+#[derive(Debug, Clone, PartialEq)]
+pub struct PayrollContext {
+    pub province: Province,
+    /// The constants for the year of the payroll run
+    pub year: &'static YearConstants,
+    pub pay_period: PayPeriod,
+    pub residency: Residency,
+    pub payee_type: PayeeType,
+    /// P, when the year has more pay dates than usual for the pay frequency; see `with_pay_calendar`
+    pub pay_periods: Option<i64>,
+}
+
+impl PayrollContext {
+    /// A context for employees resident in Canada; returns `PayrollError::UnsupportedYear` if there are no constants for the year.
+    pub fn new(province: Province, year: u16, pay_period: PayPeriod) -> Result<Self, PayrollError> {
+        Ok(PayrollContext {
+            province,
+            year: year::constants(year).ok_or(PayrollError::UnsupportedYear(year))?,
+            pay_period,
+            residency: Residency::Canada,
+            payee_type: PayeeType::Employee,
+            pay_periods: None,
+        })
+    }
+
+    /// P: The number of pay periods in the year
+    pub fn periods_per_year(&self) -> i64 {
+        self.pay_periods.unwrap_or_else(|| self.pay_period.periods_per_year())
+    }
+
+    /** The context with P counted from the pay calendar of the year.
+    *
+    *   A biweekly or weekly schedule has 27 or 53 pay dates in some years, depending on the first pay date; P is then the actual number of pay dates. A year with fewer pay dates than usual, such as one starting mid-year, keeps the usual P.
+    */
+    pub fn with_pay_calendar(self, first_pay_date: NaiveDate) -> Result<Self, PayrollError> {
+        let pay_periods = pay_calendar::pay_periods(first_pay_date, self.pay_period, self.year.year as i32)?;
+        if pay_periods > self.pay_period.periods_per_year() {
+            return Ok(PayrollContext { pay_periods: Some(pay_periods), ..self });
+        }
+        Ok(self)
+    }
+}
+
+/// Dependants claimed for the Ontario tax reduction (Factor Y) and the Manitoba family tax benefit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Dependents {
+    pub under_19: i64,
+    pub disabled: i64,
+}
+
+/** Factors to use in place of the calculated ones, for audits and what-if scenarios.
+*
+*   Any factor left as `None` is calculated as usual.
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct FactorOverrides {
+    /// K2: Federal CPP and EI credits
+    pub k2: Option<f64>,
+    /// K2P: Provincial or territorial CPP and EI credits
+    pub k2p: Option<f64>,
+    /// K3: Other federal non-refundable tax credits, in place of `other_federal_credits`
+    pub k3: Option<f64>,
+    /// T3: Annual basic federal tax
+    pub t3: Option<f64>,
+    /// T4: Annual basic provincial or territorial tax
+    pub t4: Option<f64>,
+}
+
+/// When a voluntary deduction is taken from the pay.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeductionTiming {
+    /// Deducted before tax, like an RRSP contribution; lowers the taxable income (F)
+    PreTax,
+    /// Deducted from the pay after tax
+    PostTax,
+}
+
+/// A deduction the employee chose to have taken from their pay, such as health premiums, pension top-ups or charitable giving.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VoluntaryDeduction {
+    pub name: String,
+    /// Amount deducted for the pay period
+    pub amount: f64,
+    pub timing: DeductionTiming,
+}
+
+/// A Form TD1 that replaced an earlier one during the year.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Td1Change {
+    /// PR: Pay periods left in the year, including the pay period the new Form TD1 took effect
+    pub periods_remaining: i64,
+    /// TC on the federal Form TD1 before the change
+    pub previous_federal_claim: f64,
+    /// TCP on the provincial or territorial Form TD1 before the change
+    pub previous_provincial_claim: f64,
+}
+
+/** Employee input for a single pay period.
+*
+*   Per-period amounts are for the current pay period only; year-to-date amounts are before the current pay period.
+*/
+#[derive(Debug, Clone, PartialEq)]
+pub struct PayrollInput {
+    /// Cash remuneration for the pay period
+    pub cash_income: f64,
+    /// Taxable benefits for the pay period; taxable and pensionable
+    pub taxable_benefits: f64,
+    /// The part of `taxable_benefits` that is not insurable, such as non-cash benefits
+    pub non_insurable_benefits: f64,
+    /// F: RPP, RRSP, PRPP or RCA contributions for the pay period
+    pub retirement_contributions: f64,
+    /// Voluntary deductions for the pay period; pre-tax ones are added to F
+    pub voluntary_deductions: Vec<VoluntaryDeduction>,
+    /// F2: Alimony or maintenance payments for the pay period
+    pub support_payments: f64,
+    /// U1: Union dues for the pay period
+    pub union_dues: f64,
+    /// Prescribed zone the employee lives in, for the annual deduction HD
+    pub prescribed_zone: ZoneType,
+    /// Days in the year the employee lives in the prescribed zone
+    pub prescribed_zone_days: i64,
+    /// Amount deducted for the pay period to acquire approved labour-sponsored venture capital shares, for the credit LCF
+    pub labour_sponsored_shares: f64,
+    /// F1: Annual deductions authorized by a tax services office or tax centre
+    pub annual_deductions: f64,
+    /// L: Additional tax deductions for the pay period requested on Form TD1
+    pub additional_tax: f64,
+    /// TC: Total claim amount on the federal Form TD1
+    pub federal_claim: f64,
+    /// TCP: Total claim amount on the provincial or territorial Form TD1; `None` for the basic personal amount of the province or territory (see `provincial_income_tax::basic_amount`)
+    pub provincial_claim: Option<f64>,
+    /// K3: Other annual federal non-refundable tax credits
+    pub other_federal_credits: f64,
+    /// K3P: Other annual provincial or territorial non-refundable tax credits
+    pub other_provincial_credits: f64,
+    pub dependents: Dependents,
+    /// The earlier claim amounts, when `federal_claim` and `provincial_claim` are from a Form TD1 received during the year; the change in the credits is prorated over the pay periods left
+    pub td1_change: Option<Td1Change>,
+    /// PM: Months in the year during which CPP contributions are required
+    pub contribution_months: i64,
+    /// Date the pay period is paid, for rules that depend on it such as a CPT30 election
+    pub pay_date: Option<NaiveDate>,
+    /// Effective date of an election on Form CPT30 to stop contributing to the CPP, for a working beneficiary aged 65 to 70; no CPP is deducted on pay dates from then on
+    pub cpt30_election: Option<NaiveDate>,
+    /// D: Year-to-date CPP contributions, or QPP contributions in Quebec
+    pub ytd_cpp: f64,
+    /// D2: Year-to-date second additional CPP contributions
+    pub ytd_cpp2: f64,
+    /// D1: Year-to-date EI premiums
+    pub ytd_ei: f64,
+    /// DQPIP: Year-to-date QPIP premiums, in Quebec
+    pub ytd_qpip: f64,
+    /// PI_YTD: Year-to-date pensionable earnings
+    pub ytd_pensionable: f64,
+    pub overrides: FactorOverrides,
+    /// When the tax for the pay period is rounded; CRA rounds once, at the end
+    pub rounding: RoundingStage,
+}
+
+impl PayrollInput {
+    /// I: Gross remuneration for the pay period, the cash income plus taxable benefits
+    pub fn gross(&self) -> f64 {
+        self.cash_income + self.taxable_benefits
+    }
+
+    /// PI: Pensionable earnings for the pay period; taxable benefits are pensionable
+    pub fn pensionable_earnings(&self) -> f64 {
+        other_deductions::pensionable_earnings(self.cash_income, self.taxable_benefits, 0.0)
+    }
+
+    /** IE: Insurable earnings for the pay period, the gross remuneration less non-insurable benefits
+    *
+    *   A pay period with no cash remuneration, such as unpaid leave with only a non-cash benefit, has no insurable earnings, while the benefit is still pensionable.
+    */
+    pub fn insurable_earnings(&self) -> f64 {
+        if self.cash_income <= 0.0 {
+            return 0.0;
+        }
+        utils::floor_zero(self.gross() - self.non_insurable_benefits)
+    }
+
+    /// F: Deductions for the pay period that lower the taxable income, the retirement contributions plus the pre-tax voluntary deductions
+    pub fn pre_tax_deductions(&self) -> f64 {
+        self.retirement_contributions + self.voluntary(DeductionTiming::PreTax)
+    }
+
+    /// Voluntary deductions for the pay period taken after tax
+    pub fn post_tax_deductions(&self) -> f64 {
+        self.voluntary(DeductionTiming::PostTax)
+    }
+
+    /** PM for the year, less the months from a CPT30 election on
+    *
+    *   An election effective in the year leaves only the months before the month it is effective in; one effective in an earlier year leaves none.
+    */
+    pub fn cpp_contribution_months(&self, year: u16) -> i64 {
+        match self.cpt30_election {
+            Some(election) if election.year() < year as i32 => 0,
+            Some(election) if election.year() == year as i32 => self.contribution_months.min(election.month() as i64 - 1),
+            _ => self.contribution_months,
+        }
+    }
+
+    /// Whether the pay date is on or after the effective date of a CPT30 election, so no CPP is deducted.
+    pub fn cpp_stopped(&self) -> bool {
+        match (self.pay_date, self.cpt30_election) {
+            (Some(pay_date), Some(election)) => pay_date >= election,
+            _ => false,
+        }
+    }
+
+    fn voluntary(&self, timing: DeductionTiming) -> f64 {
+        self.voluntary_deductions.iter().filter(|deduction| deduction.timing == timing).map(|deduction| deduction.amount).sum()
+    }
+}
+
+/// Deductions for a single pay period.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PayrollResult {
+    /// I: Gross remuneration, including taxable benefits
+    pub gross: f64,
+    /// C: CPP contributions for the pay period, or QPP contributions in Quebec
+    pub cpp: f64,
+    /// C2: Second additional CPP contributions for the pay period
+    pub cpp2: f64,
+    /// EI: EI premiums for the pay period
+    pub ei: f64,
+    /// QPIP premiums for the pay period; zero outside Quebec
+    pub qpip: f64,
+    /// A: Annual taxable income
+    pub annual_taxable_income: f64,
+    /// BPAF: Federal basic personal amount, phased out on net income (A + HD)
+    pub basic_personal_amount: f64,
+    /// T1: Annual federal tax deduction
+    pub federal_tax: f64,
+    /// T2: Annual provincial or territorial tax deduction
+    pub provincial_tax: f64,
+    /// T: Federal and provincial tax deductions for the pay period
+    pub tax: f64,
+    /// Cash income less statutory and voluntary deductions; taxable benefits are not paid out
+    pub net: f64,
+}
+
+/// Change in each amount between two `PayrollResult`s (after − before).
+#[derive(Debug, Clone, PartialEq)]
+pub struct PayrollDiff {
+    pub gross: f64,
+    pub cpp: f64,
+    pub cpp2: f64,
+    pub ei: f64,
+    pub qpip: f64,
+    pub annual_taxable_income: f64,
+    pub basic_personal_amount: f64,
+    pub federal_tax: f64,
+    pub provincial_tax: f64,
+    pub tax: f64,
+    pub net: f64,
+}
+
+/// Compare two results, such as before and after a raise or a TD1 change.
+pub fn diff(before: &PayrollResult, after: &PayrollResult) -> PayrollDiff {
+    PayrollDiff {
+        gross: utils::round(after.gross - before.gross),
+        cpp: utils::round(after.cpp - before.cpp),
+        cpp2: utils::round(after.cpp2 - before.cpp2),
+        ei: utils::round(after.ei - before.ei),
+        qpip: utils::round(after.qpip - before.qpip),
+        annual_taxable_income: utils::round(after.annual_taxable_income - before.annual_taxable_income),
+        basic_personal_amount: utils::round(after.basic_personal_amount - before.basic_personal_amount),
+        federal_tax: utils::round(after.federal_tax - before.federal_tax),
+        provincial_tax: utils::round(after.provincial_tax - before.provincial_tax),
+        tax: utils::round(after.tax - before.tax),
+        net: utils::round(after.net - before.net),
+    }
+}
+
+/// Amounts an employer remits to CRA for a pay period.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Remittance {
+    /// Federal and provincial income tax deducted
+    pub income_tax: f64,
+    /// Employee CPP and CPP2 contributions deducted, plus the matching employer contributions
+    pub cpp: f64,
+    /// Employee EI premiums deducted, plus the employer premiums at 1.4 times the employee premiums
+    pub ei: f64,
+}
+
+impl Remittance {
+    /// Total amount to remit
+    pub fn total(&self) -> f64 {
+        utils::round(self.income_tax + self.cpp + self.ei)
+    }
+}
+
+/** Total the amounts to remit for a pay period across all employees.
+*
+*   The employer matches each employee's CPP contributions and pays EI premiums at the standard rate; see `other_deductions::employer_EI`.
+*/
+pub fn employer_remittance(results: &[PayrollResult]) -> Remittance {
+    let mut remittance = Remittance::default();
+    for result in results {
+        let cpp = result.cpp + result.cpp2;
+        remittance.income_tax = utils::round(remittance.income_tax + result.tax);
+        remittance.cpp = utils::round(remittance.cpp + 2.0 * cpp);
+        remittance.ei = utils::round(
+            remittance.ei + result.ei + other_deductions::employer_EI(result.ei, EiReductionCategory::Standard),
+        );
+    }
+    remittance
+}
+
+/** Remittances for a year totalled by calendar quarter, for employers who remit quarterly rather than each pay period.
+*
+*   Each pay date's remittance (see `employer_remittance`) goes to the quarter of its month: January to March, April to June, July to September and October to December. Pay dates are expected to be in one calendar year, such as those of `pay_calendar::pay_calendar`.
+*/
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct RemittanceAccumulator {
+    quarters: [Remittance; 4],
+}
+
+impl RemittanceAccumulator {
+    /// Adds the results of every employee paid on the pay date to the quarter of the pay date.
+    pub fn add(&mut self, pay_date: NaiveDate, results: &[PayrollResult]) {
+        let remittance = employer_remittance(results);
+        let quarter = &mut self.quarters[(pay_date.month() as usize - 1) / 3];
+        quarter.income_tax = utils::round(quarter.income_tax + remittance.income_tax);
+        quarter.cpp = utils::round(quarter.cpp + remittance.cpp);
+        quarter.ei = utils::round(quarter.ei + remittance.ei);
+    }
+
+    /// The remittances for the first to fourth quarters
+    pub fn quarters(&self) -> &[Remittance; 4] {
+        &self.quarters
+    }
+
+    /// The remittances for the year, the sum of the four quarters
+    pub fn annual(&self) -> Remittance {
+        let sum = |amount: fn(&Remittance) -> f64| utils::round(self.quarters.iter().map(amount).sum());
+        Remittance {
+            income_tax: sum(|quarter| quarter.income_tax),
+            cpp: sum(|quarter| quarter.cpp),
+            ei: sum(|quarter| quarter.ei),
+        }
+    }
+}
+
+/** Calculate the deductions for one pay period.
+*
+*   Returns `PayrollError::NonFiniteInput` if any amount is NaN or infinite, rather than letting it flow into the tax, and `PayrollError::InvalidPayPeriods` for an irregular pay frequency without a positive number of payments. When the deductions leave no annual taxable income (A is zero or negative), the tax is only the additional tax (L); CPP and EI are still deducted on the earnings.
+*
+*   In Quebec, QPP contributions are deducted in place of CPP, with K2Q and F5Q in place of K2 and F5, and QPIP premiums are deducted with EI at the Quebec rate.
+*/
+#[allow(non_snake_case)]
+pub fn calculate(ctx: &PayrollContext, input: &PayrollInput) -> Result<PayrollResult, PayrollError> {
+    check_finite(input)?;
+
+    let P = ctx.periods_per_year();
+    if P <= 0 {
+        return Err(PayrollError::InvalidPayPeriods(P));
+    }
+    if let Some(Td1Change { periods_remaining: PR, .. }) = input.td1_change {
+        if PR < 1 || PR > P {
+            return Err(PayrollError::InvalidPeriodsRemaining { P, PR });
+        }
+    }
+    let I = input.gross();
+    let PI = input.pensionable_earnings();
+
+    let (C, C2, EI) = match ctx.payee_type {
+        PayeeType::Pensioner => (0.0, 0.0, 0.0),
+        PayeeType::Employee if input.cpp_stopped() => {
+            (0.0, 0.0, other_deductions::EI(input.ytd_ei, input.insurable_earnings(), ctx.province, ctx.year))
+        }
+        PayeeType::Employee => {
+            let PM = input.cpp_contribution_months(ctx.year.year);
+            let W = other_deductions::W(input.ytd_pensionable, ctx.year.ympe, PM);
+            (
+                pension_contributions(ctx, PM, input.ytd_cpp, PI),
+                other_deductions::C2(PM, input.ytd_cpp2, input.ytd_pensionable, PI, W, ctx.year),
+                other_deductions::EI(input.ytd_ei, input.insurable_earnings(), ctx.province, ctx.year),
+            )
+        }
+    };
+    let QPIP = qpip(ctx, input.ytd_qpip, input.insurable_earnings());
+
+    let F5 = additional_contributions(ctx, C, C2);
+    let F5A = if PI > 0.0 { federal_income_tax::F5A(F5, PI, 0.0) } else { 0.0 };
+
+    let HD = basic_personal_income::prescribed_zone_deduction(input.prescribed_zone, input.prescribed_zone_days);
+    let annual = basic_personal_income::A(
+        P,
+        I,
+        input.pre_tax_deductions(),
+        input.support_payments,
+        F5A,
+        input.union_dues,
+        HD,
+        input.annual_deductions,
+    );
+    let A = annual.annual_taxable_income;
+
+    // With no annual taxable income there is no federal or provincial tax, only the additional tax (L)
+    let (T1, T2, T) = if annual.tax_floored_to_L || A <= 0.0 {
+        provincial_income_tax::basic_amount(ctx.province, ctx.year)?;
+        (0.0, 0.0, utils::round(annual.tax(0.0, input.additional_tax)))
+    } else {
+        let T1 = federal_tax(ctx, input, A, C, EI);
+        let T2 = provincial_tax(ctx, input, A, C, EI)?;
+        debug_assert!(ctx.residency == Residency::Canada || T2 == 0.0);
+        (T1, T2, income_tax::T_rounded(T1, T2, P, input.additional_tax, input.rounding))
+    };
+
+    let net = input.cash_income
+        - C
+        - C2
+        - EI
+        - QPIP
+        - T
+        - input.pre_tax_deductions()
+        - input.support_payments
+        - input.union_dues
+        - input.post_tax_deductions();
+
+    Ok(PayrollResult {
+        gross: utils::round(I),
+        cpp: C,
+        cpp2: C2,
+        ei: EI,
+        qpip: QPIP,
+        annual_taxable_income: A,
+        basic_personal_amount: basic_personal_income::BPAF(A, HD, ctx.year)?,
+        federal_tax: T1,
+        provincial_tax: T2,
+        tax: T,
+        net: utils::round(net),
+    })
+}
+
+/** Calculate the deductions for the same pay in each of several provinces, such as to compare net pay.
+*
+*   Each province is calculated with `ctx` in that province (see `calculate`), in the order given. A province that cannot be calculated, such as one without tables for the year, has its error in place of a result rather than failing the others.
+*
+* Given:
+*
+*   input: The employee's earnings and claims for the pay period
+*
+*   provinces: The provinces or territories of employment to compare
+*
+*   ctx: The settings for the payroll run; its province is ignored
+*/
+pub fn compare_provinces(
+    input: &PayrollInput,
+    provinces: &[Province],
+    ctx: &PayrollContext,
+) -> Vec<(Province, Result<PayrollResult, PayrollError>)> {
+    provinces
+        .iter()
+        .map(|&province| (province, calculate(&PayrollContext { province, ..ctx.clone() }, input)))
+        .collect()
+}
+
+/// Deductions for a year projected from a single pay period; see `project_annual`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnnualProjection {
+    /// T1: Annual federal tax deduction
+    pub federal_tax: f64,
+    /// T2: Annual provincial or territorial tax deduction
+    pub provincial_tax: f64,
+    /// Tax deducted over the year, including the additional tax (L)
+    pub tax: f64,
+    /// CPP contributions, up to the annual maximum
+    pub cpp: f64,
+    /// Second additional CPP contributions, up to the annual maximum
+    pub cpp2: f64,
+    /// EI premiums, up to the annual maximum
+    pub ei: f64,
+    /// QPIP premiums, up to the annual maximum
+    pub qpip: f64,
+}
+
+/** Project the deductions for the year from one representative pay period, such as for an employee's pay stub.
+*
+*   The federal and provincial tax (T1 and T2) are already annual; the other amounts are the pay period's times P, with CPP (QPP in Quebec), CPP2, EI and QPIP limited to the annual maximums for the year. This is an estimate that assumes every pay period is the same, not a calculation of the year.
+*
+* Given:
+*
+*   period_result: The deductions for a representative pay period
+*
+*   ctx: The settings for the payroll run the pay period was calculated with
+*/
+pub fn project_annual(period_result: &PayrollResult, ctx: &PayrollContext) -> AnnualProjection {
+    let periods = ctx.periods_per_year() as f64;
+    let year = ctx.year;
+    let (cpp_max, ei_max) = match ctx.province {
+        Province::Quebec => (year.qpp_max_contributions, year.ei_quebec_max_contributions),
+        _ => (year.cpp_total_max_contributions, year.ei_max_contributions),
+    };
+    let cpp2_max = year.cpp_second_additional_rate * (year.yampe - year.ympe);
+    AnnualProjection {
+        federal_tax: period_result.federal_tax,
+        provincial_tax: period_result.provincial_tax,
+        tax: utils::round(period_result.tax * periods),
+        cpp: utils::round((period_result.cpp * periods).min(cpp_max)),
+        cpp2: utils::round((period_result.cpp2 * periods).min(cpp2_max)),
+        ei: utils::round((period_result.ei * periods).min(ei_max)),
+        qpip: utils::round((period_result.qpip * periods).min(year.qpip_max_contributions)),
+    }
+}
+
+/** Effective marginal deduction rate: the share of the next dollar of earnings deducted for tax, CPP and EI
+*
+*   The federal and provincial or territorial marginal tax rates (for Quebec, the federal rate less the Quebec abatement and the Quebec rate), plus the CPP and EI rates when an employee has not yet reached the annual maximums. For Quebec, the QPP, Quebec EI and QPIP rates until their maximums. Surtaxes, tax reductions and the second additional CPP contributions are not included.
+*
+*   Returns `PayrollError::UnsupportedProvince` for a province or territory without tables for the year.
+*
+* Given:
+*
+*   a: Annual taxable income
+*
+*   ytd_cpp: Year-to-date CPP contributions with the employer
+*
+*   ytd_ei: Year-to-date EI premiums with the employer
+*
+*   ytd_qpip: Year-to-date QPIP premiums with the employer, for Quebec
+*
+*   ctx: The settings for the payroll run
+*/
+pub fn marginal_deduction_rate(a: f64, ytd_cpp: f64, ytd_ei: f64, ytd_qpip: f64, ctx: &PayrollContext) -> Result<f64, PayrollError> {
+    let federal = federal_income_tax::marginal_rate(a, ctx.year);
+    let tax = match ctx.province {
+        Province::Quebec => federal * (1.0 - v2025::QUEBEC_ABATEMENT_RATE) + year::bracket(quebec::brackets(ctx.year)?, a).rate,
+        _ => federal + year::bracket(provincial_income_tax::brackets(ctx.province, ctx.year)?, a).rate,
+    };
+
+    let contributions = match ctx.payee_type {
+        PayeeType::Pensioner => 0.0,
+        PayeeType::Employee => {
+            let (cpp_rate, cpp_max, ei_rate, ei_max) = match ctx.province {
+                Province::Quebec => (
+                    ctx.year.qpp_rate,
+                    ctx.year.qpp_max_contributions,
+                    ctx.year.ei_quebec_rate,
+                    ctx.year.ei_quebec_max_contributions,
+                ),
+                _ => (
+                    ctx.year.cpp_base_rate + ctx.year.cpp_first_additional_rate,
+                    ctx.year.cpp_total_max_contributions,
+                    ctx.year.ei_rate,
+                    ctx.year.ei_max_contributions,
+                ),
+            };
+            let cpp = if ytd_cpp < cpp_max { cpp_rate } else { 0.0 };
+            let ei = if ytd_ei < ei_max { ei_rate } else { 0.0 };
+            let qpip = if ctx.province == Province::Quebec && ytd_qpip < ctx.year.qpip_max_contributions {
+                ctx.year.qpip_rate
+            } else {
+                0.0
+            };
+            cpp + ei + qpip
+        }
+    };
+
+    Ok(tax + contributions)
+}
+
+/** Average federal and provincial or territorial tax rate on a salary: the annual tax over the annual gross
+*
+*   For an employee paid the salary evenly over the pay periods of `ctx`, claiming only the basic personal amounts, with no other deductions; the annual tax is T1 + T2 from `calculate`. A salary of zero has a rate of zero.
+*
+*   Returns `PayrollError::UnsupportedProvince` when there are no tables for the province for the year.
+*
+* Given:
+*
+*   annual_gross: The annual salary
+*
+*   ctx: The settings for the payroll run
+*/
+pub fn average_tax_rate(annual_gross: f64, ctx: &PayrollContext) -> Result<f64, PayrollError> {
+    if annual_gross <= 0.0 {
+        return Ok(0.0);
+    }
+    let input = PayrollInput {
+        cash_income: utils::round(annual_gross / ctx.periods_per_year() as f64),
+        taxable_benefits: 0.0,
+        non_insurable_benefits: 0.0,
+        retirement_contributions: 0.0,
+        voluntary_deductions: Vec::new(),
+        support_payments: 0.0,
+        union_dues: 0.0,
+        prescribed_zone: ZoneType::NotPrescribed,
+        prescribed_zone_days: 0,
+        labour_sponsored_shares: 0.0,
+        annual_deductions: 0.0,
+        additional_tax: 0.0,
+        federal_claim: ctx.year.federal_basic_personal_amount,
+        provincial_claim: None,
+        other_federal_credits: 0.0,
+        other_provincial_credits: 0.0,
+        dependents: Dependents::default(),
+        td1_change: None,
+        contribution_months: 12,
+        pay_date: None,
+        cpt30_election: None,
+        ytd_cpp: 0.0,
+        ytd_cpp2: 0.0,
+        ytd_ei: 0.0,
+        ytd_qpip: 0.0,
+        ytd_pensionable: 0.0,
+        overrides: FactorOverrides::default(),
+        rounding: RoundingStage::default(),
+    };
+    let result = calculate(ctx, &input)?;
+    Ok((result.federal_tax + result.provincial_tax) / annual_gross)
+}
+
+/** Annual gross income below which no federal tax (T1) is deducted
+*
+*   For an employee paid evenly over the year, claiming only the basic personal amount, with no other deductions or credits. T3 is zero where the lowest federal rate on A equals the credits K1, K2 and K4; for an employee, A is the gross less the first additional CPP contributions (F5A), and K2 grows with the gross, so the equation is solved for the gross. In Quebec, the QPP, Quebec EI and QPIP rates are used, with K2Q for K2. A pensioner has only K1, and the threshold is the basic personal amount.
+*
+* Given:
+*
+*   ctx: The settings for the payroll run
+*/
+#[allow(non_snake_case)]
+pub fn federal_tax_free_threshold(ctx: &PayrollContext) -> f64 {
+    let TC = ctx.year.federal_basic_personal_amount;
+    if ctx.payee_type == PayeeType::Pensioner {
+        return TC;
+    }
+
+    let CEA = federal_income_tax::canada_employment_amount(ctx.year);
+    let exemption = ctx.year.cpp_basic_exemption;
+    let (base, first_additional, insurance_rate) = match ctx.province {
+        Province::Quebec => (
+            ctx.year.qpp_base_rate,
+            ctx.year.qpp_rate - ctx.year.qpp_base_rate,
+            ctx.year.ei_quebec_rate + ctx.year.qpip_rate,
+        ),
+        _ => (ctx.year.cpp_base_rate, ctx.year.cpp_first_additional_rate, ctx.year.ei_rate),
+    };
+
+    // A = G − f × (G − exemption) and A = TC + CEA + base × (G − exemption) + (EI + QPIP rate) × G, for the gross G
+    let G = (TC + CEA - (base + first_additional) * exemption) / (1.0 - base - first_additional - insurance_rate);
+    utils::round(G)
+}
+
+/** Find the cash income for the pay period that leaves a target net pay after all deductions, such as to pay a net signing bonus
+*
+*   The cash income in `input` is replaced; everything else is used as given. Searches by bisection for the smallest cash income, to the cent, whose net pay from `calculate` is at least the target, so the net pay is within a cent of it.
+*
+*   Returns `PayrollError::NonFiniteInput` or `PayrollError::NegativeInput` for a target that is not a finite, non-negative amount.
+*
+* Given:
+*
+*   target_net: The net pay wanted for the pay period
+*
+*   ctx: The settings for the payroll run
+*
+*   input: The employee's earnings and claims for the pay period
+*/
+pub fn gross_up(target_net: f64, ctx: &PayrollContext, input: &PayrollInput) -> Result<f64, PayrollError> {
+    if !target_net.is_finite() {
+        return Err(PayrollError::NonFiniteInput("target_net"));
+    }
+    if target_net < 0.0 {
+        return Err(PayrollError::NegativeInput("target_net"));
+    }
+
+    let net = |cents: i64| -> Result<f64, PayrollError> {
+        Ok(calculate(ctx, &PayrollInput { cash_income: cents as f64 / 100.0, ..input.clone() })?.net)
+    };
+
+    // Net pay is never more than the cash income, so the answer is at least the target
+    let mut lower = (target_net * 100.0).round() as i64;
+    if net(lower)? >= target_net {
+        return Ok(lower as f64 / 100.0);
+    }
+    let mut upper = lower.max(100) * 2;
+    while net(upper)? < target_net {
+        lower = upper;
+        upper *= 2;
+    }
+    while upper - lower > 1 {
+        let middle = (lower + upper) / 2;
+        if net(middle)? < target_net {
+            lower = middle;
+        } else {
+            upper = middle;
+        }
+    }
+    Ok(upper as f64 / 100.0)
+}
+
+/** Calculate the deductions for one pay period, listing each factor in the order it is calculated, such as to compare with the CRA's calculation
+*
+*   The factors are A, K1, K2, K3, K4, T3, T1, K1P, K2P, T4, T2 and T, named as in the CRA formulas; T1, T2 and T are those of `calculate`. For Quebec, K1P is the Quebec personal credit and T4 the Quebec tax.
+*
+* Given:
+*
+*   input: The employee's earnings and claims for the pay period
+*
+*   ctx: The settings for the payroll run
+*/
+pub fn explain(input: &PayrollInput, ctx: &PayrollContext) -> Result<Vec<(String, f64)>, PayrollError> {
+    Ok(trace(ctx, input)?.1)
+}
+
+// The result of `calculate`, and the factors of `explain`
+#[allow(non_snake_case)]
+fn trace(ctx: &PayrollContext, input: &PayrollInput) -> Result<(PayrollResult, Vec<(String, f64)>), PayrollError> {
+    let result = calculate(ctx, input)?;
+    let (A, C, EI) = (result.annual_taxable_income, result.cpp, result.ei);
+    let (credits, T3, _) = federal_factors(ctx, input, A, C, EI);
+    let provincial = provincial_factors(ctx, input, A, C, EI)?;
+
+    let factors = [
+        ("A", A),
+        ("K1", credits.K1),
+        ("K2", credits.K2),
+        ("K3", credits.K3),
+        ("K4", credits.K4),
+        ("T3", T3),
+        ("T1", result.federal_tax),
+        ("K1P", provincial.K1P),
+        ("K2P", provincial.K2P),
+        ("T4", provincial.T4),
+        ("T2", result.provincial_tax),
+        ("T", result.tax),
+    ]
+    .iter()
+    .map(|(name, value)| (name.to_string(), *value))
+    .collect();
+    Ok((result, factors))
+}
+
+/// Deductions on a retroactive pay increase, in addition to those on the regular pay.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetroactivePay {
+    /// Incremental federal and provincial tax on the retroactive pay
+    pub tax: f64,
+    /// Additional CPP contributions; retroactive pay is pensionable
+    pub cpp: f64,
+    /// Additional EI premiums; retroactive pay is insurable
+    pub ei: f64,
+}
+
+/** Calculate the deductions on a retroactive pay increase paid with the regular pay.
+*
+*   The retroactive pay is taxed as a non-periodic payment, with the bonus method: the tax is the annual tax on the annual taxable income of the regular pay (A, as `calculate` derives it from `input`) plus the retroactive pay, less the annual tax on the annual taxable income alone. The CPP and EI are the additional contributions and premiums on the pay period's earnings including the retroactive pay, up to the annual maximums.
+*
+* Given:
+*
+*   ctx: The settings for the payroll run
+*
+*   input: The employee's regular earnings and claims for the pay period
+*
+*   retro_amount: The retroactive pay increase
+*/
+#[allow(non_snake_case)]
+pub fn retroactive_pay_tax(ctx: &PayrollContext, input: &PayrollInput, retro_amount: f64) -> Result<RetroactivePay, PayrollError> {
+    if !retro_amount.is_finite() {
+        return Err(PayrollError::NonFiniteInput("retro_amount"));
+    }
+
+    let regular = calculate(ctx, input)?;
+    let tax = tax_on_bonus(ctx, input, &regular, retro_amount, 0.0)?;
+
+    let PM = input.cpp_contribution_months(ctx.year.year);
+    let PI = input.pensionable_earnings();
+    let IE = input.insurable_earnings();
+
+    let (C, C_with_retro, EI, EI_with_retro) = match ctx.payee_type {
+        PayeeType::Pensioner => (0.0, 0.0, 0.0, 0.0),
+        PayeeType::Employee if input.cpp_stopped() => (
+            0.0,
+            0.0,
+            other_deductions::EI(input.ytd_ei, IE, ctx.province, ctx.year),
+            other_deductions::EI(input.ytd_ei, IE + retro_amount, ctx.province, ctx.year),
+        ),
+        PayeeType::Employee => (
+            pension_contributions(ctx, PM, input.ytd_cpp, PI),
+            pension_contributions(ctx, PM, input.ytd_cpp, PI + retro_amount),
+            other_deductions::EI(input.ytd_ei, IE, ctx.province, ctx.year),
+            other_deductions::EI(input.ytd_ei, IE + retro_amount, ctx.province, ctx.year),
+        ),
+    };
+
+    Ok(RetroactivePay {
+        tax,
+        cpp: utils::round(C_with_retro - C),
+        ei: utils::round(EI_with_retro - EI),
+    })
+}
+
+/** Calculate the tax on a bonus paid with the regular pay, using the bonus method.
+*
+*   The tax on the bonus is the annual tax on the annual taxable income from the regular pay plus the bonus less F5B (the additional CPP or QPP contributions deducted from it), less the annual tax on the regular pay alone. Unlike `CumulativeAverage`, the year-to-date earnings are not averaged.
+*
+* Given:
+*
+*   ctx: The settings for the payroll run
+*
+*   input: The employee's regular earnings and claims for the pay period
+*
+*   bonus: The bonus paid in the pay period
+*/
+#[allow(non_snake_case)]
+pub fn bonus_method_tax(ctx: &PayrollContext, input: &PayrollInput, bonus: f64) -> Result<f64, PayrollError> {
+    if !bonus.is_finite() {
+        return Err(PayrollError::NonFiniteInput("bonus"));
+    }
+
+    let regular = calculate(ctx, input)?;
+    tax_on_bonus(ctx, input, &regular, bonus, 0.0)
+}
+
+/// The bonus method's tax on a bonus, less F3 (the part of it contributed to an RRSP), reusing the annual tax (T1 and T2) already calculated on the regular pay.
+#[allow(non_snake_case)]
+fn tax_on_bonus(ctx: &PayrollContext, input: &PayrollInput, regular: &PayrollResult, bonus: f64, F3: f64) -> Result<f64, PayrollError> {
+    let (C, EI) = (regular.cpp, regular.ei);
+
+    // F5B: the part of the additional contributions on the pay period's earnings and the bonus that is on the bonus
+    let PI = input.pensionable_earnings() + bonus;
+    let (C_with_bonus, C2_with_bonus) = match ctx.payee_type {
+        PayeeType::Employee if !input.cpp_stopped() => {
+            let PM = input.cpp_contribution_months(ctx.year.year);
+            let W = other_deductions::W(input.ytd_pensionable, ctx.year.ympe, PM);
+            (
+                pension_contributions(ctx, PM, input.ytd_cpp, PI),
+                other_deductions::C2(PM, input.ytd_cpp2, input.ytd_pensionable, PI, W, ctx.year),
+            )
+        }
+        _ => (0.0, 0.0),
+    };
+    let F5 = additional_contributions(ctx, C_with_bonus, C2_with_bonus);
+    let F5B = if PI > 0.0 { utils::round(F5 - federal_income_tax::F5A(F5, PI, bonus)) } else { 0.0 };
+    let A = utils::round(regular.annual_taxable_income + bonus - F3 - F5B);
+
+    let T1_with_bonus = federal_tax(ctx, input, A, C, EI);
+    let T2_with_bonus = provincial_tax(ctx, input, A, C, EI)?;
+
+    Ok(utils::round(utils::floor_zero(T1_with_bonus + T2_with_bonus - regular.federal_tax - regular.provincial_tax)))
+}
+
+/** Calculate the tax on a bonus when the employee directs all or part of it to an RRSP.
+*
+*   The RRSP contribution is deducted at source (F3 for the bonus, F4 once it is a year-to-date non-periodic payment), so no tax is withheld on it; the rest of the bonus is taxed as in `bonus_method_tax`. The whole bonus is still pensionable, so F5B is the additional CPP contributions on all of it. A contribution larger than the bonus leaves no tax on the bonus.
+*
+* Given:
+*
+*   bonus: The bonus paid in the pay period
+*
+*   rrsp_from_bonus: F3: The part of the bonus contributed to an RRSP
+*
+*   ctx: The settings for the payroll run
+*
+*   input: The employee's regular earnings and claims for the pay period
+*/
+pub fn bonus_tax_with_rrsp(bonus: f64, rrsp_from_bonus: f64, ctx: &PayrollContext, input: &PayrollInput) -> Result<f64, PayrollError> {
+    if !bonus.is_finite() {
+        return Err(PayrollError::NonFiniteInput("bonus"));
+    }
+    if !rrsp_from_bonus.is_finite() {
+        return Err(PayrollError::NonFiniteInput("rrsp_from_bonus"));
+    }
+
+    let regular = calculate(ctx, input)?;
+    tax_on_bonus(ctx, input, &regular, bonus, rrsp_from_bonus.min(bonus))
+}
+
+/// Deductions on accrued vacation pay paid out on separation, in addition to those on the final regular pay.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VacationPayout {
+    /// Tax on the vacation pay, using the bonus method
+    pub tax: f64,
+    /// Additional CPP contributions; vacation pay is pensionable
+    pub cpp: f64,
+    /// Additional EI premiums; vacation pay is insurable
+    pub ei: f64,
+}
+
+/** Calculate the deductions on accrued vacation pay paid as a lump sum when the employee leaves.
+*
+*   Vacation pay when vacation is not taken is a non-periodic payment (factor B), so it is taxed with the bonus method (see `bonus_method_tax`); vacation pay for vacation taken is part of the regular pay (factor I) instead. The CPP and EI are the additional contributions and premiums on the final pay period's earnings including the vacation pay, up to the annual maximums.
+*
+* Given:
+*
+*   ctx: The settings for the payroll run
+*
+*   input: The employee's earnings and claims for the final pay period
+*
+*   accrued_vacation: The vacation pay paid out
+*/
+#[allow(non_snake_case)]
+pub fn vacation_payout_tax(ctx: &PayrollContext, input: &PayrollInput, accrued_vacation: f64) -> Result<VacationPayout, PayrollError> {
+    if !accrued_vacation.is_finite() {
+        return Err(PayrollError::NonFiniteInput("accrued_vacation"));
+    }
+
+    let regular = calculate(ctx, input)?;
+    let tax = tax_on_bonus(ctx, input, &regular, accrued_vacation, 0.0)?;
+
+    let PM = input.cpp_contribution_months(ctx.year.year);
+    let PI = input.pensionable_earnings();
+    let IE = input.insurable_earnings();
+
+    let (cpp, ei) = match ctx.payee_type {
+        PayeeType::Pensioner => (0.0, 0.0),
+        PayeeType::Employee if input.cpp_stopped() => (
+            0.0,
+            other_deductions::EI(input.ytd_ei, IE + accrued_vacation, ctx.province, ctx.year)
+                - other_deductions::EI(input.ytd_ei, IE, ctx.province, ctx.year),
+        ),
+        PayeeType::Employee => (
+            pension_contributions(ctx, PM, input.ytd_cpp, PI + accrued_vacation)
+                - pension_contributions(ctx, PM, input.ytd_cpp, PI),
+            other_deductions::EI(input.ytd_ei, IE + accrued_vacation, ctx.province, ctx.year)
+                - other_deductions::EI(input.ytd_ei, IE, ctx.province, ctx.year),
+        ),
+    };
+
+    Ok(VacationPayout { tax, cpp: utils::round(cpp), ei: utils::round(ei) })
+}
+
+/// Deductions on an employee's final payment, by component.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TerminationResult {
+    /// Deductions on the final regular pay
+    pub regular: PayrollResult,
+    /// Deductions on the accrued vacation pay, in addition to those on the regular pay
+    pub vacation: VacationPayout,
+    /// Flat lump-sum withholding on the severance pay
+    pub severance_tax: f64,
+    /// Tax on all three components
+    pub tax: f64,
+    /// The final payment less every deduction
+    pub net: f64,
+}
+
+/** Calculate the deductions on a terminated employee's final payment of regular pay, accrued vacation pay and severance pay.
+*
+*   The regular pay is taxed for the pay period as usual (see `calculate`) and the vacation pay with the bonus method (see `vacation_payout_tax`). Severance pay is a retiring allowance: it has no CPP or EI, and is withheld at the flat lump-sum rate for its amount (see `income_tax::lump_sum_withholding`) rather than annualized.
+*
+* Given:
+*
+*   regular: Regular pay for the final pay period, in place of `input.cash_income`
+*
+*   vacation: Accrued vacation pay paid out
+*
+*   severance: Severance pay, or another retiring allowance
+*
+*   ctx: The settings for the payroll run
+*
+*   input: The employee's earnings and claims for the final pay period
+*/
+pub fn termination_pay(
+    regular: f64,
+    vacation: f64,
+    severance: f64,
+    ctx: &PayrollContext,
+    input: &PayrollInput,
+) -> Result<TerminationResult, PayrollError> {
+    if !severance.is_finite() {
+        return Err(PayrollError::NonFiniteInput("severance"));
+    }
+    if severance < 0.0 {
+        return Err(PayrollError::NegativeInput("severance"));
+    }
+
+    let input = PayrollInput { cash_income: regular, ..input.clone() };
+    let regular = calculate(ctx, &input)?;
+    let vacation_payout = vacation_payout_tax(ctx, &input, vacation)?;
+    let severance_tax = income_tax::lump_sum_withholding(severance, ctx.province);
+
+    let net = regular.net + vacation - vacation_payout.tax - vacation_payout.cpp - vacation_payout.ei + severance - severance_tax;
+    Ok(TerminationResult {
+        tax: utils::round(regular.tax + vacation_payout.tax + severance_tax),
+        net: utils::round(net),
+        regular,
+        vacation: vacation_payout,
+        severance_tax,
+    })
+}
+
+/// Year-to-date amounts for one employee with one employer.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct YtdTotals {
+    /// Gross remuneration, which a clawback can make negative
+    pub gross: f64,
+    /// PI_YTD: Pensionable earnings
+    pub pensionable: f64,
+    /// Insurable earnings
+    pub insurable: f64,
+    /// D: CPP contributions
+    pub cpp: f64,
+    /// D2: Second additional CPP contributions
+    pub cpp2: f64,
+    /// D1: EI premiums
+    pub ei: f64,
+    /// Income tax deducted
+    pub tax: f64,
+}
+
+/// CPP and EI returned to the employee on a clawback, and the year-to-date amounts after it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Clawback {
+    pub cpp: f64,
+    pub cpp2: f64,
+    pub ei: f64,
+    pub ytd: YtdTotals,
+}
+
+/** Return the CPP and EI deducted on earnings that are clawed back, such as to recover an overpayment from an earlier pay period.
+*
+*   The CPP, second additional CPP and EI on the clawed-back earnings are returned, at the year's rates on the part of the earnings within each contribution's band (up to the YMPE, from the YMPE to the YAMPE, and up to the maximum insurable earnings), so earnings above a maximum return nothing; each is at most its year-to-date amount, which never goes below zero. Income tax is not returned through payroll; the employee recovers it on their return, so the year-to-date tax is unchanged and no tax is deducted on the period.
+*
+*   A positive adjustment is not a clawback and returns nothing.
+*
+* Given:
+*
+*   adjustment: The earnings clawed back, as a negative amount
+*
+*   ytd: The year-to-date amounts before the clawback
+*
+*   ctx: The settings for the payroll run
+*/
+pub fn apply_clawback(adjustment: f64, ytd: YtdTotals, ctx: &PayrollContext) -> Result<Clawback, PayrollError> {
+    if !adjustment.is_finite() {
+        return Err(PayrollError::NonFiniteInput("adjustment"));
+    }
+    let clawed_back = utils::floor_zero(-adjustment);
+    let pensionable = utils::floor_zero(ytd.pensionable - clawed_back);
+    let insurable = utils::floor_zero(ytd.insurable - clawed_back);
+
+    // The earnings below `to` and above `from` that are no longer in the year-to-date earnings
+    let removed = |before: f64, after: f64, from: f64, to: f64| utils::clamp(before, from, to) - utils::clamp(after, from, to);
+    let rates = other_deductions::cpp_rate_components(ctx.year);
+    let ei_rate = match ctx.province {
+        Province::Quebec => ctx.year.ei_quebec_rate,
+        _ => ctx.year.ei_rate,
+    };
+
+    let year = ctx.year;
+    let cpp = utils::round(utils::clamp(rates.employee * removed(ytd.pensionable, pensionable, 0.0, year.ympe), 0.0, ytd.cpp));
+    let cpp2 = utils::round(utils::clamp(
+        rates.second_additional * removed(ytd.pensionable, pensionable, year.ympe, year.yampe),
+        0.0,
+        ytd.cpp2,
+    ));
+    let ei = utils::round(utils::clamp(ei_rate * removed(ytd.insurable, insurable, 0.0, year.mie), 0.0, ytd.ei));
+
+    Ok(Clawback {
+        cpp,
+        cpp2,
+        ei,
+        ytd: YtdTotals {
+            gross: utils::round(ytd.gross - clawed_back),
+            pensionable: utils::round(pensionable),
+            insurable: utils::round(insurable),
+            cpp: utils::round(ytd.cpp - cpp),
+            cpp2: utils::round(ytd.cpp2 - cpp2),
+            ei: utils::round(ytd.ei - ei),
+            tax: ytd.tax,
+        },
+    })
+}
+
+/// Deductions for one employee in a payroll run, and the annual maximums the employee reached in the pay period.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatchResult {
+    pub result: PayrollResult,
+    /// The CPP contributions reached the annual maximum in this pay period; see `other_deductions::reached_cpp_max`
+    pub reached_cpp_max: bool,
+    /// The EI premiums reached the annual maximum in this pay period; see `other_deductions::reached_ei_max`
+    pub reached_ei_max: bool,
+}
+
+/// Everything needed to reproduce one calculation: what went in, each factor, and what came out.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AuditRecord {
+    /// Version of this crate that made the calculation
+    pub crate_version: &'static str,
+    /// The settings for the payroll run, including the year constants used
+    pub ctx: PayrollContext,
+    pub input: PayrollInput,
+    /// Each factor in the order it is calculated; see `explain`
+    pub factors: Vec<(String, f64)>,
+    pub result: PayrollResult,
+}
+
+/// Receives an `AuditRecord` for each calculation, such as to write it to a file or a database.
+pub trait AuditSink {
+    fn record(&mut self, record: AuditRecord);
+}
+
+/// An `AuditSink` that keeps the records in memory, in the order they were made.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct InMemoryAuditSink {
+    pub records: Vec<AuditRecord>,
+}
+
+impl AuditSink for InMemoryAuditSink {
+    fn record(&mut self, record: AuditRecord) {
+        self.records.push(record);
+    }
+}
+
+/// Calculates the pay periods of one payroll run, with the context shared by every employee.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PayrollCalculator {
+    pub ctx: PayrollContext,
+}
+
+impl PayrollCalculator {
+    pub fn new(ctx: PayrollContext) -> Self {
+        PayrollCalculator { ctx }
+    }
+
+    /// The deductions for one pay period; see `calculate`.
+    pub fn compute(&self, input: &PayrollInput) -> Result<PayrollResult, PayrollError> {
+        calculate(&self.ctx, input)
+    }
+
+    /** The deductions for one pay period, recording the calculation in the audit sink.
+    *
+    *   A calculation that fails is not recorded.
+    */
+    pub fn compute_audited(&self, input: &PayrollInput, sink: &mut dyn AuditSink) -> Result<PayrollResult, PayrollError> {
+        let (result, factors) = trace(&self.ctx, input)?;
+        sink.record(AuditRecord {
+            crate_version: env!("CARGO_PKG_VERSION"),
+            ctx: self.ctx.clone(),
+            input: input.clone(),
+            factors,
+            result: result.clone(),
+        });
+        Ok(result)
+    }
+
+    /** The deductions on the regular pay, and the tax on a bonus paid with it.
+    *
+    *   The bonus tax is calculated with the bonus method (see `bonus_method_tax`), from the annual tax on the regular pay rather than calculating it again.
+    */
+    pub fn compute_with_bonus(&self, regular: &PayrollInput, bonus: f64) -> Result<(PayrollResult, f64), PayrollError> {
+        if !bonus.is_finite() {
+            return Err(PayrollError::NonFiniteInput("bonus"));
+        }
+        let result = calculate(&self.ctx, regular)?;
+        let bonus_tax = tax_on_bonus(&self.ctx, regular, &result, bonus, 0.0)?;
+        Ok((result, bonus_tax))
+    }
+
+    /** The deductions for each employee in the payroll run, in order, flagging the employees who reached the CPP or EI maximum in the pay period.
+    *
+    *   Returns the first error if any employee's deductions cannot be calculated.
+    */
+    pub fn compute_batch(&self, inputs: &[PayrollInput]) -> Result<Vec<BatchResult>, PayrollError> {
+        inputs
+            .iter()
+            .map(|input| {
+                let result = calculate(&self.ctx, input)?;
+                Ok(BatchResult {
+                    reached_cpp_max: other_deductions::reached_cpp_max(
+                        input.cpp_contribution_months(self.ctx.year.year),
+                        input.ytd_cpp,
+                        result.cpp,
+                        self.ctx.year,
+                    ),
+                    reached_ei_max: other_deductions::reached_ei_max(input.ytd_ei, result.ei, self.ctx.province, self.ctx.year),
+                    result,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Contributions deducted in one pay period of a year worked in more than one province.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SegmentPeriod {
+    pub province: Province,
+    pub cpp: f64,
+    pub qpp: f64,
+    pub ei: f64,
+    pub qpip: f64,
+}
+
+/// Contributions for a year worked in more than one province, reconciled across Quebec and the rest of Canada.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SegmentedYear {
+    pub periods: Vec<SegmentPeriod>,
+    pub cpp: f64,
+    pub qpp: f64,
+    pub ei: f64,
+    pub qpip: f64,
+    /// K2R: Federal CPP/EI credit for an employee transferred out of Quebec, as of the first pay period after the transfer
+    pub transfer_credit: Option<f64>,
+}
+
+/** Calculate the contributions for a year worked in more than one province.
+*
+*   Each segment is a province and the number of consecutive months worked there, in order; the months may total at most 12, and the pay periods of a full year total P. The pay frequency and payee type are taken from `ctx`, whose province is ignored, and the per-period earnings and PM (less the months from a CPT30 election on) from `input`, whose year-to-date amounts are ignored.
+*
+*   QPP, QPIP and EI at the Quebec rate are deducted while in Quebec, and CPP and EI elsewhere. QPP already deducted counts toward the CPP maximum at the CPP rate (QPP × 0.0595 / 0.0640 in 2025), and the reverse, and EI at either rate counts toward the EI maximum, so the combined contributions never exceed the annual maximums.
+*
+*   Second additional contributions (CPP2 and QPP2) are not included.
+*/
+#[allow(non_snake_case)]
+pub fn calculate_segments(
+    ctx: &PayrollContext,
+    input: &PayrollInput,
+    segments: &[(Province, i64)]) -> Result<SegmentedYear, PayrollError> {
+    check_finite(input)?;
+    if segments.iter().any(|(_, months)| *months < 0) || segments.iter().map(|(_, months)| months).sum::<i64>() > 12 {
+        return Err(PayrollError::InvalidSegments);
+    }
+
+    let P = ctx.periods_per_year();
+    let PM = input.cpp_contribution_months(ctx.year.year);
+    let PI = input.pensionable_earnings();
+    let IE = input.insurable_earnings();
+    // The employee CPP rate, base and first additional
+    let cpp_rate = ctx.year.cpp_base_rate + ctx.year.cpp_first_additional_rate;
+    let mut year = SegmentedYear {
+        periods: Vec::new(),
+        cpp: 0.0,
+        qpp: 0.0,
+        ei: 0.0,
+        qpip: 0.0,
+        transfer_credit: None,
+    };
+
+    // Months worked before the segment, so each segment's pay periods run to the nearest period after its last month and the periods of a full year total P
+    let mut months_before: i64 = 0;
+    for &(province, months) in segments {
+        let first = (P * months_before + 6) / 12;
+        months_before += months;
+        for _ in first..(P * months_before + 6) / 12 {
+            let mut period = SegmentPeriod { province, cpp: 0.0, qpp: 0.0, ei: 0.0, qpip: 0.0 };
+
+            if ctx.payee_type == PayeeType::Employee {
+                if province == Province::Quebec {
+                    let D = year.qpp + year.cpp * (ctx.year.qpp_rate / cpp_rate);
+                    period.qpp = quebec::QPP(PM, D, PI, P, ctx.year);
+                    period.ei = quebec::EI(year.ei, IE, ctx.year);
+                    period.qpip = quebec::qpip(IE, year.qpip, ctx.year);
+                } else {
+                    let D = year.cpp + year.qpp * (cpp_rate / ctx.year.qpp_rate);
+                    period.cpp = other_deductions::C(PM, D, PI, P, ctx.year);
+                    period.ei = other_deductions::EI(year.ei, IE, province, ctx.year);
+
+                    if year.transfer_credit.is_none() && year.qpp > 0.0 {
+                        let PR = P - year.periods.len() as i64;
+                        year.transfer_credit =
+                            Some(federal_income_tax::K2R(PR, period.cpp, period.ei, year.qpp, year.ei, year.qpip, ctx.year));
+                    }
+                }
+            }
+
+            year.cpp = utils::round(year.cpp + period.cpp);
+            year.qpp = utils::round(year.qpp + period.qpp);
+            year.ei = utils::round(year.ei + period.ei);
+            year.qpip = utils::round(year.qpip + period.qpip);
+            year.periods.push(period);
+        }
+    }
+
+    Ok(year)
+}
+
+/** Check every input for problems, without calculating anything.
+*
+*   Unlike `calculate`, which stops at the first problem, returns all of them; an empty list means the input is valid.
+*/
+#[allow(non_snake_case)]
+pub fn validate(input: &PayrollInput, ctx: &PayrollContext) -> Vec<PayrollError> {
+    let mut errors: Vec<PayrollError> = Vec::new();
+
+    let P = ctx.periods_per_year();
+    if P <= 0 {
+        errors.push(PayrollError::InvalidPayPeriods(P));
+    }
+
+    for (name, amount) in amounts(input) {
+        if !amount.is_finite() {
+            errors.push(PayrollError::NonFiniteInput(name));
+        }
+    }
+    if input.voluntary_deductions.iter().any(|deduction| !deduction.amount.is_finite()) {
+        errors.push(PayrollError::NonFiniteInput("voluntary_deductions"));
+    }
+    if input.voluntary_deductions.iter().any(|deduction| deduction.amount < 0.0) {
+        errors.push(PayrollError::NegativeInput("voluntary_deductions"));
+    }
+
+    let non_negative = [
+        ("cash_income", input.cash_income),
+        ("taxable_benefits", input.taxable_benefits),
+        ("non_insurable_benefits", input.non_insurable_benefits),
+        ("labour_sponsored_shares", input.labour_sponsored_shares),
+        ("federal_claim", input.federal_claim),
+        ("provincial_claim", input.provincial_claim.unwrap_or(0.0)),
+        ("other_federal_credits", input.other_federal_credits),
+        ("other_provincial_credits", input.other_provincial_credits),
+    ];
+    for (name, amount) in non_negative {
+        if amount < 0.0 {
+            errors.push(PayrollError::NegativeInput(name));
+        }
+    }
+
+    if let Err(error) = provincial_income_tax::basic_amount(ctx.province, ctx.year) {
+        errors.push(error);
+    }
+
+    if let Some(Td1Change { periods_remaining: PR, .. }) = input.td1_change {
+        if PR < 1 || PR > P {
+            errors.push(PayrollError::InvalidPeriodsRemaining { P, PR });
+        }
+    }
+
+    let PM = input.contribution_months;
+    let minimum = match ctx.payee_type {
+        PayeeType::Employee => 1,
+        PayeeType::Pensioner => 0,
+    };
+    if !(minimum..=12).contains(&PM) {
+        errors.push(PayrollError::InvalidContributionMonths(PM));
+    }
+
+    errors
+}
+
+fn amounts(input: &PayrollInput) -> [(&'static str, f64); 25] {
+    [
+        ("cash_income", input.cash_income),
+        ("taxable_benefits", input.taxable_benefits),
+        ("non_insurable_benefits", input.non_insurable_benefits),
+        ("retirement_contributions", input.retirement_contributions),
+        ("support_payments", input.support_payments),
+        ("union_dues", input.union_dues),
+        ("labour_sponsored_shares", input.labour_sponsored_shares),
+        ("annual_deductions", input.annual_deductions),
+        ("additional_tax", input.additional_tax),
+        ("federal_claim", input.federal_claim),
+        ("provincial_claim", input.provincial_claim.unwrap_or(0.0)),
+        ("other_federal_credits", input.other_federal_credits),
+        ("other_provincial_credits", input.other_provincial_credits),
+        ("td1_change.previous_federal_claim", input.td1_change.map_or(0.0, |change| change.previous_federal_claim)),
+        ("td1_change.previous_provincial_claim", input.td1_change.map_or(0.0, |change| change.previous_provincial_claim)),
+        ("ytd_cpp", input.ytd_cpp),
+        ("ytd_cpp2", input.ytd_cpp2),
+        ("ytd_ei", input.ytd_ei),
+        ("ytd_qpip", input.ytd_qpip),
+        ("ytd_pensionable", input.ytd_pensionable),
+        ("overrides.k2", input.overrides.k2.unwrap_or(0.0)),
+        ("overrides.k2p", input.overrides.k2p.unwrap_or(0.0)),
+        ("overrides.k3", input.overrides.k3.unwrap_or(0.0)),
+        ("overrides.t3", input.overrides.t3.unwrap_or(0.0)),
+        ("overrides.t4", input.overrides.t4.unwrap_or(0.0)),
+    ]
+}
+
+pub(crate) fn check_finite(input: &PayrollInput) -> Result<(), PayrollError> {
+    if let Some((name, _)) = amounts(input).iter().find(|(_, amount)| !amount.is_finite()) {
+        return Err(PayrollError::NonFiniteInput(name));
+    }
+    if input.voluntary_deductions.iter().any(|deduction| !deduction.amount.is_finite()) {
+        return Err(PayrollError::NonFiniteInput("voluntary_deductions"));
+    }
+    Ok(())
+}
+
+/// C: CPP contributions for the pay period of an employee, or QPP contributions in Quebec.
+#[allow(non_snake_case)]
+pub(crate) fn pension_contributions(ctx: &PayrollContext, PM: i64, D: f64, PI: f64) -> f64 {
+    let P = ctx.periods_per_year();
+    match ctx.province {
+        Province::Quebec => quebec::QPP(PM, D, PI, P, ctx.year),
+        _ => other_deductions::C(PM, D, PI, P, ctx.year),
+    }
+}
+
+/// F5: The additional CPP contributions for the pay period, or F5Q for the additional QPP contributions in Quebec.
+#[allow(non_snake_case)]
+pub(crate) fn additional_contributions(ctx: &PayrollContext, C: f64, C2: f64) -> f64 {
+    match ctx.province {
+        Province::Quebec => federal_income_tax::F5Q(C, C2, ctx.year),
+        _ => federal_income_tax::F5(C, C2, ctx.year),
+    }
+}
+
+/// QPIP premiums for the pay period of an employee working in Quebec; there are none elsewhere, or for a pensioner.
+#[allow(non_snake_case)]
+pub(crate) fn qpip(ctx: &PayrollContext, DQPIP: f64, IE: f64) -> f64 {
+    match (ctx.province, ctx.payee_type) {
+        (Province::Quebec, PayeeType::Employee) => quebec::qpip(IE, DQPIP, ctx.year),
+        _ => 0.0,
+    }
+}
+
+/// Whether the employee has contributed the maximum CPP (or QPP in Quebec), and separately the maximum EI, for the year with the employer, including this pay period, so K2 and K2P use each maximum once it is reached.
+#[allow(non_snake_case)]
+fn max_reached(ctx: &PayrollContext, input: &PayrollInput, C: f64, EI: f64) -> (bool, bool) {
+    if ctx.payee_type == PayeeType::Pensioner {
+        return (false, false);
+    }
+    let (pension_max, ei_max) = match ctx.province {
+        Province::Quebec => (ctx.year.qpp_max_contributions, ctx.year.ei_quebec_max_contributions),
+        _ => (ctx.year.cpp_total_max_contributions, ctx.year.ei_max_contributions),
+    };
+    let cpp_max = utils::round(pension_max * (input.cpp_contribution_months(ctx.year.year) as f64 / 12.0));
+    (utils::round(input.ytd_cpp + C) >= cpp_max, utils::round(input.ytd_ei + EI) >= ei_max)
+}
+
+/// The federal tax credits, T3 and T1 for the annual taxable income; see `federal_tax`.
+#[allow(non_snake_case)]
+fn federal_factors(ctx: &PayrollContext, input: &PayrollInput, A: f64, C: f64, EI: f64) -> (FederalCredits, f64, f64) {
+    let P = ctx.periods_per_year();
+    let Bracket { rate: R, constant: K, .. } = *year::bracket(ctx.year.federal_brackets, A);
+    let credits = match input.td1_change {
+        Some(change) => FederalCredits::default().changed_claim(
+            P,
+            change.periods_remaining,
+            change.previous_federal_claim,
+            input.federal_claim,
+        ),
+        None => FederalCredits::default().claim(input.federal_claim),
+    };
+    let PM = input.cpp_contribution_months(ctx.year.year);
+    let (cpp_max_reached, ei_max_reached) = max_reached(ctx, input, C, EI);
+    let credits = match ctx.province {
+        Province::Quebec => {
+            let PPIP = qpip(ctx, input.ytd_qpip, input.insurable_earnings());
+            credits.quebec_contributions(P, PM, C, EI, PPIP, cpp_max_reached, ei_max_reached, ctx.year)
+        }
+        _ => credits.contributions(P, PM, C, EI, cpp_max_reached, ei_max_reached, ctx.year),
+    };
+    let mut credits = credits.other_credits(input.overrides.k3.unwrap_or(input.other_federal_credits));
+    if let Some(K2) = input.overrides.k2 {
+        credits.K2 = K2;
+    }
+    if ctx.payee_type == PayeeType::Employee {
+        credits = credits.employment_amount(A, federal_income_tax::canada_employment_amount(ctx.year));
+    }
+    let T3 = input.overrides.t3.unwrap_or_else(|| federal_income_tax::T3_credits(R, A, K, &credits));
+    let LCF = federal_income_tax::LCF_period(P, input.labour_sponsored_shares);
+    let T1 = match ctx.province {
+        Province::Quebec => federal_income_tax::T1_quebec(T3, P, LCF),
+        _ => federal_income_tax::T1(T3, P, LCF, ctx.residency == Residency::OutsideCanada),
+    };
+    (credits, T3, T1)
+}
+
+#[allow(non_snake_case)]
+pub(crate) fn federal_tax(ctx: &PayrollContext, input: &PayrollInput, A: f64, C: f64, EI: f64) -> f64 {
+    federal_factors(ctx, input, A, C, EI).2
+}
+
+/// The provincial or territorial factors for the annual taxable income; for Quebec, K1P is the Quebec personal credit and T4 the Quebec tax. All are zero for employees outside Canada.
+#[allow(non_snake_case)]
+struct ProvincialFactors {
+    K1P: f64,
+    K2P: f64,
+    T4: f64,
+    T2: f64,
+}
+
+#[allow(non_snake_case)]
+fn provincial_factors(ctx: &PayrollContext, input: &PayrollInput, A: f64, C: f64, EI: f64) -> Result<ProvincialFactors, PayrollError> {
+    // The 48% increase in the federal tax is in place of the provincial tax
+    if ctx.residency == Residency::OutsideCanada {
+        return Ok(ProvincialFactors { K1P: 0.0, K2P: 0.0, T4: 0.0, T2: 0.0 });
+    }
+
+    let TCP = match input.provincial_claim {
+        Some(TCP) => TCP,
+        None => provincial_income_tax::basic_amount(ctx.province, ctx.year)?,
+    };
+
+    if ctx.province == Province::Quebec {
+        let credits = quebec::QuebecCredits::default()
+            .claim(TCP, ctx.year)?
+            .other_credits(input.other_provincial_credits);
+        let T2 = quebec::provincial_tax(A, credits, ctx.year)?;
+        return Ok(ProvincialFactors { K1P: credits.K1, K2P: 0.0, T4: T2, T2 });
+    }
+
+    let P = ctx.periods_per_year();
+    let brackets = provincial_income_tax::brackets(ctx.province, ctx.year)?;
+    let Bracket { rate: V, constant: KP, .. } = *year::bracket(brackets, A);
+
+    let credits = ProvincialCredits::new(ctx.province, ctx.year);
+    let credits = match input.td1_change {
+        Some(change) => credits.changed_claim(
+            P,
+            change.periods_remaining,
+            change.previous_provincial_claim,
+            TCP,
+        ),
+        None => credits.claim(TCP),
+    };
+    let (cpp_max_reached, ei_max_reached) = max_reached(ctx, input, C, EI);
+    let mut credits = credits
+        .contributions(P, input.cpp_contribution_months(ctx.year.year), C, EI, cpp_max_reached, ei_max_reached, ctx.year)
+        .other_credits(input.other_provincial_credits);
+    if let Some(K2P) = input.overrides.k2p {
+        credits.K2P = K2P;
+    }
+    let T4 = input.overrides.t4.unwrap_or_else(|| provincial_income_tax::T4_credits(V, A, KP, &credits));
+
+    let V1 = provincial_income_tax::provincial_surtax(ctx.province, T4, ctx.year);
+    let V2 = provincial_income_tax::health_premium(ctx.province, A, ctx.year);
+    let S = provincial_income_tax::tax_reduction(ctx.province, T4, V1, A, input.dependents, ctx.year)?;
+
+    Ok(ProvincialFactors {
+        K1P: credits.K1P,
+        K2P: credits.K2P,
+        T4,
+        T2: provincial_income_tax::T2(T4, V1, V2, S, P, 0.0),
+    })
+}
+
+#[allow(non_snake_case)]
+pub(crate) fn provincial_tax(ctx: &PayrollContext, input: &PayrollInput, A: f64, C: f64, EI: f64) -> Result<f64, PayrollError> {
+    Ok(provincial_factors(ctx, input, A, C, EI)?.T2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures;
+
+    #[test]
+    fn test_calculate_rejects_nan_gross() {
+        let (ctx, input) = test_fixtures::ontario_biweekly_employee();
+        let input = PayrollInput { cash_income: f64::NAN, ..input };
+        assert_eq!(calculate(&ctx, &input), Err(PayrollError::NonFiniteInput("cash_income")));
+    }
+
+    #[test]
+    fn test_calculate_rejects_infinite_claim() {
+        let (ctx, input) = test_fixtures::alberta_semi_monthly_employee();
+        let input = PayrollInput { federal_claim: f64::INFINITY, ..input };
+        assert_eq!(calculate(&ctx, &input), Err(PayrollError::NonFiniteInput("federal_claim")));
+
+        let (ctx, input) = test_fixtures::alberta_semi_monthly_employee();
+
+        let input = PayrollInput { ytd_ei: f64::NEG_INFINITY, ..input };
+        assert_eq!(calculate(&ctx, &input), Err(PayrollError::NonFiniteInput("ytd_ei")));
+    }
+
+    #[test]
+    fn test_calculate_rejects_nan_k2p_override() {
+        let (ctx, input) = test_fixtures::ontario_biweekly_employee();
+        let input = PayrollInput { overrides: FactorOverrides { k2p: Some(f64::NAN), ..Default::default() }, ..input };
+        assert_eq!(calculate(&ctx, &input), Err(PayrollError::NonFiniteInput("overrides.k2p")));
+        assert!(validate(&input, &ctx).contains(&PayrollError::NonFiniteInput("overrides.k2p")));
+    }
+
+    #[test]
+    fn test_rrsp_over_income_leaves_only_additional_tax() {
+        let (ctx, input) = test_fixtures::ontario_biweekly_employee();
+        let regular = calculate(&ctx, &input).unwrap();
+        let input = PayrollInput { retirement_contributions: input.cash_income + 500.0, additional_tax: 25.0, ..input };
+
+        let result = calculate(&ctx, &input).unwrap();
+        assert!(result.annual_taxable_income < 0.0);
+        assert_eq!(result.federal_tax, 0.0);
+        assert_eq!(result.provincial_tax, 0.0);
+        assert_eq!(result.tax, 25.0);
+
+        assert!(result.cpp > 0.0 && result.ei > 0.0);
+        assert_eq!(result.cpp, regular.cpp);
+        assert_eq!(result.ei, regular.ei);
+    }
+
+    #[test]
+    fn test_labour_sponsored_credit_is_capped_at_750_a_year() {
+        let (ctx, input) = test_fixtures::alberta_semi_monthly_employee();
+        let without = calculate(&ctx, &input).unwrap();
+
+        // $200 twice a month is $4,800 a year; 15% would be $720
+        let under = calculate(&ctx, &PayrollInput { labour_sponsored_shares: 200.0, ..input.clone() }).unwrap();
+        assert_eq!(utils::round(without.federal_tax - under.federal_tax), 720.0);
+
+        // $300 twice a month would be $1,080
+        let over = calculate(&ctx, &PayrollInput { labour_sponsored_shares: 300.0, ..input }).unwrap();
+        assert_eq!(utils::round(without.federal_tax - over.federal_tax), 750.0);
+    }
+
+    #[test]
+    fn test_irregular_payment_is_annualized_over_the_assumed_payments() {
+        let (ctx, input) = test_fixtures::ontario_biweekly_employee();
+        let biweekly = calculate(&ctx, &input).unwrap();
+
+        let irregular = |assumed_periods| PayrollContext { pay_period: PayPeriod::Irregular { assumed_periods }, ..ctx.clone() };
+        assert_eq!(calculate(&irregular(26), &input).unwrap(), biweekly);
+
+        // The same payment expected only 10 times a year is less annual income, so less tax
+        let casual = calculate(&irregular(10), &input).unwrap();
+        assert!(casual.annual_taxable_income < biweekly.annual_taxable_income / 2.0);
+        assert!(casual.tax < biweekly.tax);
+
+        assert_eq!(calculate(&irregular(0), &input), Err(PayrollError::InvalidPayPeriods(0)));
+    }
+
+    #[test]
+    fn test_pre_tax_deduction_lowers_tax_and_post_tax_does_not() {
+        let (ctx, input) = test_fixtures::ontario_biweekly_employee();
+        let without = calculate(&ctx, &input).unwrap();
+        let deduction = |name: &str, amount, timing| VoluntaryDeduction { name: name.to_string(), amount, timing };
+        let input = PayrollInput {
+            voluntary_deductions: vec![
+                deduction("Group RRSP", 100.0, DeductionTiming::PreTax),
+                deduction("United Way", 50.0, DeductionTiming::PostTax),
+            ],
+            ..input
+        };
+
+        let with = calculate(&ctx, &input).unwrap();
+        assert!(with.tax < without.tax);
+        assert!(with.net < without.net);
+        assert_eq!(with.annual_taxable_income, utils::round(without.annual_taxable_income - 26.0 * 100.0));
+        assert_eq!(with.net, utils::round(without.net - 100.0 - 50.0 + (without.tax - with.tax)));
+
+        // A pre-tax voluntary deduction is taxed like a retirement contribution
+        let post_tax = vec![deduction("United Way", 50.0, DeductionTiming::PostTax)];
+        let rrsp = PayrollInput { retirement_contributions: 100.0, voluntary_deductions: post_tax.clone(), ..input.clone() };
+        assert_eq!(calculate(&ctx, &rrsp).unwrap(), with);
+
+        let post_tax_only = calculate(&ctx, &PayrollInput { voluntary_deductions: post_tax, ..input }).unwrap();
+        assert_eq!(post_tax_only.tax, without.tax);
+        assert_eq!(post_tax_only.net, utils::round(without.net - 50.0));
+    }
+
+    #[test]
+    fn test_provincial_claim_defaults_to_the_basic_amount() {
+        let (ctx, input) = test_fixtures::ontario_biweekly_employee();
+        let claimed = calculate(&ctx, &input).unwrap();
+        let default = calculate(&ctx, &PayrollInput { provincial_claim: None, ..input.clone() }).unwrap();
+        assert_eq!(default, claimed);
+
+        // Claiming zero leaves no personal credit, so more provincial tax is withheld
+        let zero = calculate(&ctx, &PayrollInput { provincial_claim: Some(0.0), ..input }).unwrap();
+        assert!(zero.provincial_tax > claimed.provincial_tax);
+        assert_eq!(zero.federal_tax, claimed.federal_tax);
+    }
+
+    #[test]
+    fn test_td1_change_at_period_13_prorates_the_increase() {
+        let (ctx, before) = test_fixtures::ontario_biweekly_employee();
+        let after = PayrollInput {
+            federal_claim: before.federal_claim + 5000.0,
+            provincial_claim: before.provincial_claim.map(|claim| claim + 5000.0),
+            td1_change: Some(Td1Change {
+                periods_remaining: 14,
+                previous_federal_claim: before.federal_claim,
+                previous_provincial_claim: v2025::ONTARIO_BASIC_AMT,
+            }),
+            ..before.clone()
+        };
+
+        let first_half = calculate(&ctx, &before).unwrap();
+        let second_half = calculate(&ctx, &after).unwrap();
+        let unprorated = calculate(&ctx, &PayrollInput { td1_change: None, ..after.clone() }).unwrap();
+
+        // The increase of 5,000 is credited over the 14 pay periods left, rather than all 26
+        assert!(second_half.tax < unprorated.tax && unprorated.tax < first_half.tax);
+        let federal_credit = 0.15 * 5000.0 * 26.0 / 14.0;
+        assert!((first_half.federal_tax - second_half.federal_tax - federal_credit).abs() < 0.01);
+
+        // Over the year, the federal tax withheld is the annual tax on the new claim
+        let withheld = (12.0 * first_half.federal_tax + 14.0 * second_half.federal_tax) / 26.0;
+        assert!((withheld - unprorated.federal_tax).abs() < 0.01);
+
+        let invalid = PayrollInput { td1_change: Some(Td1Change { periods_remaining: 27, ..after.td1_change.unwrap() }), ..after };
+        assert_eq!(calculate(&ctx, &invalid), Err(PayrollError::InvalidPeriodsRemaining { P: 26, PR: 27 }));
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_pipeline_rounding_stages_agree_within_a_cent() {
+        for (ctx, input) in test_fixtures::all() {
+            let once = calculate(&ctx, &input).unwrap();
+            let each = calculate(&ctx, &PayrollInput { rounding: RoundingStage::EachFactor, ..input.clone() }).unwrap();
+            let P = ctx.periods_per_year();
+
+            assert_eq!((each.federal_tax, each.provincial_tax), (once.federal_tax, once.provincial_tax));
+            assert_eq!(
+                utils::round(each.tax - once.tax),
+                income_tax::rounding_difference(once.federal_tax, once.provincial_tax, P, input.additional_tax)
+            );
+            assert!((each.tax - once.tax).abs() <= 0.01 + 1e-9, "{:?}", input);
+        }
+    }
+
+    #[test]
+    fn test_27_pay_period_year_lowers_the_tax_per_period() {
+        let (ctx, input) = test_fixtures::ontario_biweekly_employee();
+        let usual = ctx.clone().with_pay_calendar(NaiveDate::from_ymd_opt(2025, 1, 3).unwrap()).unwrap();
+        assert_eq!(usual.periods_per_year(), 26);
+        assert_eq!(usual.pay_periods, None);
+
+        // Paid every two weeks from January 1, the 27th pay date is December 31
+        let extra = ctx.clone().with_pay_calendar(NaiveDate::from_ymd_opt(2025, 1, 1).unwrap()).unwrap();
+        assert_eq!(extra.periods_per_year(), 27);
+
+        let tax_27 = calculate(&extra, &input).unwrap();
+        let tax_26 = calculate(&usual, &input).unwrap();
+        assert!(tax_27.tax > 0.0);
+        assert!(tax_27.annual_taxable_income > tax_26.annual_taxable_income);
+
+        // The same annual salary over 27 pay dates is less per pay period, and less tax per pay period
+        let salary = 26.0 * input.cash_income;
+        let per_period_27 = PayrollInput { cash_income: utils::round(salary / 27.0), ..input.clone() };
+        assert!(calculate(&extra, &per_period_27).unwrap().tax < tax_26.tax);
+    }
+
+    #[test]
+    fn test_diff_after_a_raise() {
+        let (ctx, input) = test_fixtures::ontario_biweekly_employee();
+        let before = calculate(&ctx, &input).unwrap();
+        let after = calculate(&ctx, &PayrollInput { cash_income: input.cash_income + 200.0, ..input }).unwrap();
+
+        let change = diff(&before, &after);
+        assert_eq!(change.gross, 200.0);
+        assert!(change.federal_tax > 0.0);
+        assert!(change.cpp > 0.0 && change.ei > 0.0);
+        assert_eq!(change.net, utils::round(200.0 - change.cpp - change.cpp2 - change.ei - change.tax));
+        assert!(change.net > 0.0 && change.net < 200.0);
+    }
+
+    #[test]
+    fn test_benefit_only_period_has_cpp_but_no_ei() {
+        let (ctx, input) = test_fixtures::ontario_biweekly_employee();
+        // Unpaid leave with a company car, not marked as non-insurable
+        let leave = PayrollInput { cash_income: 0.0, taxable_benefits: 500.0, ..input };
+        assert_eq!(leave.insurable_earnings(), 0.0);
+        assert_eq!(leave.pensionable_earnings(), 500.0);
+
+        let result = calculate(&ctx, &leave).unwrap();
+        assert_eq!(result.ei, 0.0);
+        assert_eq!(result.cpp, utils::round(0.0595 * (500.0 - 3500.0 / 26.0)));
+        assert_eq!(result.gross, 500.0);
+    }
+
+    #[test]
+    fn test_non_insurable_benefit_is_pensionable_but_not_insurable() {
+        let (ctx, input) = test_fixtures::ontario_biweekly_employee();
+        let before = calculate(&ctx, &input).unwrap();
+        let after = calculate(&ctx, &PayrollInput { taxable_benefits: 150.0, non_insurable_benefits: 150.0, ..input }).unwrap();
+
+        assert_eq!(after.gross, before.gross + 150.0);
+        assert!(after.cpp > before.cpp);
+        assert_eq!(after.ei, before.ei);
+        assert!(after.tax > before.tax);
+        assert_eq!(after.net, utils::round(before.net - (after.cpp - before.cpp) - (after.tax - before.tax)));
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_federal_overrides_change_tax_by_expected_delta() {
+        let (ctx, input) = test_fixtures::ontario_biweekly_employee();
+        let base = calculate(&ctx, &input).unwrap();
+
+        // T1 is T3 outside Quebec, with no labour-sponsored funds credit
+        let overrides = FactorOverrides { t3: Some(base.federal_tax + 260.0), ..Default::default() };
+        let result = calculate(&ctx, &PayrollInput { overrides, ..input.clone() }).unwrap();
+        assert_eq!(result.tax, utils::round(base.tax + 10.0));
+
+        let K2 = federal_income_tax::K2(26, 12, base.cpp, base.ei, false, false, ctx.year);
+        let overrides = FactorOverrides { k2: Some(K2 + 520.0), ..Default::default() };
+        let result = calculate(&ctx, &PayrollInput { overrides, ..input.clone() }).unwrap();
+        assert_eq!(result.tax, utils::round(base.tax - 20.0));
+
+        let overrides = FactorOverrides { k3: Some(130.0), ..Default::default() };
+        let result = calculate(&ctx, &PayrollInput { overrides, ..input }).unwrap();
+        assert_eq!(result.tax, utils::round(base.tax - 5.0));
+    }
+
+    #[test]
+    fn test_provincial_override_changes_tax_by_expected_delta() {
+        // No surtax or reduction in Alberta, so T2 is T4
+        let (ctx, input) = test_fixtures::alberta_semi_monthly_employee();
+        let base = calculate(&ctx, &input).unwrap();
+
+        let overrides = FactorOverrides { t4: Some(base.provincial_tax + 240.0), ..Default::default() };
+        let result = calculate(&ctx, &PayrollInput { overrides, ..input }).unwrap();
+        assert_eq!(result.tax, utils::round(base.tax + 10.0));
+        assert_eq!(result.federal_tax, base.federal_tax);
+    }
+
+    #[test]
+    fn test_context_is_reused_across_employees() {
+        let ctx = PayrollContext::new(Province::Ontario, 2025, PayPeriod::BiWeekly).unwrap();
+        let (_, employee) = test_fixtures::ontario_biweekly_employee();
+
+        let salaries = [1500.0, 2307.69, 4000.0];
+        let results: Vec<PayrollResult> = salaries
+            .iter()
+            .map(|&cash_income| calculate(&ctx, &PayrollInput { cash_income, ..employee.clone() }).unwrap())
+            .collect();
+
+        assert_eq!(results[1], calculate(&test_fixtures::ontario_biweekly_employee().0, &employee).unwrap());
+        assert!(results.windows(2).all(|pair| pair[0].tax < pair[1].tax));
+        assert_eq!(ctx.province, Province::Ontario);
+        assert_eq!(ctx.year.year, 2025);
+    }
+
+    #[test]
+    fn test_context_rejects_unsupported_year() {
+        assert_eq!(
+            PayrollContext::new(Province::Ontario, 2019, PayPeriod::Monthly),
+            Err(PayrollError::UnsupportedYear(2019))
+        );
+    }
+
+    #[test]
+    fn test_quebec_tax_uses_quebec_rates() {
+        let (ctx, input) = test_fixtures::ontario_biweekly_employee();
+        let ctx = PayrollContext { province: Province::Quebec, ..ctx };
+        let input = PayrollInput { provincial_claim: None, ..input };
+        let result = calculate(&ctx, &input).unwrap();
+
+        let credits = quebec::QuebecCredits::default().claim(v2025::QUEBEC_BASIC_AMT, ctx.year).unwrap();
+        assert_eq!(Ok(result.provincial_tax), quebec::provincial_tax(result.annual_taxable_income, credits, ctx.year));
+        assert!(result.provincial_tax > 0.0);
+        assert_eq!(result.ei, quebec::EI(0.0, input.insurable_earnings(), ctx.year));
+
+        let input = PayrollInput { retirement_contributions: input.cash_income, ..input };
+        assert_eq!(calculate(&ctx, &input).unwrap().tax, 0.0);
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_quebec_deducts_QPP_and_credits_its_base_portion() {
+        let (ctx, input) = test_fixtures::ontario_biweekly_employee();
+        let ctx = PayrollContext { province: Province::Quebec, ..ctx };
+        let input = PayrollInput { provincial_claim: None, ..input };
+        let result = calculate(&ctx, &input).unwrap();
+
+        // 6.40% of (2,307.69 − 3,500 / 26)
+        assert_eq!(result.cpp, 139.08);
+        let (credits, _, _) = federal_factors(&ctx, &input, result.annual_taxable_income, result.cpp, result.ei);
+        assert_eq!(credits.K2, federal_income_tax::K2Q(26, 12, 139.08, result.ei, result.qpip, false, false, ctx.year));
+
+        // The QPP maximum, not the CPP maximum, ends the contributions
+        let input = PayrollInput { ytd_cpp: v2025::QPP_MAX_CONTRIBUTIONS - 10.0, ..input };
+        assert_eq!(calculate(&ctx, &input).unwrap().cpp, 10.0);
+    }
+
+    #[test]
+    fn test_quebec_net_pay_deducts_qpip() {
+        let (ctx, input) = test_fixtures::ontario_biweekly_employee();
+        let ctx = PayrollContext { province: Province::Quebec, ..ctx };
+        let input = PayrollInput { provincial_claim: None, ..input };
+        let result = calculate(&ctx, &input).unwrap();
+
+        // 0.494% of the insurable earnings
+        assert_eq!(result.qpip, 11.4);
+        assert_eq!(result.net, utils::round(input.cash_income - result.cpp - result.cpp2 - result.ei - result.qpip - result.tax));
+
+        // Up to the annual maximum
+        let input = PayrollInput { ytd_qpip: v2025::QPIP_MAX_CONTRIBUTIONS - 5.0, ..input };
+        assert_eq!(calculate(&ctx, &input).unwrap().qpip, 5.0);
+
+        // None outside Quebec
+        let (ctx, input) = test_fixtures::ontario_biweekly_employee();
+        assert_eq!(calculate(&ctx, &input).unwrap().qpip, 0.0);
+    }
+
+    #[test]
+    fn test_quebec_federal_tax_is_abated() {
+        // A pensioner has no CPP or EI, so the basic federal tax is the same in both provinces
+        let (ctx, input) = test_fixtures::alberta_monthly_pensioner();
+        let ontario = calculate(&PayrollContext { province: Province::Ontario, ..ctx.clone() }, &input).unwrap();
+        let quebec = calculate(&PayrollContext { province: Province::Quebec, ..ctx }, &input).unwrap();
+
+        assert!(ontario.federal_tax > 0.0);
+        assert!((quebec.federal_tax - ontario.federal_tax * (1.0 - v2025::QUEBEC_ABATEMENT_RATE)).abs() <= 0.01);
+    }
+
+    #[test]
+    fn test_compare_provinces_for_a_100k_salary() {
+        let (ctx, input) = test_fixtures::ontario_biweekly_employee();
+        let input = PayrollInput { cash_income: utils::round(100000.0 / 26.0), ..input };
+        let results = compare_provinces(&input, &[Province::Ontario, Province::Alberta, Province::Quebec], &ctx);
+        let provinces: Vec<Province> = results.iter().map(|(province, _)| *province).collect();
+        assert_eq!(provinces, vec![Province::Ontario, Province::Alberta, Province::Quebec]);
+
+        let ontario = results[0].1.as_ref().unwrap();
+        let alberta = results[1].1.as_ref().unwrap();
+        assert_eq!(*ontario, calculate(&ctx, &input).unwrap());
+        assert_ne!(ontario.provincial_tax, alberta.provincial_tax);
+        assert_eq!(ontario.federal_tax, alberta.federal_tax);
+        assert_eq!((ontario.cpp, ontario.cpp2, ontario.ei), (alberta.cpp, alberta.cpp2, alberta.ei));
+
+        // Quebec tax comes from Revenu Québec's rates rather than T4
+        let quebec = results[2].1.as_ref().unwrap();
+        assert_ne!(quebec.provincial_tax, ontario.provincial_tax);
+        assert_ne!(quebec.ei, ontario.ei);
+    }
+
+    #[test]
+    fn test_project_annual_caps_cpp_and_ei() {
+        let (ctx, input) = test_fixtures::ontario_biweekly_employee();
+        let result = calculate(&ctx, &input).unwrap();
+        let projection = project_annual(&result, &ctx);
+        assert_eq!(projection.cpp, utils::round(26.0 * result.cpp));
+        assert_eq!(projection.ei, utils::round(26.0 * result.ei));
+        assert_eq!(projection.tax, utils::round(26.0 * result.tax));
+        assert_eq!(projection.federal_tax, result.federal_tax);
+
+        // $200,000 a year: 26 pay periods of CPP, CPP2 and EI would exceed the maximums
+        let input = PayrollInput { cash_income: 7692.31, ..input };
+        let result = calculate(&ctx, &input).unwrap();
+        assert!(26.0 * result.cpp > v2025::CONSTANTS.cpp_total_max_contributions);
+        assert!(26.0 * result.ei > v2025::EI_MAX_CONTRIBUTIONS);
+
+        let projection = project_annual(&result, &ctx);
+        assert_eq!(projection.cpp, v2025::CONSTANTS.cpp_total_max_contributions);
+        assert!(projection.cpp2 <= 396.0);
+        assert_eq!(projection.ei, v2025::EI_MAX_CONTRIBUTIONS);
+        assert_eq!(projection.provincial_tax, result.provincial_tax);
+    }
+
+    #[test]
+    fn test_project_annual_caps_at_the_year_maximums() {
+        let (ctx, input) = test_fixtures::ontario_biweekly_employee();
+        // There are no 2024 provincial tables, so the employee is outside Canada
+        let ctx = PayrollContext { year: &crate::year::v2024::CONSTANTS, residency: Residency::OutsideCanada, ..ctx };
+        let input = PayrollInput { cash_income: 7692.31, ytd_pensionable: 70000.0, ..input };
+        let result = calculate(&ctx, &input).unwrap();
+        assert!(result.cpp2 > 0.0);
+
+        // 2024: the CPP2 maximum is 4% of (73,200 − 68,500)
+        let projection = project_annual(&result, &ctx);
+        assert_eq!(projection.cpp, 3867.5);
+        assert_eq!(projection.cpp2, 188.0);
+        assert_eq!(projection.ei, 1049.12);
+
+        // Quebec: the QPP, Quebec EI and QPIP maximums
+        let ctx = PayrollContext { province: Province::Quebec, year: &v2025::CONSTANTS, residency: Residency::Canada, ..ctx };
+        let input = PayrollInput { provincial_claim: None, ..input };
+        let projection = project_annual(&calculate(&ctx, &input).unwrap(), &ctx);
+        assert_eq!(projection.cpp, v2025::QPP_MAX_CONTRIBUTIONS);
+        assert_eq!(projection.ei, v2025::EI_QUEBEC_MAX_CONTRIBUTIONS);
+        assert_eq!(projection.qpip, v2025::QPIP_MAX_CONTRIBUTIONS);
+    }
+
+    #[test]
+    fn test_average_tax_rate_for_an_ontario_salary() {
+        let (ctx, _) = test_fixtures::ontario_biweekly_employee();
+        let rate = average_tax_rate(75000.0, &ctx).unwrap();
+        assert!(rate > 0.12 && rate < 0.20, "{}", rate);
+
+        let rates: Vec<f64> = [40000.0, 75000.0, 120000.0, 250000.0]
+            .iter()
+            .map(|&salary| average_tax_rate(salary, &ctx).unwrap())
+            .collect();
+        assert!(rates.windows(2).all(|pair| pair[0] < pair[1]), "{:?}", rates);
+        assert_eq!(average_tax_rate(0.0, &ctx), Ok(0.0));
+    }
+
+    #[test]
+    fn test_average_tax_rate_for_2024() {
+        // There are no 2024 provincial tables, so the employee is outside Canada
+        let (ctx, input) = test_fixtures::ontario_biweekly_employee();
+        let ctx = PayrollContext { year: &crate::year::v2024::CONSTANTS, residency: Residency::OutsideCanada, ..ctx };
+        let input = PayrollInput { cash_income: utils::round(60000.0 / 26.0), federal_claim: 15705.0, provincial_claim: None, ..input };
+
+        let result = calculate(&ctx, &input).unwrap();
+        assert!(result.federal_tax > 0.0);
+        assert_eq!(average_tax_rate(60000.0, &ctx), Ok((result.federal_tax + result.provincial_tax) / 60000.0));
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_federal_tax_free_threshold() {
+        for (ctx, input) in [test_fixtures::ontario_biweekly_employee(), test_fixtures::alberta_monthly_pensioner()] {
+            let threshold = federal_tax_free_threshold(&ctx);
+            let P = ctx.periods_per_year() as f64;
+            let T1 = |annual: f64| calculate(&ctx, &PayrollInput { cash_income: annual / P, ..input.clone() }).unwrap().federal_tax;
+
+            assert!(T1(threshold) < 0.05, "{:?}: {} at {}", ctx.payee_type, T1(threshold), threshold);
+            assert!(T1(threshold + 10.0) > 0.0);
+            assert_eq!(T1(threshold - 100.0), 0.0);
+        }
+
+        let (ctx, _) = test_fixtures::alberta_monthly_pensioner();
+        assert_eq!(federal_tax_free_threshold(&ctx), v2025::MINIMUM_BASIC_AMT);
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_federal_tax_free_threshold_in_quebec() {
+        let (ctx, input) = test_fixtures::ontario_biweekly_employee();
+        let quebec = PayrollContext { province: Province::Quebec, ..ctx.clone() };
+        let input = PayrollInput { provincial_claim: None, ..input };
+
+        let threshold = federal_tax_free_threshold(&quebec);
+        let T1 = |annual: f64| calculate(&quebec, &PayrollInput { cash_income: annual / 26.0, ..input.clone() }).unwrap().federal_tax;
+        assert!(T1(threshold) < 0.05, "{} at {}", T1(threshold), threshold);
+        assert!(T1(threshold + 10.0) > 0.0);
+        assert_eq!(T1(threshold - 100.0), 0.0);
+
+        // The higher QPP rate and the QPIP premiums raise the threshold
+        assert!(threshold > federal_tax_free_threshold(&ctx));
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_federal_tax_free_threshold_for_2024() {
+        let v2024 = &crate::year::v2024::CONSTANTS;
+        // There are no 2024 provincial tables, so the employee is outside Canada
+        let (ctx, input) = test_fixtures::ontario_biweekly_employee();
+        let ctx = PayrollContext { year: v2024, residency: Residency::OutsideCanada, ..ctx };
+        let input = PayrollInput { federal_claim: 15705.0, ..input };
+
+        let threshold = federal_tax_free_threshold(&ctx);
+        let T1 = |annual: f64| calculate(&ctx, &PayrollInput { cash_income: annual / 26.0, ..input.clone() }).unwrap().federal_tax;
+        assert!(T1(threshold) < 0.05, "{} at {}", T1(threshold), threshold);
+        assert!(T1(threshold + 10.0) > 0.0);
+        assert_eq!(T1(threshold - 100.0), 0.0);
+
+        let (ctx, _) = test_fixtures::alberta_monthly_pensioner();
+        assert_eq!(federal_tax_free_threshold(&PayrollContext { year: v2024, ..ctx }), 15705.0);
+    }
+
+    #[test]
+    fn test_marginal_deduction_rate_below_and_above_the_maximums() {
+        let (ctx, _) = test_fixtures::ontario_biweekly_employee();
+
+        // $60,000: 20.5% federal and 9.15% Ontario, plus CPP and EI
+        let rate = marginal_deduction_rate(60000.0, 1000.0, 500.0, 0.0, &ctx).unwrap();
+        assert!((rate - (0.205 + 0.0915 + 0.0595 + 0.0164)).abs() < 1e-9, "{}", rate);
+
+        let rate = marginal_deduction_rate(60000.0, 4034.1, v2025::EI_MAX_CONTRIBUTIONS, 0.0, &ctx).unwrap();
+        assert!((rate - (0.205 + 0.0915)).abs() < 1e-9, "{}", rate);
+
+        let (ctx, _) = test_fixtures::alberta_monthly_pensioner();
+        assert_eq!(marginal_deduction_rate(30000.0, 0.0, 0.0, 0.0, &ctx), Ok(0.15 + 0.10));
+        let ctx = PayrollContext { province: Province::Manitoba, ..ctx };
+        assert_eq!(marginal_deduction_rate(30000.0, 0.0, 0.0, 0.0, &ctx), Err(PayrollError::UnsupportedProvince(Province::Manitoba)));
+    }
+
+    #[test]
+    fn test_marginal_deduction_rate_in_quebec() {
+        let (ctx, _) = test_fixtures::ontario_biweekly_employee();
+        let ctx = PayrollContext { province: Province::Quebec, ..ctx };
+
+        // $60,000: 20.5% federal less the 16.5% abatement and 19% Quebec, plus QPP, Quebec EI and QPIP
+        let rate = marginal_deduction_rate(60000.0, 1000.0, 500.0, 100.0, &ctx).unwrap();
+        assert!((rate - (0.205 * 0.835 + 0.19 + 0.064 + 0.0131 + 0.00494)).abs() < 1e-9, "{}", rate);
+
+        // The QPP maximum is higher than the CPP maximum
+        let rate = marginal_deduction_rate(60000.0, 4034.1, v2025::EI_QUEBEC_MAX_CONTRIBUTIONS, v2025::QPIP_MAX_CONTRIBUTIONS, &ctx).unwrap();
+        assert!((rate - (0.205 * 0.835 + 0.19 + 0.064)).abs() < 1e-9, "{}", rate);
+
+        let rate = marginal_deduction_rate(60000.0, v2025::QPP_MAX_CONTRIBUTIONS, 500.0, v2025::QPIP_MAX_CONTRIBUTIONS, &ctx).unwrap();
+        assert!((rate - (0.205 * 0.835 + 0.19 + 0.0131)).abs() < 1e-9, "{}", rate);
+    }
+
+    #[test]
+    fn test_gross_up_a_net_bonus() {
+        let (ctx, input) = test_fixtures::ontario_biweekly_employee();
+        let gross = gross_up(1000.0, &ctx, &input).unwrap();
+        assert!(gross > 1000.0);
+
+        let result = calculate(&ctx, &PayrollInput { cash_income: gross, ..input.clone() }).unwrap();
+        assert!((result.net - 1000.0).abs() <= 0.01, "{} {}", gross, result.net);
+        let one_cent_less = calculate(&ctx, &PayrollInput { cash_income: gross - 0.01, ..input.clone() }).unwrap();
+        assert!(one_cent_less.net < 1000.0);
+
+        assert_eq!(gross_up(0.0, &ctx, &input), Ok(0.0));
+        assert_eq!(gross_up(-5.0, &ctx, &input), Err(PayrollError::NegativeInput("target_net")));
+    }
+
+    #[test]
+    fn test_explain_lists_every_factor_in_order() {
+        let (ctx, input) = test_fixtures::ontario_biweekly_employee();
+        let trace = explain(&input, &ctx).unwrap();
+        let result = calculate(&ctx, &input).unwrap();
+
+        let names: Vec<&str> = trace.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, vec!["A", "K1", "K2", "K3", "K4", "T3", "T1", "K1P", "K2P", "T4", "T2", "T"]);
+        assert_eq!(trace.last().unwrap().1, result.tax);
+        assert_eq!(trace[0].1, result.annual_taxable_income);
+        assert_eq!(trace[1].1, federal_income_tax::K1(input.federal_claim));
+        // No labour-sponsored funds credit, so T1 is T3
+        assert_eq!(trace[5].1, trace[6].1);
+    }
+
+    #[test]
+    fn test_audit_sink_records_each_calculation() {
+        let (ctx, input) = test_fixtures::ontario_biweekly_employee();
+        let calculator = PayrollCalculator::new(ctx.clone());
+        let mut sink = InMemoryAuditSink::default();
+
+        let raise = PayrollInput { cash_income: 2500.0, ..input.clone() };
+        let first = calculator.compute_audited(&input, &mut sink).unwrap();
+        let second = calculator.compute_audited(&raise, &mut sink).unwrap();
+        assert_eq!(sink.records.len(), 2);
+
+        for (record, (input, result)) in sink.records.iter().zip([(&input, &first), (&raise, &second)]) {
+            assert_eq!(&record.result, result);
+            assert_eq!(record.factors.last(), Some(&("T".to_string(), result.tax)));
+            assert_eq!(&record.input, input);
+            assert_eq!(record.ctx.year.year, 2025);
+            assert_eq!(record.crate_version, env!("CARGO_PKG_VERSION"));
+        }
+        assert_eq!(second, calculate(&ctx, &raise).unwrap());
+
+        // Failed calculations are not recorded
+        let invalid = PayrollInput { cash_income: f64::NAN, ..input };
+        assert!(calculator.compute_audited(&invalid, &mut sink).is_err());
+        assert_eq!(sink.records.len(), 2);
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_outside_canada_pays_only_federal_tax() {
+        let (ctx, input) = test_fixtures::ontario_biweekly_employee();
+        let ctx = PayrollContext { residency: Residency::OutsideCanada, ..ctx };
+        let input = PayrollInput { additional_tax: 25.0, ..input };
+        let result = calculate(&ctx, &input).unwrap();
+
+        assert_eq!(result.provincial_tax, 0.0);
+        let P = ctx.periods_per_year();
+        let T1 = federal_tax(&ctx, &input, result.annual_taxable_income, result.cpp, result.ei);
+        assert_eq!(result.federal_tax, T1);
+        assert_eq!(result.tax, utils::round(T1 / P as f64 + 25.0));
+
+        // 48% more federal tax than in Canada
+        let in_canada = calculate(&PayrollContext { residency: Residency::Canada, ..ctx }, &input).unwrap();
+        assert_eq!(result.federal_tax, utils::round(in_canada.federal_tax * 1.48));
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_retroactive_pay_across_a_federal_bracket() {
+        let (ctx, input) = test_fixtures::ontario_biweekly_employee();
+        let input = PayrollInput { cash_income: 2160.0, ..input };
+        let A = calculate(&ctx, &input).unwrap().annual_taxable_income;
+        assert!(A < 57375.0 && A + 3000.0 > 57375.0, "{}", A);
+        let retro = retroactive_pay_tax(&ctx, &input, 3000.0).unwrap();
+
+        // F5B: 50.25 of additional CPP on the period's 5,160, 29.22 of it on the retroactive pay
+        let B = 3000.0 - 29.22;
+        // Federally from the 15% bracket into the 20.5% bracket (R × A − K); all of it at 9.15% in Ontario
+        let expected = (0.205 * (A + B) - 3156.0) - 0.15 * A + 0.0915 * B;
+        assert!((retro.tax - expected).abs() <= 0.01, "{} {}", retro.tax, expected);
+        assert_eq!(retro.cpp, utils::round(0.0595 * 3000.0));
+        assert_eq!(retro.ei, utils::round(0.0164 * 3000.0));
+    }
+
+    #[test]
+    fn test_retroactive_pay_once_maximums_are_reached() {
+        let (ctx, input) = test_fixtures::ontario_biweekly_employee();
+        let input = PayrollInput { ytd_cpp: 4034.1, ytd_ei: v2025::EI_MAX_CONTRIBUTIONS, ..input };
+        let retro = retroactive_pay_tax(&ctx, &input, 1000.0).unwrap();
+        assert_eq!(retro.cpp, 0.0);
+        assert_eq!(retro.ei, 0.0);
+        assert!(retro.tax > 0.0);
+    }
+
+    #[test]
+    fn test_bonus_method_tax_in_a_single_period() {
+        // $36,000 a year of pension income, in the lowest federal and Alberta brackets
+        let (ctx, input) = test_fixtures::alberta_monthly_pensioner();
+        assert_eq!(bonus_method_tax(&ctx, &input, 5000.0), Ok(0.15 * 5000.0 + 0.10 * 5000.0));
+        assert_eq!(bonus_method_tax(&ctx, &input, 0.0), Ok(0.0));
+        assert_eq!(bonus_method_tax(&ctx, &input, f64::NAN), Err(PayrollError::NonFiniteInput("bonus")));
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_bonus_method_tax_deducts_the_additional_cpp_on_the_bonus() {
+        let (ctx, input) = test_fixtures::ontario_biweekly_employee();
+        let regular = calculate(&ctx, &input).unwrap();
+
+        // F5B: 51.73 of additional CPP on the period's 5,307.69, 29.24 of it on the bonus
+        let A = utils::round(regular.annual_taxable_income + 3000.0 - 29.24);
+        let T1 = federal_tax(&ctx, &input, A, regular.cpp, regular.ei);
+        let T2 = provincial_tax(&ctx, &input, A, regular.cpp, regular.ei).unwrap();
+        let expected = utils::round(T1 + T2 - regular.federal_tax - regular.provincial_tax);
+        assert_eq!(bonus_method_tax(&ctx, &input, 3000.0), Ok(expected));
+
+        // Nothing is deducted once the CPP maximum is reached
+        let maxed = PayrollInput { ytd_cpp: 4034.1, ..input };
+        let regular = calculate(&ctx, &maxed).unwrap();
+        let A = utils::round(regular.annual_taxable_income + 3000.0);
+        let T1 = federal_tax(&ctx, &maxed, A, regular.cpp, regular.ei);
+        let T2 = provincial_tax(&ctx, &maxed, A, regular.cpp, regular.ei).unwrap();
+        assert_eq!(bonus_method_tax(&ctx, &maxed, 3000.0), Ok(utils::round(T1 + T2 - regular.federal_tax - regular.provincial_tax)));
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_bonus_tax_with_rrsp() {
+        let (ctx, input) = test_fixtures::ontario_biweekly_employee();
+
+        // The entire bonus goes to the RRSP: nothing is withheld on it
+        assert_eq!(bonus_tax_with_rrsp(3000.0, 3000.0, &ctx, &input), Ok(0.0));
+        assert_eq!(bonus_tax_with_rrsp(3000.0, 4000.0, &ctx, &input), Ok(0.0));
+
+        // F5B is on the whole bonus (29.24, as in `test_bonus_method_tax_deducts_the_additional_cpp_on_the_bonus`), not the 2,000 left after the RRSP
+        let regular = calculate(&ctx, &input).unwrap();
+        let A = utils::round(regular.annual_taxable_income + 3000.0 - 1000.0 - 29.24);
+        let T1 = federal_tax(&ctx, &input, A, regular.cpp, regular.ei);
+        let T2 = provincial_tax(&ctx, &input, A, regular.cpp, regular.ei).unwrap();
+        let expected = utils::round(T1 + T2 - regular.federal_tax - regular.provincial_tax);
+        assert_eq!(bonus_tax_with_rrsp(3000.0, 1000.0, &ctx, &input), Ok(expected));
+        assert!(expected < bonus_method_tax(&ctx, &input, 2000.0).unwrap());
+        assert_eq!(bonus_tax_with_rrsp(3000.0, 0.0, &ctx, &input), bonus_method_tax(&ctx, &input, 3000.0));
+        assert_eq!(
+            bonus_tax_with_rrsp(3000.0, f64::NAN, &ctx, &input),
+            Err(PayrollError::NonFiniteInput("rrsp_from_bonus"))
+        );
+    }
+
+    #[test]
+    fn test_bonus_method_tax_matches_retroactive_pay_tax() {
+        let (ctx, input) = test_fixtures::ontario_biweekly_employee();
+        let bonus = bonus_method_tax(&ctx, &input, 3000.0).unwrap();
+        assert!(bonus > 0.0);
+        assert_eq!(bonus, retroactive_pay_tax(&ctx, &input, 3000.0).unwrap().tax);
+    }
+
+    #[test]
+    fn test_vacation_payout_on_termination() {
+        let (ctx, input) = test_fixtures::ontario_biweekly_employee();
+        let payout = vacation_payout_tax(&ctx, &input, 4000.0).unwrap();
+
+        // Taxed as a lump sum with the bonus method, with CPP and EI on the full amount below the maximums
+        assert_eq!(payout.tax, bonus_method_tax(&ctx, &input, 4000.0).unwrap());
+        assert!(payout.tax > 0.0);
+        assert_eq!(payout.cpp, utils::round(0.0595 * 4000.0));
+        assert_eq!(payout.ei, utils::round(0.0164 * 4000.0));
+
+        // Once the maximums are reached, only the tax is owed
+        let input = PayrollInput { ytd_cpp: 4034.1, ytd_ei: v2025::EI_MAX_CONTRIBUTIONS, ..input };
+        let payout = vacation_payout_tax(&ctx, &input, 4000.0).unwrap();
+        assert_eq!((payout.cpp, payout.ei), (0.0, 0.0));
+        assert!(payout.tax > 0.0);
+        assert_eq!(vacation_payout_tax(&ctx, &input, f64::NAN), Err(PayrollError::NonFiniteInput("accrued_vacation")));
+    }
+
+    #[test]
+    fn test_termination_with_regular_vacation_and_severance_pay() {
+        let (ctx, input) = test_fixtures::ontario_biweekly_employee();
+        let termination = termination_pay(2307.69, 4000.0, 20000.0, &ctx, &input).unwrap();
+
+        assert_eq!(termination.regular, calculate(&ctx, &input).unwrap());
+        assert_eq!(termination.vacation, vacation_payout_tax(&ctx, &input, 4000.0).unwrap());
+        // Over $15,000, 30% of the whole payment
+        assert_eq!(termination.severance_tax, 6000.0);
+        assert_eq!(termination.tax, utils::round(termination.regular.tax + termination.vacation.tax + 6000.0));
+        let vacation = &termination.vacation;
+        assert_eq!(
+            termination.net,
+            utils::round(termination.regular.net + 4000.0 - vacation.tax - vacation.cpp - vacation.ei + 14000.0)
+        );
+
+        // Up to and including $5,000, 10%
+        assert_eq!(termination_pay(2307.69, 0.0, 5000.0, &ctx, &input).unwrap().severance_tax, 500.0);
+        assert_eq!(termination_pay(2307.69, 0.0, 5000.01, &ctx, &input).unwrap().severance_tax, 1000.0);
+        assert_eq!(termination_pay(2307.69, 0.0, -1.0, &ctx, &input), Err(PayrollError::NegativeInput("severance")));
+    }
+
+    #[test]
+    fn test_clawback_returns_cpp_and_ei_but_not_tax() {
+        let (ctx, _) = test_fixtures::ontario_biweekly_employee();
+        let ytd = YtdTotals {
+            gross: 10000.0,
+            pensionable: 10000.0,
+            insurable: 10000.0,
+            cpp: 386.75,
+            cpp2: 0.0,
+            ei: 164.0,
+            tax: 1500.0,
+        };
+
+        let clawback = apply_clawback(-2000.0, ytd, &ctx).unwrap();
+        assert_eq!(clawback.cpp, 119.0);
+        assert_eq!(clawback.ei, 32.8);
+        assert_eq!(clawback.cpp2, 0.0);
+        assert_eq!(clawback.ytd.cpp, 267.75);
+        assert_eq!(clawback.ytd.pensionable, 8000.0);
+        assert_eq!(clawback.ytd.tax, 1500.0);
+
+        // Never more than was deducted; only the gross goes below zero
+        let clawback = apply_clawback(-15000.0, ytd, &ctx).unwrap();
+        assert_eq!((clawback.cpp, clawback.ei), (386.75, 164.0));
+        assert_eq!((clawback.ytd.cpp, clawback.ytd.ei), (0.0, 0.0));
+        assert_eq!(clawback.ytd.pensionable, 0.0);
+        assert_eq!(clawback.ytd.gross, -5000.0);
+
+        assert_eq!(apply_clawback(500.0, ytd, &ctx).unwrap().ytd, ytd);
+    }
+
+    #[test]
+    fn test_cpp2_starts_at_the_ympe_of_the_year() {
+        let (ctx, input) = test_fixtures::ontario_biweekly_employee();
+        // There are no 2024 provincial tables, so the employee is outside Canada
+        let ctx = PayrollContext { year: &crate::year::v2024::CONSTANTS, residency: Residency::OutsideCanada, ..ctx };
+        let input = PayrollInput { cash_income: 5000.0, ytd_pensionable: 66000.0, ..input };
+
+        // 4% of the 2,500 above the 2024 YMPE of 68,500
+        assert_eq!(calculate(&ctx, &input).unwrap().cpp2, 100.0);
+    }
+
+    #[test]
+    fn test_clawback_uses_the_maximums_of_the_year() {
+        let (ctx, _) = test_fixtures::ontario_biweekly_employee();
+        let ctx = PayrollContext { year: &crate::year::v2024::CONSTANTS, ..ctx };
+        let ytd = YtdTotals {
+            gross: 72000.0,
+            pensionable: 72000.0,
+            insurable: 72000.0,
+            cpp: 3867.5,
+            cpp2: 140.0,
+            ei: 1049.12,
+            tax: 15000.0,
+        };
+
+        // From 72,000 to 67,000 in 2024: 1,500 below the YMPE of 68,500, 3,500 below the YAMPE of 73,200, and all above the MIE of 63,200
+        let clawback = apply_clawback(-5000.0, ytd, &ctx).unwrap();
+        assert_eq!(clawback.cpp, utils::round(0.0595 * 1500.0));
+        assert_eq!(clawback.cpp2, utils::round(0.04 * 3500.0));
+        assert_eq!(clawback.ei, 0.0);
+    }
+
+    #[test]
+    fn test_clawback_above_the_maximums_returns_only_the_second_cpp() {
+        let (ctx, _) = test_fixtures::ontario_biweekly_employee();
+        let ytd = YtdTotals {
+            gross: 90000.0,
+            pensionable: 90000.0,
+            insurable: 90000.0,
+            cpp: 4034.1,
+            cpp2: 396.0,
+            ei: v2025::EI_MAX_CONTRIBUTIONS,
+            tax: 20000.0,
+        };
+
+        // The earnings clawed back were all above the YAMPE and the maximum insurable earnings
+        let clawback = apply_clawback(-5000.0, ytd, &ctx).unwrap();
+        assert_eq!((clawback.cpp, clawback.cpp2, clawback.ei), (0.0, 0.0, 0.0));
+
+        // From 90,000 to 80,000: 1,200 below the YAMPE of 81,200 is second additional earnings; the base earnings stay above the YMPE
+        let clawback = apply_clawback(-10000.0, ytd, &ctx).unwrap();
+        assert_eq!((clawback.cpp, clawback.cpp2, clawback.ei), (0.0, utils::round(0.04 * 1200.0), 0.0));
+    }
+
+    #[test]
+    fn test_employer_remittance_for_three_employees() {
+        let results: Vec<PayrollResult> = [
+            test_fixtures::ontario_biweekly_employee(),
+            test_fixtures::ontario_weekly_employee_with_dependents(),
+            test_fixtures::alberta_semi_monthly_employee(),
+        ]
+        .iter()
+        .map(|(ctx, input)| calculate(ctx, input).unwrap())
+        .collect();
+
+        let remittance = employer_remittance(&results);
+        let tax: f64 = results.iter().map(|result| result.tax).sum();
+        let cpp: f64 = results.iter().map(|result| result.cpp + result.cpp2).sum();
+        let ei: f64 = results.iter().map(|result| result.ei).sum();
+        assert_eq!(remittance.income_tax, utils::round(tax));
+        assert_eq!(remittance.cpp, utils::round(2.0 * cpp));
+        assert!((remittance.ei - 2.4 * ei).abs() < 0.02);
+        assert_eq!(remittance.total(), utils::round(remittance.income_tax + remittance.cpp + remittance.ei));
+
+        assert_eq!(employer_remittance(&results[..1]).ei, 37.85 + 52.99);
+        assert_eq!(employer_remittance(&[]), Remittance::default());
+    }
+
+    #[test]
+    fn test_remittance_accumulator_by_quarter() {
+        let (ctx, input) = test_fixtures::ontario_biweekly_employee();
+        let alberta = PayrollContext { province: Province::Alberta, ..ctx.clone() };
+        let results = [
+            calculate(&ctx, &input).unwrap(),
+            calculate(&alberta, &PayrollInput { provincial_claim: Some(v2025::ALBERTA_BASIC_AMT), ..input.clone() }).unwrap(),
+        ];
+
+        let calendar = pay_calendar::pay_calendar(NaiveDate::from_ymd_opt(2025, 1, 3).unwrap(), PayPeriod::BiWeekly, 2025).unwrap();
+        let mut accumulator = RemittanceAccumulator::default();
+        let mut all = Vec::new();
+        for pay_date in &calendar {
+            accumulator.add(*pay_date, &results);
+            all.extend(results.iter().cloned());
+        }
+
+        // January 3 to March 28, April 11 to June 20, July 4 to September 26, October 10 to December 19
+        let per_date = employer_remittance(&results);
+        let quarters = accumulator.quarters();
+        for (quarter, pay_dates) in quarters.iter().zip([7.0, 6.0, 7.0, 6.0]) {
+            assert!((quarter.total() - pay_dates * per_date.total()).abs() < 0.015, "{:?}", quarter);
+        }
+
+        let annual = accumulator.annual();
+        let expected = employer_remittance(&all);
+        assert!((annual.income_tax - expected.income_tax).abs() < 0.005);
+        assert!((annual.cpp - expected.cpp).abs() < 0.005);
+        assert!((annual.ei - expected.ei).abs() < 0.005);
+        assert_eq!(annual.total(), utils::round(quarters.iter().map(Remittance::total).sum()));
+    }
+
+    #[test]
+    fn test_compute_with_bonus_matches_computing_each_separately() {
+        let (ctx, input) = test_fixtures::ontario_biweekly_employee();
+        let calculator = PayrollCalculator::new(ctx.clone());
+
+        let (result, bonus_tax) = calculator.compute_with_bonus(&input, 2500.0).unwrap();
+        assert_eq!(result, calculate(&ctx, &input).unwrap());
+        assert_eq!(bonus_tax, bonus_method_tax(&ctx, &input, 2500.0).unwrap());
+        assert!(bonus_tax > 0.0);
+        assert_eq!(calculator.compute(&input).unwrap(), result);
+
+        assert_eq!(calculator.compute_with_bonus(&input, 0.0).unwrap(), (result, 0.0));
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_compute_with_bonus_deducts_the_additional_cpp_on_the_bonus() {
+        let (ctx, input) = test_fixtures::ontario_biweekly_employee();
+        let (result, bonus_tax) = PayrollCalculator::new(ctx.clone()).compute_with_bonus(&input, 2500.0).unwrap();
+
+        // F5B: 46.73 of additional CPP on the period's 4,807.69, 24.30 of it on the bonus
+        let A = utils::round(result.annual_taxable_income + 2500.0 - 24.3);
+        let T1 = federal_tax(&ctx, &input, A, result.cpp, result.ei);
+        let T2 = provincial_tax(&ctx, &input, A, result.cpp, result.ei).unwrap();
+        assert_eq!(bonus_tax, utils::round(T1 + T2 - result.federal_tax - result.provincial_tax));
+    }
+
+    #[test]
+    fn test_compute_batch_flags_the_employee_who_reaches_the_ei_maximum() {
+        let (ctx, input) = test_fixtures::ontario_biweekly_employee();
+        let calculator = PayrollCalculator::new(ctx.clone());
+        let near_max = PayrollInput { ytd_ei: v2025::EI_MAX_CONTRIBUTIONS - 10.0, ..input.clone() };
+        let at_max = PayrollInput { ytd_ei: v2025::EI_MAX_CONTRIBUTIONS, ..input.clone() };
+
+        let batch = calculator.compute_batch(&[input.clone(), near_max, at_max]).unwrap();
+        let flags: Vec<(bool, bool)> = batch.iter().map(|entry| (entry.reached_cpp_max, entry.reached_ei_max)).collect();
+        assert_eq!(flags, vec![(false, false), (false, true), (false, false)]);
+        assert_eq!(batch[1].result.ei, 10.0);
+        assert_eq!(batch[0].result, calculate(&ctx, &input).unwrap());
+
+        let invalid = PayrollInput { cash_income: f64::NAN, ..input };
+        assert_eq!(calculator.compute_batch(&[invalid]), Err(PayrollError::NonFiniteInput("cash_income")));
+    }
+
+    #[test]
+    fn test_credits_use_the_maximums_once_reached() {
+        let (ctx, input) = test_fixtures::ontario_biweekly_employee();
+        let maxed = PayrollInput { ytd_cpp: 4034.1, ytd_cpp2: 396.0, ytd_ei: v2025::EI_MAX_CONTRIBUTIONS, ..input };
+        let result = calculate(&ctx, &maxed).unwrap();
+        assert_eq!((result.cpp, result.ei), (0.0, 0.0));
+
+        // K2 is on the maximums, not on this pay period's contributions of zero
+        let maximum = utils::round(0.15 * (v2025::CPP_MAX_CONTRIBUTIONS + v2025::EI_MAX_CONTRIBUTIONS));
+        let at_maximum = FactorOverrides { k2: Some(maximum), ..Default::default() };
+        let on_zero = FactorOverrides { k2: Some(0.0), ..Default::default() };
+        assert_eq!(result.federal_tax, calculate(&ctx, &PayrollInput { overrides: at_maximum, ..maxed.clone() }).unwrap().federal_tax);
+        assert!(result.federal_tax < calculate(&ctx, &PayrollInput { overrides: on_zero, ..maxed }).unwrap().federal_tax);
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_credits_use_the_ei_maximum_before_the_cpp_maximum() {
+        // Earning more than the MIE but less than the YMPE, the employee reaches the EI maximum first
+        let (ctx, input) = test_fixtures::ontario_biweekly_employee();
+        let past_mie = PayrollInput { ytd_cpp: 2500.0, ytd_ei: v2025::EI_MAX_CONTRIBUTIONS, ..input };
+        let result = calculate(&ctx, &past_mie).unwrap();
+        assert_eq!(result.ei, 0.0);
+        assert!(result.cpp > 0.0);
+
+        // The EI part of K2 is on the maximum, the CPP part on this pay period's contributions
+        let K2 = federal_income_tax::K2(26, 12, result.cpp, 0.0, false, true, ctx.year);
+        assert!(K2 > federal_income_tax::K2(26, 12, result.cpp, 0.0, false, false, ctx.year));
+        assert!(K2 < federal_income_tax::K2(26, 12, result.cpp, 0.0, true, true, ctx.year));
+        let at_ei_maximum = FactorOverrides { k2: Some(K2), ..Default::default() };
+        assert_eq!(result.federal_tax, calculate(&ctx, &PayrollInput { overrides: at_ei_maximum, ..past_mie }).unwrap().federal_tax);
+    }
+
+    #[test]
+    fn test_cpt30_election_stops_cpp_from_july() {
+        let (ctx, input) = test_fixtures::ontario_biweekly_employee();
+        let election = NaiveDate::from_ymd_opt(2025, 7, 1);
+        let paid_on = |month, day| PayrollInput { pay_date: NaiveDate::from_ymd_opt(2025, month, day), ..input.clone() };
+        let elected = |input: PayrollInput| PayrollInput { cpt30_election: election, ..input };
+
+        let june = calculate(&ctx, &elected(paid_on(6, 27))).unwrap();
+        let july = calculate(&ctx, &elected(paid_on(7, 11))).unwrap();
+        let without_election = calculate(&ctx, &paid_on(7, 11)).unwrap();
+
+        assert_eq!(june.cpp, without_election.cpp);
+        assert_eq!((july.cpp, july.cpp2), (0.0, 0.0));
+        assert_eq!(july.ei, without_election.ei);
+        assert!(july.tax > 0.0);
+        assert_eq!(elected(paid_on(6, 27)).cpp_contribution_months(2025), 6);
+
+        // CPP stops at the maximum for the six months before the election
+        let near_max = PayrollInput { ytd_cpp: 2017.05 - 10.0, ..elected(paid_on(6, 27)) };
+        assert_eq!(calculate(&ctx, &near_max).unwrap().cpp, 10.0);
+    }
+
+    #[test]
+    fn test_cpt30_election_stops_cpp_on_retroactive_and_vacation_pay() {
+        let (ctx, input) = test_fixtures::ontario_biweekly_employee();
+        let election = NaiveDate::from_ymd_opt(2025, 7, 1);
+        let elected = |month, day| PayrollInput {
+            pay_date: NaiveDate::from_ymd_opt(2025, month, day),
+            cpt30_election: election,
+            ..input.clone()
+        };
+
+        let retro = retroactive_pay_tax(&ctx, &elected(7, 11), 3000.0).unwrap();
+        assert_eq!(retro.cpp, 0.0);
+        assert_eq!(retro.ei, retroactive_pay_tax(&ctx, &input, 3000.0).unwrap().ei);
+        assert!(retro.tax > 0.0);
+
+        let vacation = vacation_payout_tax(&ctx, &elected(7, 11), 2000.0).unwrap();
+        assert_eq!(vacation.cpp, 0.0);
+        assert_eq!(vacation.ei, vacation_payout_tax(&ctx, &input, 2000.0).unwrap().ei);
+
+        // Before the election, the CPP on retroactive pay stops at the maximum for the six months
+        let near_max = PayrollInput { ytd_cpp: 2017.05 - 10.0, ..elected(6, 27) };
+        assert_eq!(retroactive_pay_tax(&ctx, &near_max, 3000.0).unwrap().cpp, 0.0);
+        assert_eq!(vacation_payout_tax(&ctx, &near_max, 2000.0).unwrap().cpp, 0.0);
+    }
+
+    #[test]
+    fn test_compute_batch_flags_the_cpp_maximum_prorated_for_a_cpt30_election() {
+        let (ctx, input) = test_fixtures::ontario_biweekly_employee();
+        let calculator = PayrollCalculator::new(ctx);
+        let june = PayrollInput {
+            pay_date: NaiveDate::from_ymd_opt(2025, 6, 27),
+            cpt30_election: NaiveDate::from_ymd_opt(2025, 7, 1),
+            ytd_cpp: 2017.05 - 10.0,
+            ..input
+        };
+        let july = PayrollInput { pay_date: NaiveDate::from_ymd_opt(2025, 7, 11), ytd_cpp: 2017.05, ..june.clone() };
+
+        let batch = calculator.compute_batch(&[june, july]).unwrap();
+        assert_eq!(batch[0].result.cpp, 10.0);
+        assert!(batch[0].reached_cpp_max);
+        assert_eq!(batch[1].result.cpp, 0.0);
+        assert!(!batch[1].reached_cpp_max);
+    }
+
+    #[test]
+    fn test_validate_fixtures_are_valid() {
+        for (ctx, input) in test_fixtures::all() {
+            assert_eq!(validate(&input, &ctx), vec![]);
+        }
+    }
+
+    #[test]
+    fn test_validate_reports_every_problem() {
+        let (ctx, input) = test_fixtures::ontario_biweekly_employee();
+        let ctx = PayrollContext { province: Province::Manitoba, ..ctx };
+        let input = PayrollInput {
+            cash_income: -100.0,
+            provincial_claim: Some(-1.0),
+            ytd_ei: f64::NAN,
+            contribution_months: 13,
+            ..input
+        };
+
+        assert_eq!(
+            validate(&input, &ctx),
+            vec![
+                PayrollError::NonFiniteInput("ytd_ei"),
+                PayrollError::NegativeInput("cash_income"),
+                PayrollError::NegativeInput("provincial_claim"),
+                PayrollError::UnsupportedProvince(Province::Manitoba),
+                PayrollError::InvalidContributionMonths(13),
+            ]
+        );
+    }
+}
@@ -0,0 +1,22 @@
+//! Provinces and Territories
+
+/** Province or territory of employment.
+*
+*   Determines which provincial or territorial tax tables are used to calculate T2.
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Province {
+    Alberta,
+    BritishColumbia,
+    Manitoba,
+    NewBrunswick,
+    NewfoundlandAndLabrador,
+    NorthwestTerritories,
+    NovaScotia,
+    Nunavut,
+    Ontario,
+    PrinceEdwardIsland,
+    Quebec,
+    Saskatchewan,
+    Yukon,
+}
@@ -0,0 +1,68 @@
+//! British Columbia Provincial Income Tax
+
+use crate::error::PayrollError;
+use crate::province::Province;
+use crate::utils;
+use crate::year::{v2025, YearConstants};
+
+/** Provincial tax reduction (only applies to Ontario and British Columbia)
+*
+*   The British Columbia reduction is income-tested: the full reduction applies up to the net income threshold, and is reduced by a percentage of net income above it. It can never exceed the basic provincial tax. Returns `PayrollError::UnsupportedProvince` for a year without the reduction's amounts.
+*
+*
+* Given:
+*
+*   T4: Annual basic provincial or territorial tax
+*
+*   A: Annual taxable income, used as the net income for the income test
+*
+*   year: The constants for the year of the calculation
+*/
+#[allow(non_snake_case)]
+pub fn S(T4: f64, A: f64, year: &YearConstants) -> Result<f64, PayrollError> {
+    let (base, threshold, rate) = match year.year {
+        2025 => (v2025::BC_REDUCTION_BASE, v2025::BC_REDUCTION_THRESHOLD, v2025::BC_REDUCTION_RATE),
+        _ => return Err(PayrollError::UnsupportedProvince(Province::BritishColumbia)),
+    };
+    let mut s: f64 = base;
+    if A > threshold {
+        s -= rate * (A - threshold);
+    }
+    Ok(utils::round(utils::clamp(s, 0.0, T4)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::year::v2024;
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_S_below_threshold_is_full_reduction() {
+        let year = &v2025::CONSTANTS;
+        assert_eq!(S(1000.0, 20000.0, year), Ok(v2025::BC_REDUCTION_BASE));
+        assert_eq!(S(1000.0, v2025::BC_REDUCTION_THRESHOLD, year), Ok(v2025::BC_REDUCTION_BASE));
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_S_mid_phase_out() {
+        let year = &v2025::CONSTANTS;
+        assert_eq!(S(1000.0, 30000.0, year), Ok(384.71));
+        // Never more than the basic provincial tax
+        assert_eq!(S(300.0, 30000.0, year), Ok(300.0));
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_S_above_phase_out_is_zero() {
+        assert_eq!(S(2000.0, 50000.0, &v2025::CONSTANTS), Ok(0.0));
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_S_for_a_year_without_amounts() {
+        assert_eq!(S(1000.0, 20000.0, &v2024::CONSTANTS), Err(PayrollError::UnsupportedProvince(Province::BritishColumbia)));
+    }
+}
@@ -0,0 +1,55 @@
+//! Manitoba Provincial Income Tax
+
+use crate::utils;
+
+// Historical Manitoba family tax benefit parameters, not current law: the base amount, the amount for each
+// dependant, the reduction rate on net income, and the credit rate (the lowest Manitoba rate when it was in force)
+const FAMILY_BENEFIT_BASE: f64 = 2065.0;
+const FAMILY_BENEFIT_PER_DEPENDENT: f64 = 2752.0;
+const FAMILY_BENEFIT_INCOME_RATE: f64 = 0.09;
+const FAMILY_BENEFIT_RATE: f64 = 0.108;
+
+/** Family tax benefit reduction
+*
+*   The basic amount plus an amount for each dependant, less a percentage of net income, at the lowest Manitoba rate. It can never exceed the basic provincial tax.
+*
+*   The parameters are historical ones, not a current year's. There are no Manitoba tax tables yet, so `payroll::calculate` returns `PayrollError::UnsupportedProvince` for Manitoba and does not reach this reduction.
+*
+*
+* Given:
+*
+*   T4: Annual basic provincial or territorial tax
+*
+*   A: Annual taxable income, used as the net income for the income test
+*
+*   dependents: Number of dependants under 19
+*/
+#[allow(non_snake_case)]
+pub fn S(T4: f64, A: f64, dependents: i64) -> f64 {
+    let amount = FAMILY_BENEFIT_BASE + FAMILY_BENEFIT_PER_DEPENDENT * dependents.max(0) as f64;
+    let s = FAMILY_BENEFIT_RATE * utils::floor_zero(amount - FAMILY_BENEFIT_INCOME_RATE * A);
+    utils::round(utils::clamp(s, 0.0, T4))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_S_without_dependents() {
+        // 10.8% × (2,065 − 9% × 15,000)
+        assert_eq!(S(1000.0, 15000.0, 0), 77.22);
+        assert_eq!(S(1000.0, 30000.0, 0), 0.0);
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_S_with_several_dependents() {
+        // 10.8% × (2,065 + 3 × 2,752 − 9% × 30,000)
+        assert_eq!(S(2000.0, 30000.0, 3), 823.07);
+        assert!(S(2000.0, 30000.0, 3) > S(2000.0, 30000.0, 1));
+        // Never more than the basic provincial tax
+        assert_eq!(S(500.0, 30000.0, 3), 500.0);
+    }
+}
@@ -2,4 +2,7 @@
 
 pub mod provincial_income_tax;
 pub mod ontario;
+pub mod british_columbia;
+pub mod prince_edward_island;
 
+pub mod manitoba;
@@ -1,9 +1,15 @@
 //! Ontario Provincial Income Tax
 
+use crate::error::PayrollError;
+use crate::province::Province;
+use crate::provincial_income_tax::provincial_income_tax;
 use crate::utils;
+use crate::year::{v2025, Bracket, YearConstants};
 
 /** Provincial surtax calculated on the basic provincial tax (only applies to Ontario)
 *
+*   Each surtax rate applies to the basic provincial tax above its threshold; tax exactly on a threshold is not surtaxed at that threshold's rate.
+*
 *
 * Given:
 *
@@ -11,83 +17,35 @@ use crate::utils;
 */
 #[allow(non_snake_case)]
 pub fn V1(T4: f64) -> f64 {
-    // TODO: these fixed numbers have to be extracted from the csv file 'thrrtsmnts-01-25e.csv'
-    if T4 <= 5710.0 {
-        return 0.0;
-    } else
-
-    if T4 > 5710.0 && T4 < 7307.0 {
-        return 0.2 * (T4 - 5710.0);
-    } else
-    // if T4 > 7307.0
-    {
-        return utils::round(0.2 * (T4 - 5710.0) + 0.36 * (T4 - 7307.0));
-    }
+    provincial_income_tax::surtax(&v2025::ON_SURTAX, T4)
 }
 
 /** Additional tax calculated on taxable income (only applies to the Ontario Health Premium)
 *
+*   A band applies above its threshold: the previous band's maximum plus the band's rate on the income above the threshold, up to the band's maximum. Each band includes its upper threshold, so there is no gap at 20,000, 36,000, 48,000, 72,000 or 200,000.
 *
 * Given:
 *
 *   A: Annual taxable income
+*
+*   year: The constants for the year of the calculation
 */
 #[allow(non_snake_case)]
-pub fn V2(A: f64) -> f64 {
-    let v2: f64;
-    if A < 20000.0 {
-        return 0.0;
-    } else
-
-    if A > 20000.0 && A < 36000.0 {
-        v2 = 0.06 * (A - 20000.0);
-        if v2 < 300.0 {
-                return utils::round(v2);
-            } else {
-                return 300.0;
-            }
-    } else
-
-    if A > 36000.0 && A < 48000.0 {
-        v2 = 300.0 + (0.06 * (A - 36000.0));
-        if v2 < 450.0 {
-            return utils::round(v2);
-        } else {
-            return 450.0;
-        }
-    } else
-
-    if A > 48000.0 && A < 72000.0 {
-        v2 = 600.0 + (0.25 * (A - 72000.0));
-        if v2 < 750.0 {
-            return utils::round(v2);
-        } else {
-            return 750.0;
-        }
-    } else
-
-    if A > 72000.0 && A < 200000.0 {
-        v2 = 600.0 + (0.25 * (A - 72000.0));
-        if v2 < 900.0 {
-            return utils::round(v2);
-        } else {
-            return 900.0;
-        }
-    } else
-    // if A > 200000.0
-    {
-        v2 = 750.0 + (0.25 * (A - 200000.0));
-        if v2 < 900.0 {
-            return utils::round(v2);
-        }
-        else {
-            return 900.0;
+pub fn V2(A: f64, year: &YearConstants) -> f64 {
+    let mut v2 = 0.0;
+    let mut base = 0.0;
+    for &(threshold, rate, maximum) in year.on_health_premium {
+        if A > threshold {
+            v2 = (base + rate * (A - threshold)).min(maximum);
         }
+        base = maximum;
     }
+    utils::round(v2)
 }
 
 /** Provincial tax reduction (only applies to Ontario and British Columbia)
 *
+*   The lesser of T4 + V1, and twice the basic reduction amount plus Y, less T4 + V1. Never negative.
 *
 * Given:
 *
@@ -96,11 +54,13 @@ pub fn V2(A: f64) -> f64 {
 *   V1: Provincial surtax calculated on the basic provincial tax (only applies to Ontario)
 *
 *   Y: Additional provincial tax reduction amount based on the number of eligible dependents used in the calculation of Factor S (only applies to Ontario)
+*
+*   year: The constants for the year of the calculation
 */
 #[allow(non_snake_case)]
-pub fn S(T4: f64, V1: f64, Y: i64) -> f64 {
+pub fn S(T4: f64, V1: f64, Y: f64, year: &YearConstants) -> f64 {
     let s1: f64 = T4 + V1;
-    let s2: f64 = (2.0 * 294.0 + Y as f64) - (T4 + V1);
+    let s2: f64 = (2.0 * year.on_reduction_base + Y) - (T4 + V1);
     if s1 < 0.0 && s2 < 0.0 {
         return 0.0;
     }
@@ -126,9 +86,178 @@ pub fn S(T4: f64, V1: f64, Y: i64) -> f64 {
 *   number_of_disabled_dependants: Number of disabled dependants
 *
 *   number_of_minor_dependents: Number of dependents under the age of 19
+*
+*   year: The constants for the year of the calculation
+*/
+#[allow(non_snake_case)]
+pub fn Y(number_of_disabled_dependants: i64, number_if_minor_dependents: i64, year: &YearConstants) -> f64 {
+    year.on_per_dependent * number_of_disabled_dependants as f64 + year.on_per_dependent * number_if_minor_dependents as f64
+}
+
+/** Annual taxable incomes at which the basic provincial tax (T4) reaches the first and second surtax thresholds
+*
+*   For an employee who claims only the basic personal amount (K1P), with no other credits; other credits raise both incomes. Each is the highest income, to the cent, with T4 at or below the threshold, so the surtax starts above it.
+*
+*   Returns `PayrollError::UnsupportedProvince` for a year without Ontario tables, and `PayrollError::UnsupportedYear` when the year's Ontario surtax does not have two tiers.
+*
+* Given:
+*
+*   year: The constants for the year of the calculation
 */
 #[allow(non_snake_case)]
-pub fn Y(number_of_disabled_dependants: i64, number_if_minor_dependents: i64) -> f64 {
-    544.0 * number_of_disabled_dependants as f64 + 544.0 * number_if_minor_dependents as f64
+pub fn income_at_surtax_threshold(year: &YearConstants) -> Result<(f64, f64), PayrollError> {
+    let brackets = provincial_income_tax::brackets(Province::Ontario, year)?;
+    let K1P = provincial_income_tax::K1P(
+        provincial_income_tax::lowest_rate(Province::Ontario, year),
+        provincial_income_tax::basic_amount(Province::Ontario, year)?,
+    );
+    let income = |threshold: f64| income_at_T4(brackets, K1P, threshold);
+    match year.on_surtax {
+        [(first, _), (second, _), ..] => Ok((income(*first), income(*second))),
+        _ => Err(PayrollError::UnsupportedYear(year.year)),
+    }
+}
+
+// T4 = V × A − KP − K1P in the bracket of A, solved for A in the first bracket that contains it
+#[allow(non_snake_case)]
+fn income_at_T4(brackets: &[Bracket], K1P: f64, T4: f64) -> f64 {
+    let upper_thresholds = brackets.iter().skip(1).map(|b| b.threshold).chain([f64::INFINITY]);
+    let A = brackets
+        .iter()
+        .zip(upper_thresholds)
+        .map(|(b, upper)| ((T4 + b.constant + K1P) / b.rate, upper))
+        .find(|(A, upper)| A <= upper)
+        .map(|(A, _)| A)
+        .unwrap_or(0.0);
+    (A * 100.0).floor() / 100.0
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::year::{v2023, v2024};
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_V1_at_thresholds() {
+        assert_eq!(V1(5710.0), 0.0);
+        assert_eq!(V1(7307.0), 319.4);
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_V1_starts_at_the_income_at_each_surtax_threshold() {
+        let year = &v2025::CONSTANTS;
+        let (first, second) = income_at_surtax_threshold(year).unwrap();
+        assert!(first < second);
+
+        let K1P = utils::round(0.0505 * v2025::ONTARIO_BASIC_AMT);
+        let T4 = |A: f64| {
+            let b = crate::year::bracket(&v2025::ONTARIO_BRACKETS, A);
+            provincial_income_tax::T4(b.rate, A, b.constant, K1P, 0.0, 0.0, 0.0)
+        };
+        assert_eq!(V1(T4(first)), 0.0);
+        assert!(V1(T4(first + 1.0)) > 0.0);
+
+        // Above the second threshold, the 36% rate adds to the 20%
+        assert!(T4(second) <= 7307.0 && T4(second + 1.0) > 7307.0);
+        assert_eq!(V1(T4(second)), utils::round(0.20 * (T4(second) - 5710.0)));
+        assert!(V1(T4(second + 1.0)) > utils::round(0.20 * (T4(second + 1.0) - 5710.0)));
+
+        assert_eq!(income_at_surtax_threshold(&v2024::CONSTANTS), Err(PayrollError::UnsupportedProvince(Province::Ontario)));
+        let one_tier = YearConstants { on_surtax: &[(5710.0, 0.20)], ..v2025::CONSTANTS };
+        assert_eq!(income_at_surtax_threshold(&one_tier), Err(PayrollError::UnsupportedYear(2025)));
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_V1_in_each_zone() {
+        assert_eq!(V1(4000.0), 0.0);
+        assert_eq!(V1(6000.0), 58.0);
+        assert_eq!(V1(9000.0), utils::round(0.2 * 3290.0 + 0.36 * 1693.0));
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_V2_at_band_thresholds() {
+        let year = &v2025::CONSTANTS;
+        assert_eq!(V2(20000.0, year), 0.0);
+        assert_eq!(V2(36000.0, year), 300.0);
+        assert_eq!(V2(48000.0, year), 450.0);
+        assert_eq!(V2(72000.0, year), 600.0);
+        assert_eq!(V2(200000.0, year), 750.0);
+        assert_eq!(V2(200600.0, year), 900.0);
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_V2_never_decreases() {
+        let year = &v2025::CONSTANTS;
+        let mut previous = V2(0.0, year);
+        for dollars in (0..260000).step_by(50) {
+            let v2 = V2(dollars as f64, year);
+            assert!(v2 >= previous, "V2({}) = {} < {}", dollars, v2, previous);
+            previous = v2;
+        }
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_S_without_dependents() {
+        let year = &v2025::CONSTANTS;
+        assert_eq!(Y(0, 0, year), 0.0);
+        // Fully reduced while T4 + V1 is at most the basic reduction
+        assert_eq!(S(200.0, 0.0, 0.0, year), 200.0);
+        assert_eq!(S(294.0, 0.0, 0.0, year), 294.0);
+        // (2 × 294) − 400
+        assert_eq!(S(400.0, 0.0, 0.0, year), 188.0);
+        assert_eq!(S(588.0, 0.0, 0.0, year), 0.0);
+        assert_eq!(S(1000.0, 0.0, 0.0, year), 0.0);
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_S_with_several_dependents() {
+        let year = &v2025::CONSTANTS;
+        let Y = Y(1, 3, year);
+        assert_eq!(Y, 4.0 * 544.0);
+        // (2 × 294 + 4 × 544) − (2,500 + 100)
+        assert_eq!(S(2500.0, 100.0, Y, year), 164.0);
+        assert_eq!(S(1000.0, 0.0, Y, year), 1000.0);
+        assert_eq!(S(3000.0, 0.0, Y, year), 0.0);
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_S_and_Y_use_the_year_amounts() {
+        let year = &v2024::CONSTANTS;
+        assert_eq!(Y(0, 2, year), 2.0 * 529.0);
+        assert_eq!(S(400.0, 0.0, 0.0, year), 2.0 * 286.0 - 400.0);
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_V2_between_48000_and_200000() {
+        let year = &v2025::CONSTANTS;
+        assert_eq!(V2(48400.0, year), 550.0);
+        assert_eq!(V2(60000.0, year), 600.0);
+        assert_eq!(V2(72400.0, year), 700.0);
+        assert_eq!(V2(100000.0, year), 750.0);
+        assert_eq!(V2(200400.0, year), 850.0);
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_V2_uses_the_bands_of_the_year() {
+        // The bands have not changed since the premium was introduced
+        for year in [&v2023::CONSTANTS, &v2024::CONSTANTS] {
+            assert_eq!(year.on_health_premium, v2025::CONSTANTS.on_health_premium);
+            assert_eq!(V2(60000.0, year), 600.0);
+        }
+        let doubled = YearConstants { on_health_premium: &[(20000.0, 0.12, 600.0)], ..v2025::CONSTANTS };
+        assert_eq!(V2(22000.0, &doubled), 240.0);
+        assert_eq!(V2(100000.0, &doubled), 600.0);
+    }
+}
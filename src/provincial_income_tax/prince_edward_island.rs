@@ -0,0 +1,56 @@
+//! Prince Edward Island Provincial Income Tax
+
+use crate::error::PayrollError;
+use crate::province::Province;
+use crate::utils;
+use crate::year::{v2025, YearConstants};
+
+/** Low-income tax reduction
+*
+*   The full reduction applies up to the net income threshold, and is reduced by a percentage of net income above it. It can never exceed the basic provincial tax. Returns `PayrollError::UnsupportedProvince` for a year without the reduction's amounts.
+*
+*
+* Given:
+*
+*   T4: Annual basic provincial or territorial tax
+*
+*   A: Annual taxable income
+*
+*   year: The constants for the year of the calculation
+*/
+#[allow(non_snake_case)]
+pub fn S(T4: f64, A: f64, year: &YearConstants) -> Result<f64, PayrollError> {
+    let (base, threshold, rate) = match year.year {
+        2025 => (v2025::PEI_REDUCTION_BASE, v2025::PEI_REDUCTION_THRESHOLD, v2025::PEI_REDUCTION_RATE),
+        _ => return Err(PayrollError::UnsupportedProvince(Province::PrinceEdwardIsland)),
+    };
+    let mut s: f64 = base;
+    if A > threshold {
+        s -= rate * (A - threshold);
+    }
+    Ok(utils::round(utils::clamp(s, 0.0, T4)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::year::v2024;
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_S_phases_out_above_the_threshold() {
+        let year = &v2025::CONSTANTS;
+        assert_eq!(S(1000.0, 20000.0, year), Ok(v2025::PEI_REDUCTION_BASE));
+        // 350 − 5% × (27,000 − 23,000)
+        assert_eq!(S(1000.0, 27000.0, year), Ok(150.0));
+        assert_eq!(S(100.0, 20000.0, year), Ok(100.0));
+        assert_eq!(S(1000.0, 30000.0, year), Ok(0.0));
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_S_for_a_year_without_amounts() {
+        assert_eq!(S(1000.0, 20000.0, &v2024::CONSTANTS), Err(PayrollError::UnsupportedProvince(Province::PrinceEdwardIsland)));
+    }
+}
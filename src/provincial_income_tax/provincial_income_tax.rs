@@ -1,7 +1,86 @@
 //! Annual Basic Provincial or Territorial Tax
 
+use crate::error::PayrollError;
+use crate::payroll::Dependents;
+use crate::province::Province;
+use crate::provincial_income_tax::{british_columbia, manitoba, ontario, prince_edward_island};
 use crate::utils;
-use crate::year::v2025;
+use crate::year::{self, v2025, Bracket, YearConstants};
+
+/** Lowest provincial or territorial tax rate, used for the K1P and K2P credits
+*
+* Given:
+*
+*   province: Province or territory of employment
+*
+*   year: The constants for the year of the calculation
+*/
+pub fn lowest_rate(province: Province, year: &YearConstants) -> f64 {
+    year.lowest_provincial_rates
+        .iter()
+        .find(|(p, _)| *p == province)
+        .map(|(_, rate)| *rate)
+        .unwrap_or(0.0)
+}
+
+/// Provinces and territories with provincial or territorial tax tables
+pub fn supported_provinces() -> &'static [Province] {
+    &[Province::Alberta, Province::Ontario]
+}
+
+/** Provincial or territorial rates (V), income thresholds (A) and constants (KP)
+*
+*   Returns `PayrollError::UnsupportedProvince` for a province or territory without tables for the year.
+*
+* Given:
+*
+*   province: Province or territory of employment
+*
+*   year: The constants for the year of the calculation
+*/
+pub fn brackets(province: Province, year: &YearConstants) -> Result<&'static [Bracket], PayrollError> {
+    match (province, year.year) {
+        (Province::Ontario, 2025) => Ok(&v2025::ONTARIO_BRACKETS),
+        (Province::Alberta, 2025) => Ok(&v2025::ALBERTA_BRACKETS),
+        _ => Err(PayrollError::UnsupportedProvince(province)),
+    }
+}
+
+/** Provincial or territorial basic personal amount, the total claim amount (TCP) of an employee who claims only the basic amount
+*
+*   Returns `PayrollError::UnsupportedProvince` for a province or territory without tables for the year.
+*
+* Given:
+*
+*   province: Province or territory of employment
+*
+*   year: The constants for the year of the calculation
+*/
+pub fn basic_amount(province: Province, year: &YearConstants) -> Result<f64, PayrollError> {
+    match (province, year.year) {
+        (Province::Ontario, 2025) => Ok(v2025::ONTARIO_BASIC_AMT),
+        (Province::Alberta, 2025) => Ok(v2025::ALBERTA_BASIC_AMT),
+        (Province::Quebec, 2025) => Ok(v2025::QUEBEC_BASIC_AMT),
+        _ => Err(PayrollError::UnsupportedProvince(province)),
+    }
+}
+
+/** Provincial or territorial constant (KP) for the annual taxable income
+*
+*   Computed from the province's rate schedule for the year; matches the constant published with the provincial rates.
+*
+* Given:
+*
+*   province: Province or territory of employment
+*
+*   A: Annual taxable income
+*
+*   year: The constants for the year of the calculation
+*/
+#[allow(non_snake_case)]
+pub fn provincial_constant(province: Province, A: f64, year: &YearConstants) -> Result<f64, PayrollError> {
+    Ok(year::constant(brackets(province, year)?, A))
+}
 
 /** Annual basic provincial or territorial tax
 *
@@ -28,11 +107,78 @@ use crate::year::v2025;
 */
 #[allow(non_snake_case)]
 pub fn T4(V: f64, A: f64, KP: f64, K1P: f64, K2P: f64, K3P: f64, K4P: f64) -> f64 {
-    let t4: f64 = (V * A) - KP - K1P - K2P - K3P - K4P;
-    if t4 < 0.0 {
-        return 0.0;
+    utils::round(utils::saturating_sub_credits(V * A, &[KP, K1P, K2P, K3P, K4P]))
+}
+
+/** Provincial or territorial non-refundable tax credits used in the calculation of T4
+*
+*   Build with `ProvincialCredits::new` for the province and year, which sets the lowest rate used for K1P and K2P, and the methods for each credit; a credit not set is zero.
+*/
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[allow(non_snake_case)]
+pub struct ProvincialCredits {
+    /// Lowest provincial or territorial tax rate; see `lowest_rate`
+    pub lowest_rate: f64,
+    /// Provincial or territorial non-refundable personal tax credit
+    pub K1P: f64,
+    /// Provincial or territorial base CPP contributions and EI premiums tax credits for the year
+    pub K2P: f64,
+    /// Other provincial or territorial non-refundable tax credits
+    pub K3P: f64,
+    /// Territorial non-refundable tax credit calculated using the Canada employment amount
+    pub K4P: f64,
+}
+
+#[allow(non_snake_case)]
+impl ProvincialCredits {
+    /// No credits, at the lowest tax rate of the province or territory for the year.
+    pub fn new(province: Province, year: &YearConstants) -> Self {
+        ProvincialCredits { lowest_rate: lowest_rate(province, year), K1P: 0.0, K2P: 0.0, K3P: 0.0, K4P: 0.0 }
     }
-    utils::round(t4)
+
+    /// Sets K1P from the total claim amount (TCP) on the provincial or territorial Form TD1; see `K1P`.
+    pub fn claim(self, TCP: f64) -> Self {
+        ProvincialCredits { K1P: K1P(self.lowest_rate, TCP), ..self }
+    }
+
+    /// Sets K1P from a total claim amount changed mid-year, with PR pay periods left when it took effect; the change is prorated as in `federal_income_tax::K1_prorated`.
+    pub fn changed_claim(self, P: i64, PR: i64, TCP_before: f64, TCP: f64) -> Self {
+        self.claim(TCP_before + (TCP - TCP_before) * P as f64 / PR as f64)
+    }
+
+    /// Sets K2P from the CPP contributions and EI premiums for the pay period, or each annual maximum once it is reached; see `K2P`.
+    pub fn contributions(self, P: i64, PM: i64, C: f64, EI: f64, cpp_max_reached: bool, ei_max_reached: bool, year: &YearConstants) -> Self {
+        ProvincialCredits { K2P: K2P(self.lowest_rate, P, PM, C, EI, cpp_max_reached, ei_max_reached, year), ..self }
+    }
+
+    /// Sets K3P to the other provincial or territorial non-refundable tax credits.
+    pub fn other_credits(self, K3P: f64) -> Self {
+        ProvincialCredits { K3P, ..self }
+    }
+
+    /// Sets K4P, the territorial credit for the Canada employment amount.
+    pub fn employment_credit(self, K4P: f64) -> Self {
+        ProvincialCredits { K4P, ..self }
+    }
+}
+
+/** Annual basic provincial or territorial tax, with the credits bundled
+*
+*   The same as T4 with each credit taken from `credits`.
+*
+* Given:
+*
+*   V: Provincial or territorial tax rate for the year
+*
+*   A: Annual taxable income
+*
+*   KP: Provincial or territorial constant
+*
+*   credits: Provincial or territorial non-refundable tax credits K1P to K4P
+*/
+#[allow(non_snake_case)]
+pub fn T4_credits(V: f64, A: f64, KP: f64, credits: &ProvincialCredits) -> f64 {
+    T4(V, A, KP, credits.K1P, credits.K2P, credits.K3P, credits.K4P)
 }
 
 /** Annual provincial or territorial tax deduction (except Quebec)
@@ -54,11 +200,7 @@ pub fn T4(V: f64, A: f64, KP: f64, K1P: f64, K2P: f64, K3P: f64, K4P: f64) -> f6
 */
 #[allow(non_snake_case)]
 pub fn T2(T4: f64, V1: f64, V2: f64, S: f64, P: i64, LCP: f64) -> f64 {
-    let t2: f64 = T4 + V1 + V2 - S - (P as f64 * LCP);
-    if t2 < 0.0 {
-        return 0.0;
-    }
-    utils::round(t2)
+    utils::round(utils::floor_zero(T4 + V1 + V2 - S - (P as f64 * LCP)))
 }
 
 /** Provincial or territorial non-refundable personal tax credit
@@ -67,13 +209,13 @@ pub fn T2(T4: f64, V1: f64, V2: f64, S: f64, P: i64, LCP: f64) -> f64 {
 *
 * Given:
 *
-*   lowest_provincial_tax_rate: Lowest provincial tax rate
+*   lowest_provincial_tax_rate: Lowest provincial or territorial tax rate; see `lowest_rate`
 *
-*   TCP: "Total claim amount," reported on the provincial or territorial Form TD1.
+*   TCP: "Total claim amount," reported on the provincial or territorial Form TD1. An employee can claim less than the basic personal amount, down to zero, to have more tax withheld; the credit is never negative.
 */
 #[allow(non_snake_case)]
 pub fn K1P(lowest_provincial_tax_rate: f64, TCP: f64) -> f64 {
-    utils::round(lowest_provincial_tax_rate * TCP)
+    utils::round(utils::floor_zero(lowest_provincial_tax_rate * TCP))
 }
 
 /** Provincial or territorial base Canada Pension Plan contributions and employment insurance premiums tax credits for the year (the lowest provincial or territorial tax rate is used to calculate this credit).
@@ -82,7 +224,7 @@ pub fn K1P(lowest_provincial_tax_rate: f64, TCP: f64) -> f64 {
 *
 *  Given:
 *
-*   lowest_provincial_tax_rate:
+*   lowest_provincial_tax_rate: Lowest provincial or territorial tax rate; see `lowest_rate`
 *
 *   P: The number of pay periods in the year
 *
@@ -91,26 +233,77 @@ pub fn K1P(lowest_provincial_tax_rate: f64, TCP: f64) -> f64 {
 *   C: Canada (or Quebec) Pension Plan contributions for the pay period
 *
 *   EI: Employment insurance premiums for the pay period
+*
+*   cpp_max_reached: The employee has contributed the maximum CPP for the year with the employer; the CPP part of the credit is then on the maximum base CPP contribution, as for K2
+*
+*   ei_max_reached: The employee has paid the maximum EI premium for the year with the employer; the EI part of the credit is then on the maximum EI premium, as for K2
+*
+*   year: The constants for the year of the calculation
 */
 #[allow(non_snake_case)]
-pub fn K2P(lowest_provincial_tax_rate: f64, P: i64, PM: i64, C: f64, EI: f64) -> f64 {
-    let mut k2p: f64;
-
-    let mut cpp: f64 = P as f64 * C * (0.0495/0.0595);
-    if cpp > v2025::CPP_MAX_CONTRIBUTIONS {
-        cpp = v2025::CPP_MAX_CONTRIBUTIONS;
-    }
-    k2p = lowest_provincial_tax_rate * (cpp * (PM/12) as f64);
+pub fn K2P(
+    lowest_provincial_tax_rate: f64,
+    P: i64,
+    PM: i64,
+    C: f64,
+    EI: f64,
+    cpp_max_reached: bool,
+    ei_max_reached: bool,
+    year: &YearConstants,
+) -> f64 {
+    let base_share = year.cpp_base_rate / (year.cpp_base_rate + year.cpp_first_additional_rate);
+    let cpp: f64 = if cpp_max_reached {
+        year.cpp_max_contributions
+    } else {
+        utils::clamp(P as f64 * C * base_share, 0.0, year.cpp_max_contributions)
+    };
+    let mut k2p: f64 = lowest_provincial_tax_rate * (cpp * (PM as f64 / 12.0));
 
-    let mut ei: f64 = P as f64 * EI;
-    if ei > v2025::EI_MAX_CONTRIBUTIONS {
-        ei = v2025::EI_MAX_CONTRIBUTIONS;
-    }
+    let ei: f64 = if ei_max_reached { year.ei_max_contributions } else { utils::clamp(P as f64 * EI, 0.0, year.ei_max_contributions) };
     k2p += lowest_provincial_tax_rate * ei;
 
     utils::round(k2p)
 }
 
+/// Lowest provincial or territorial tax rates outside this band are rejected by `try_K1P` and `try_K2P`.
+const PLAUSIBLE_RATES: std::ops::RangeInclusive<f64> = 0.0..=0.25;
+
+fn check_rate(lowest_provincial_tax_rate: f64) -> Result<(), PayrollError> {
+    if !PLAUSIBLE_RATES.contains(&lowest_provincial_tax_rate) {
+        return Err(PayrollError::ImplausibleRate(lowest_provincial_tax_rate));
+    }
+    Ok(())
+}
+
+/** Provincial or territorial non-refundable personal tax credit, rejecting an implausible rate
+*
+*   Returns `PayrollError::ImplausibleRate` when the lowest rate is not between 0 and 0.25, such as 5.05 passed for 5.05%; see `K1P`.
+*/
+#[allow(non_snake_case)]
+pub fn try_K1P(lowest_provincial_tax_rate: f64, TCP: f64) -> Result<f64, PayrollError> {
+    check_rate(lowest_provincial_tax_rate)?;
+    Ok(K1P(lowest_provincial_tax_rate, TCP))
+}
+
+/** Provincial or territorial base CPP contributions and EI premiums tax credits, rejecting an implausible rate
+*
+*   Returns `PayrollError::ImplausibleRate` when the lowest rate is not between 0 and 0.25; see `K2P`.
+*/
+#[allow(non_snake_case)]
+pub fn try_K2P(
+    lowest_provincial_tax_rate: f64,
+    P: i64,
+    PM: i64,
+    C: f64,
+    EI: f64,
+    cpp_max_reached: bool,
+    ei_max_reached: bool,
+    year: &YearConstants,
+) -> Result<f64, PayrollError> {
+    check_rate(lowest_provincial_tax_rate)?;
+    Ok(K2P(lowest_provincial_tax_rate, P, PM, C, EI, cpp_max_reached, ei_max_reached, year))
+}
+
 /** Provincial or territorial base Canada Pension Plan contributions and employment insurance premiums tax credits for the year (the lowest provincial or territorial tax rate is used to calculate this credit).
 *
 *   If an employee reaches the maximum CPP or EI for the year with an employer, the instructions in the note for the K2 factor also apply to the K2P factor. For employees paid by commission, use the federal K2 formula for commissions and replace the lowest federal rate in the K2 formula with the lowest provincial or territorial tax rate
@@ -119,7 +312,7 @@ pub fn K2P(lowest_provincial_tax_rate: f64, P: i64, PM: i64, C: f64, EI: f64) ->
 *
 *  Given:
 *
-*   lowest_provincial_tax_rate:
+*   lowest_provincial_tax_rate: Lowest provincial or territorial tax rate; see `lowest_rate`
 *
 *   PE: Pensionable earnings for the pay period, or the gross income plus any taxable benefits for the pay period, plus PEYTD
 *
@@ -127,28 +320,307 @@ pub fn K2P(lowest_provincial_tax_rate: f64, P: i64, PM: i64, C: f64, EI: f64) ->
 *
 *   B1: Gross bonuses, retroactive pay increases, vacation pay when vacation is not taken, accumulated overtime payments or other non-periodic payments year-to-date (before the pay period)
 *
-*   EI: Employment insurance premiums for the pay period
+*   IE: Insurable earnings for the pay period, including insurable taxable benefits for the pay period, plus IEYTD
+*
+*   year: The constants for the year of the calculation
 */
 #[allow(non_snake_case)]
-pub fn K2P_grad(lowest_provincial_tax_rate: f64, PE: i64, S1: f64, B1: f64, EI: f64) -> f64 {
-    let mut k2p: f64;
+pub fn K2P_grad(lowest_provincial_tax_rate: f64, PE: f64, S1: f64, B1: f64, IE: f64, year: &YearConstants) -> f64 {
+    let cpp: f64 = utils::clamp(year.cpp_base_rate * ((S1 * PE) + B1 - year.cpp_basic_exemption), 0.0, year.cpp_max_contributions);
+    let ei: f64 = utils::clamp(year.ei_rate * ((S1 * IE) + B1), 0.0, year.ei_max_contributions);
 
-    let mut cpp: f64 = (S1 * PE as f64) + B1 - 3500.0;
-    if cpp.is_sign_negative() {
-        cpp = 0.0;
-    }
-    if cpp > v2025::CPP_MAX_CONTRIBUTIONS {
-        cpp = v2025::CPP_MAX_CONTRIBUTIONS;
+    utils::round(lowest_provincial_tax_rate * (cpp + ei))
+}
+
+
+/** Surtax on the basic provincial or territorial tax, for a schedule of thresholds and rates
+*
+*   Each rate applies to the basic provincial tax above its threshold; tax exactly on a threshold is not surtaxed at that threshold's rate.
+*
+* Given:
+*
+*   schedule: Thresholds and rates, in ascending order of threshold
+*
+*   T4: Annual basic provincial or territorial tax
+*/
+#[allow(non_snake_case)]
+pub fn surtax(schedule: &[(f64, f64)], T4: f64) -> f64 {
+    let mut v1: f64 = 0.0;
+    for &(threshold, rate) in schedule {
+        if T4 > threshold {
+            v1 += rate * (T4 - threshold);
+        }
     }
+    utils::round(v1)
+}
 
-    k2p = lowest_provincial_tax_rate * 0.0495 * cpp;
+/** Provincial surtax calculated on the basic provincial tax (Factor V1)
+*
+*   Ontario has a surtax every year, and Prince Edward Island had one until 2024. Provinces and territories without a surtax return zero.
+*
+* Given:
+*
+*   province: Province or territory of employment
+*
+*   T4: Annual basic provincial or territorial tax
+*
+*   year: The constants for the year of the calculation
+*/
+#[allow(non_snake_case)]
+pub fn provincial_surtax(province: Province, T4: f64, year: &YearConstants) -> f64 {
+    match province {
+        Province::Ontario => surtax(year.on_surtax, T4),
+        Province::PrinceEdwardIsland => surtax(year.pei_surtax, T4),
+        _ => 0.0,
+    }
+}
 
-    let mut ei: f64 = (S1 * EI) + B1;
-    if ei > v2025::EI_MAX_CONTRIBUTIONS {
-        ei = v2025::EI_MAX_CONTRIBUTIONS;
+/** Additional tax calculated on taxable income for the province or territory (Factor V2)
+*
+*   Only Ontario has one, the Ontario Health Premium; see `ontario::V2`. Other provinces and territories return zero.
+*
+* Given:
+*
+*   province: Province or territory of employment
+*
+*   A: Annual taxable income
+*
+*   year: The constants for the year of the calculation
+*/
+#[allow(non_snake_case)]
+pub fn health_premium(province: Province, A: f64, year: &YearConstants) -> f64 {
+    match province {
+        Province::Ontario => ontario::V2(A, year),
+        _ => 0.0,
     }
-    k2p += lowest_provincial_tax_rate * 0.0164 * ei;
+}
 
-    utils::round(k2p)
+/** Provincial tax reduction for the province or territory (Factor S)
+*
+*   Ontario's and Manitoba's reductions are based on the number of dependants; British Columbia's and Prince Edward Island's on annual taxable income. Provinces and territories without a reduction return zero, and a province without the reduction's amounts for the year `PayrollError::UnsupportedProvince`. Manitoba's is its historical family tax benefit (see `manitoba::S`), unused until there are Manitoba tax tables.
+*
+*
+* Given:
+*
+*   province: Province or territory of employment
+*
+*   T4: Annual basic provincial or territorial tax
+*
+*   V1: Provincial surtax calculated on the basic provincial tax (only applies to Ontario)
+*
+*   A: Annual taxable income
+*
+*   dependents: Dependants claimed for Factor Y (only applies to Ontario and Manitoba)
+*
+*   year: The constants for the year of the calculation
+*/
+#[allow(non_snake_case)]
+pub fn tax_reduction(province: Province, T4: f64, V1: f64, A: f64, dependents: Dependents, year: &YearConstants) -> Result<f64, PayrollError> {
+    match province {
+        Province::Ontario => {
+            let Y = ontario::Y(dependents.disabled, dependents.under_19, year);
+            Ok(ontario::S(T4, V1, Y, year))
+        }
+        Province::BritishColumbia => british_columbia::S(T4, A, year),
+        Province::PrinceEdwardIsland => prince_edward_island::S(T4, A, year),
+        Province::Manitoba => Ok(manitoba::S(T4, A, dependents.under_19)),
+        _ => Ok(0.0),
+    }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::year::{v2023, v2024};
+
+    #[test]
+    fn test_lowest_rate() {
+        assert_eq!(lowest_rate(Province::Ontario, &v2025::CONSTANTS), 0.0505);
+        assert_eq!(lowest_rate(Province::Alberta, &v2025::CONSTANTS), 0.10);
+        assert_eq!(lowest_rate(Province::Nunavut, &v2025::CONSTANTS), 0.04);
+        assert_eq!(lowest_rate(Province::Ontario, &v2025::CONSTANTS), v2025::ONTARIO_BRACKETS[0].rate);
+        assert_eq!(lowest_rate(Province::Alberta, &v2025::CONSTANTS), v2025::ALBERTA_BRACKETS[0].rate);
+    }
+
+    #[test]
+    fn test_supported_provinces_have_tables() {
+        assert!(supported_provinces().contains(&Province::Ontario));
+        for &province in supported_provinces() {
+            assert!(brackets(province, &v2025::CONSTANTS).is_ok(), "{:?}", province);
+        }
+        assert!(!supported_provinces().contains(&Province::Quebec));
+    }
+
+    #[test]
+    fn test_provincial_constant_matches_published_constants() {
+        for province in [Province::Ontario, Province::Alberta] {
+            for b in brackets(province, &v2025::CONSTANTS).unwrap() {
+                assert_eq!(provincial_constant(province, b.threshold + 1.0, &v2025::CONSTANTS), Ok(b.constant));
+            }
+        }
+        assert_eq!(provincial_constant(Province::Ontario, 60000.0, &v2025::CONSTANTS), Ok(2168.0));
+        assert_eq!(provincial_constant(Province::Ontario, 160000.0, &v2025::CONSTANTS), Ok(5794.0));
+        assert_eq!(provincial_constant(Province::Alberta, 200000.0, &v2025::CONSTANTS), Ok(4839.0));
+        assert_eq!(
+            provincial_constant(Province::Manitoba, 60000.0, &v2025::CONSTANTS),
+            Err(PayrollError::UnsupportedProvince(Province::Manitoba))
+        );
+    }
+
+    #[test]
+    fn test_lowest_rate_covers_every_province() {
+        let provinces = [
+            Province::Alberta, Province::BritishColumbia, Province::Manitoba, Province::NewBrunswick,
+            Province::NewfoundlandAndLabrador, Province::NorthwestTerritories, Province::NovaScotia, Province::Nunavut,
+            Province::Ontario, Province::PrinceEdwardIsland, Province::Quebec, Province::Saskatchewan, Province::Yukon,
+        ];
+        for year in [&v2023::CONSTANTS, &v2024::CONSTANTS, &v2025::CONSTANTS] {
+            for province in provinces {
+                assert!(lowest_rate(province, year) > 0.0, "{:?} {}", province, year.year);
+            }
+        }
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_credits_and_tax_are_clamped() {
+        assert_eq!(K2P(0.0505, 26, 12, 129.3, 37.85, false, false, &v2025::CONSTANTS), 190.94);
+        assert_eq!(K2P(0.0505, 12, 12, 400.0, 2000.0, false, false, &v2025::CONSTANTS), 223.9);
+        assert_eq!(K2P_grad(0.0505, 20000.0, 13.0, 5000.0, 20000.0, &v2025::CONSTANTS), 223.9);
+        assert_eq!(T4(0.0915, 60000.0, 2168.0, 643.72, 200.0, 0.0, 0.0), 2478.28);
+        assert_eq!(T4(0.0505, 5000.0, 0.0, 643.72, 0.0, 0.0, 0.0), 0.0);
+        assert_eq!(T2(3000.0, 0.0, 450.0, 100.0, 26, 1.0), 3324.0);
+        assert_eq!(T2(10.0, 0.0, 0.0, 100.0, 26, 1.0), 0.0);
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_K2P_grad_for_2024() {
+        // 5.05% × (4.95% × (2 × 20,000 − 3,500) + 1.66% × 2 × 20,000)
+        assert_eq!(K2P_grad(0.0505, 20000.0, 2.0, 0.0, 20000.0, &v2024::CONSTANTS), utils::round(0.0505 * (1806.75 + 664.0)));
+        // Capped at the 2024 maximum base CPP contribution and EI premium
+        assert_eq!(K2P_grad(0.0505, 5000.0, 26.0, 0.0, 5000.0, &v2024::CONSTANTS), utils::round(0.0505 * (3217.5 + 1049.12)));
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_K1P_for_a_claim_of_zero_or_the_basic_amount() {
+        assert_eq!(K1P(0.0505, 0.0), 0.0);
+        assert_eq!(K1P(0.0505, -500.0), 0.0);
+        assert_eq!(K1P(0.0505, v2025::ONTARIO_BASIC_AMT), utils::round(0.0505 * 12747.0));
+        assert_eq!(basic_amount(Province::Ontario, &v2025::CONSTANTS), Ok(v2025::ONTARIO_BASIC_AMT));
+        assert_eq!(basic_amount(Province::Alberta, &v2025::CONSTANTS), Ok(v2025::ALBERTA_BASIC_AMT));
+        assert_eq!(
+            basic_amount(Province::Manitoba, &v2025::CONSTANTS),
+            Err(PayrollError::UnsupportedProvince(Province::Manitoba))
+        );
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_try_K1P_and_K2P_reject_a_percentage() {
+        assert_eq!(try_K1P(15.0, 12747.0), Err(PayrollError::ImplausibleRate(15.0)));
+        assert_eq!(try_K2P(15.0, 26, 12, 129.3, 37.85, false, false, &v2025::CONSTANTS), Err(PayrollError::ImplausibleRate(15.0)));
+        assert_eq!(try_K1P(-0.05, 12747.0), Err(PayrollError::ImplausibleRate(-0.05)));
+
+        assert_eq!(try_K1P(0.0505, 12747.0), Ok(K1P(0.0505, 12747.0)));
+        assert_eq!(try_K2P(0.0505, 26, 12, 129.3, 37.85, false, false, &v2025::CONSTANTS), Ok(190.94));
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_K2P_once_the_maximums_are_reached() {
+        let maximum = utils::round(0.0505 * (v2025::CPP_MAX_CONTRIBUTIONS + v2025::EI_MAX_CONTRIBUTIONS));
+        assert_eq!(K2P(0.0505, 26, 12, 0.0, 0.0, false, false, &v2025::CONSTANTS), 0.0);
+        assert_eq!(K2P(0.0505, 26, 12, 0.0, 0.0, true, true, &v2025::CONSTANTS), maximum);
+        assert_eq!(K2P(0.0505, 26, 12, 129.3, 37.85, true, true, &v2025::CONSTANTS), maximum);
+
+        // Above the MIE but below the YMPE, only the EI maximum is reached
+        let cpp = 26.0 * 129.3 * (0.0495 / 0.0595);
+        assert_eq!(K2P(0.0505, 26, 12, 129.3, 0.0, false, true, &v2025::CONSTANTS), utils::round(0.0505 * (cpp + v2025::EI_MAX_CONTRIBUTIONS)));
+
+        let v2024 = &crate::year::v2024::CONSTANTS;
+        assert_eq!(K2P(0.0505, 26, 12, 0.0, 0.0, true, true, v2024), utils::round(0.0505 * (3217.5 + 1049.12)));
+        assert_eq!(K2P(0.0505, 26, 12, 400.0, 100.0, false, false, v2024), K2P(0.0505, 26, 12, 0.0, 0.0, true, true, v2024));
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_T4_when_credits_exceed_the_tax() {
+        assert_eq!(T4(0.0505, 12000.0, 0.0, K1P(0.0505, 12747.0), 0.0, 0.0, 0.0), 0.0);
+        assert_eq!(T4(0.0505, 20000.0, 0.0, 600.0, 100.0, 0.0, 0.0), 310.0);
+        assert_eq!(T4(0.0505, 20000.0, 0.0, 600.0, 100.0, 250.0, 100.0), 0.0);
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_T4_credits_matches_positional_call_for_ontario() {
+        let year = &v2025::CONSTANTS;
+        let credits = ProvincialCredits::new(Province::Ontario, year)
+            .claim(12747.0)
+            .contributions(26, 12, 150.0, 40.0, false, false, year)
+            .other_credits(80.0);
+        assert_eq!(credits.lowest_rate, 0.0505);
+        assert_eq!(credits.K4P, 0.0);
+
+        let Bracket { rate: V, constant: KP, .. } = *year::bracket(&v2025::ONTARIO_BRACKETS, 60000.0);
+        let K1P = K1P(0.0505, 12747.0);
+        let K2P = K2P(0.0505, 26, 12, 150.0, 40.0, false, false, &v2025::CONSTANTS);
+        assert_eq!(T4_credits(V, 60000.0, KP, &credits), T4(V, 60000.0, KP, K1P, K2P, 80.0, 0.0));
+    }
+
+    #[test]
+    fn test_provincial_surtax_ontario_two_tiers() {
+        let year = &v2025::CONSTANTS;
+        assert_eq!(provincial_surtax(Province::Ontario, 5000.0, year), 0.0);
+        assert_eq!(provincial_surtax(Province::Ontario, 6000.0, year), 58.0);
+        // 20% above 5,710 and a further 36% above 7,307
+        assert_eq!(provincial_surtax(Province::Ontario, 9000.0, year), utils::round(0.2 * 3290.0 + 0.36 * 1693.0));
+        assert_eq!(provincial_surtax(Province::Ontario, 9000.0, year), ontario::V1(9000.0));
+        assert_eq!(provincial_surtax(Province::Ontario, 6000.0, &v2024::CONSTANTS), utils::round(0.2 * 446.0));
+    }
+
+    #[test]
+    fn test_provincial_surtax_is_zero_without_a_schedule() {
+        assert_eq!(provincial_surtax(Province::Alberta, 20000.0, &v2025::CONSTANTS), 0.0);
+        assert_eq!(provincial_surtax(Province::PrinceEdwardIsland, 20000.0, &v2025::CONSTANTS), 0.0);
+        assert_eq!(provincial_surtax(Province::PrinceEdwardIsland, 13000.0, &v2023::CONSTANTS), 50.0);
+    }
+
+    #[test]
+    fn test_tax_reduction_ontario_with_dependents() {
+        let dependents = Dependents { under_19: 2, disabled: 1 };
+        // (2 × 294 + 3 × 544) − 1,000
+        assert_eq!(tax_reduction(Province::Ontario, 1000.0, 0.0, 30000.0, dependents, &v2025::CONSTANTS), Ok(1000.0));
+        assert_eq!(tax_reduction(Province::Ontario, 2000.0, 0.0, 45000.0, dependents, &v2025::CONSTANTS), Ok(220.0));
+        assert_eq!(tax_reduction(Province::Ontario, 2000.0, 0.0, 45000.0, Dependents::default(), &v2025::CONSTANTS), Ok(0.0));
+    }
+
+    #[test]
+    fn test_tax_reduction_british_columbia_low_income() {
+        assert_eq!(tax_reduction(Province::BritishColumbia, 900.0, 0.0, 20000.0, Dependents::default(), &v2025::CONSTANTS), Ok(562.0));
+        assert_eq!(tax_reduction(Province::BritishColumbia, 300.0, 0.0, 20000.0, Dependents::default(), &v2025::CONSTANTS), Ok(300.0));
+    }
+
+    #[test]
+    fn test_tax_reduction_manitoba_with_dependents() {
+        let dependents = Dependents { under_19: 3, disabled: 0 };
+        assert_eq!(tax_reduction(Province::Manitoba, 2000.0, 0.0, 30000.0, dependents, &v2025::CONSTANTS), Ok(823.07));
+        assert_eq!(tax_reduction(Province::Manitoba, 2000.0, 0.0, 30000.0, Dependents::default(), &v2025::CONSTANTS), Ok(0.0));
+    }
+
+    #[test]
+    fn test_health_premium_is_ontario_only() {
+        assert_eq!(health_premium(Province::Ontario, 20000.0, &v2025::CONSTANTS), 0.0);
+        assert_eq!(health_premium(Province::Ontario, 30000.0, &v2025::CONSTANTS), 300.0);
+        assert_eq!(health_premium(Province::Ontario, 60000.0, &v2025::CONSTANTS), ontario::V2(60000.0, &v2025::CONSTANTS));
+        assert_eq!(health_premium(Province::Alberta, 60000.0, &v2025::CONSTANTS), 0.0);
+        assert_eq!(health_premium(Province::Quebec, 60000.0, &v2025::CONSTANTS), 0.0);
+    }
+
+    #[test]
+    fn test_tax_reduction_alberta_is_zero() {
+        assert_eq!(tax_reduction(Province::Alberta, 2000.0, 0.0, 20000.0, Dependents { under_19: 3, disabled: 0 }, &v2025::CONSTANTS), Ok(0.0));
+    }
+}
@@ -0,0 +1,204 @@
+//! Quebec Pension Plan, Quebec Parental Insurance Plan, Quebec Employment Insurance Deductions and Quebec Income Tax
+
+use crate::error::PayrollError;
+use crate::other_deductions;
+use crate::province::Province;
+use crate::utils;
+use crate::year::{self, v2025, Bracket, YearConstants};
+
+/** Quebec Pension Plan contributions for the pay period
+*
+*   The lesser of the prorated annual maximum less D, and the contribution on the pay period's pensionable earnings.
+*
+* Given:
+*
+*   PM: The total number of months during which CPP and/or QPP contributions are required to be deducted
+*
+*   D: Employee’s year-to-date (before the pay period) Quebec Pension Plan contribution with the employer
+*
+*   PI: Pensionable earnings for the pay period
+*
+*   P: The number of pay periods in the year
+*
+*   year: The constants for the year of the calculation
+*/
+#[allow(non_snake_case)]
+pub fn QPP(PM: i64, D: f64, PI: f64, P: i64, year: &YearConstants) -> f64 {
+    let q1: f64 = year.qpp_max_contributions * (PM as f64 / 12.0) - D;
+    let q2: f64 = year.qpp_rate * (PI - (year.cpp_basic_exemption / P as f64));
+    let q: f64 = if q1 < q2 { q1 } else { q2 };
+    utils::round(utils::floor_zero(q))
+}
+
+/** Quebec Parental Insurance Plan premiums for the pay period
+*
+*   The lesser of the annual maximum less DQPIP, and the premium on the pay period's insurable earnings.
+*
+* Given:
+*
+*   IE: Insurable earnings for the pay period
+*
+*   DQPIP: Employee’s year-to-date (before the pay period) QPIP premium with the employer
+*
+*   year: The constants for the year of the calculation
+*/
+#[allow(non_snake_case)]
+pub fn qpip(IE: f64, DQPIP: f64, year: &YearConstants) -> f64 {
+    let q1: f64 = year.qpip_max_contributions - DQPIP;
+    let q2: f64 = year.qpip_rate * IE;
+    let q: f64 = if q1 < q2 { q1 } else { q2 };
+    utils::round(utils::floor_zero(q))
+}
+
+/** Employment insurance premiums for the pay period, at the reduced Quebec rate
+*
+*   Quebec employees pay EI at a lower rate than the rest of Canada, as QPIP replaces part of EI's benefits.
+*
+* Given:
+*
+*   D1: Employee’s year-to-date (before the pay period) employment insurance premium with the employer
+*
+*   IE: Insurable earnings for the pay period
+*
+*   year: The constants for the year of the calculation
+*/
+#[allow(non_snake_case)]
+pub fn EI(D1: f64, IE: f64, year: &YearConstants) -> f64 {
+    other_deductions::EI(D1, IE, Province::Quebec, year)
+}
+
+/** Quebec tax rates, income thresholds and constants
+*
+*   Returns `PayrollError::UnsupportedProvince` for a year without Quebec tables.
+*
+* Given:
+*
+*   year: The constants for the year of the calculation
+*/
+pub fn brackets(year: &YearConstants) -> Result<&'static [Bracket], PayrollError> {
+    match year.year {
+        2025 => Ok(&v2025::QUEBEC_BRACKETS),
+        _ => Err(PayrollError::UnsupportedProvince(Province::Quebec)),
+    }
+}
+
+/** Quebec non-refundable tax credits used in the calculation of the Quebec income tax
+*
+*   Build with `QuebecCredits::default()` and the methods for each credit; a credit not set is zero. There is no credit for QPP, QPIP or EI, which Quebec includes in the basic personal amount.
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[allow(non_snake_case)]
+pub struct QuebecCredits {
+    /// Quebec non-refundable personal tax credit
+    pub K1: f64,
+    /// Other Quebec non-refundable tax credits authorized by Revenu Québec
+    pub K3: f64,
+}
+
+impl QuebecCredits {
+    /// The personal credit at the year's lowest Quebec rate on the personal amounts claimed on Form TP-1015.3-V (E), such as `v2025::QUEBEC_BASIC_AMT`. Returns `PayrollError::UnsupportedProvince` for a year without Quebec tables.
+    #[allow(non_snake_case)]
+    pub fn claim(self, E: f64, year: &YearConstants) -> Result<Self, PayrollError> {
+        Ok(QuebecCredits { K1: utils::round(brackets(year)?[0].rate * E), ..self })
+    }
+
+    #[allow(non_snake_case)]
+    pub fn other_credits(self, K3: f64) -> Self {
+        QuebecCredits { K3, ..self }
+    }
+}
+
+/** Annual Quebec income tax
+*
+*   Calculated from Revenu Québec's rates and credits, separately from the provincial and territorial tax (T2) of the other provinces.
+*
+* Given:
+*
+*   A: Annual taxable income
+*
+*   credits: Quebec non-refundable tax credits
+*
+*   year: The constants for the year of the calculation
+*/
+#[allow(non_snake_case)]
+pub fn provincial_tax(A: f64, credits: QuebecCredits, year: &YearConstants) -> Result<f64, PayrollError> {
+    let Bracket { rate, constant, .. } = *year::bracket(brackets(year)?, A);
+    Ok(utils::round(utils::saturating_sub_credits(rate * A, &[constant, credits.K1, credits.K3])))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_provincial_tax_in_each_bracket() {
+        let credits = QuebecCredits::default().claim(v2025::QUEBEC_BASIC_AMT, &v2025::CONSTANTS).unwrap();
+        assert_eq!(credits.K1, 2599.94);
+
+        assert_eq!(provincial_tax(40000.0, credits, &v2025::CONSTANTS), Ok(3000.06));
+        assert_eq!(provincial_tax(80000.0, credits, &v2025::CONSTANTS), Ok(9937.06));
+        assert_eq!(provincial_tax(120000.0, credits, &v2025::CONSTANTS), Ok(18212.06));
+        assert_eq!(provincial_tax(150000.0, credits, &v2025::CONSTANTS), Ok(25770.06));
+
+        // Below the basic personal amount there is no tax
+        assert_eq!(provincial_tax(15000.0, credits, &v2025::CONSTANTS), Ok(0.0));
+        assert_eq!(provincial_tax(40000.0, credits.other_credits(500.0), &v2025::CONSTANTS), Ok(2500.06));
+    }
+
+    #[test]
+    fn test_quebec_constants_match_the_rates() {
+        for b in brackets(&v2025::CONSTANTS).unwrap() {
+            assert_eq!(year::constant(&v2025::QUEBEC_BRACKETS, b.threshold + 1.0), b.constant);
+        }
+        assert_eq!(
+            provincial_tax(40000.0, QuebecCredits::default(), &crate::year::v2024::CONSTANTS),
+            Err(PayrollError::UnsupportedProvince(Province::Quebec))
+        );
+        assert_eq!(
+            QuebecCredits::default().claim(v2025::QUEBEC_BASIC_AMT, &crate::year::v2024::CONSTANTS),
+            Err(PayrollError::UnsupportedProvince(Province::Quebec))
+        );
+    }
+
+    #[test]
+    fn test_qpip_period_premium_and_maximum() {
+        assert_eq!(qpip(5000.0, 0.0, &v2025::CONSTANTS), 24.7);
+        assert_eq!(qpip(5000.0, v2025::QPIP_MAX_CONTRIBUTIONS - 10.0, &v2025::CONSTANTS), 10.0);
+        assert_eq!(qpip(5000.0, v2025::QPIP_MAX_CONTRIBUTIONS, &v2025::CONSTANTS), 0.0);
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_quebec_EI_and_QPIP_differ_from_rest_of_canada_EI() {
+        let quebec = EI(0.0, 5000.0, &v2025::CONSTANTS) + qpip(5000.0, 0.0, &v2025::CONSTANTS);
+        let rest_of_canada = other_deductions::EI(0.0, 5000.0, Province::Ontario, &v2025::CONSTANTS);
+        assert_eq!(EI(0.0, 5000.0, &v2025::CONSTANTS), 65.5);
+        assert_eq!(rest_of_canada, 82.0);
+        assert_eq!(utils::round(quebec), 90.2);
+        assert!(quebec != rest_of_canada);
+
+        assert_eq!(EI(v2025::EI_QUEBEC_MAX_CONTRIBUTIONS, 5000.0, &v2025::CONSTANTS), 0.0);
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_QPP_period_contribution() {
+        assert_eq!(QPP(12, 0.0, 5000.0, 12, &v2025::CONSTANTS), utils::round(0.064 * (5000.0 - 3500.0 / 12.0)));
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_QPP_stops_at_maximum() {
+        assert_eq!(QPP(12, v2025::QPP_MAX_CONTRIBUTIONS - 10.0, 5000.0, 12, &v2025::CONSTANTS), 10.0);
+        assert_eq!(QPP(12, v2025::QPP_MAX_CONTRIBUTIONS, 5000.0, 12, &v2025::CONSTANTS), 0.0);
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_QPP_uses_the_year_maximum() {
+        // 6.40% of (68,500 − 3,500) in 2024
+        let year = &crate::year::v2024::CONSTANTS;
+        assert_eq!(QPP(12, 4160.0 - 10.0, 5000.0, 12, year), 10.0);
+        assert_eq!(QPP(12, 4160.0, 5000.0, 12, year), 0.0);
+    }
+}
@@ -0,0 +1,256 @@
+//! # T4 Slip
+//! Year-end totals of the per-period deductions, reported in the boxes of the T4 Statement of Remuneration Paid.
+
+use crate::payroll::PayrollResult;
+use crate::utils;
+use crate::year::YearConstants;
+
+/// Earnings reported for a pay period, alongside its `PayrollResult`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Earnings {
+    /// Gross employment income, including taxable benefits
+    pub employment_income: f64,
+    /// PI: Pensionable earnings for the pay period
+    pub pensionable: f64,
+    /// IE: Insurable earnings for the pay period
+    pub insurable: f64,
+}
+
+/// Amounts reported on a T4 slip.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct T4Slip {
+    /// Box 14: Employment income
+    pub employment_income: f64,
+    /// Box 16: Employee's CPP contributions
+    pub cpp: f64,
+    /// Box 16A: Employee's second CPP contributions
+    pub cpp2: f64,
+    /// Box 18: Employee's EI premiums
+    pub ei: f64,
+    /// Box 22: Income tax deducted
+    pub income_tax: f64,
+    /// Box 24: EI insurable earnings, up to the maximum insurable earnings
+    pub insurable_earnings: f64,
+    /// Box 26: CPP pensionable earnings, up to the year's additional maximum pensionable earnings
+    pub pensionable_earnings: f64,
+}
+
+/// A benefit paid to the employee, with whether it is included in each kind of earnings.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Benefit {
+    pub amount: f64,
+    /// Included in employment income (box 14)
+    pub taxable: bool,
+    /// Included in EI insurable earnings (box 24); non-cash benefits usually are not
+    pub insurable: bool,
+    /// Included in CPP pensionable earnings (box 26)
+    pub pensionable: bool,
+}
+
+/// Employment income and the insurable and pensionable earnings reported for it, which differ by the benefits each includes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EarningsBreakdown {
+    /// Box 14: Employment income
+    pub employment_income: f64,
+    /// Box 24: EI insurable earnings, up to the maximum insurable earnings
+    pub insurable_earnings: f64,
+    /// Box 26: CPP pensionable earnings, up to the year's additional maximum pensionable earnings
+    pub pensionable_earnings: f64,
+}
+
+/** Split a year's earnings into the employment income, insurable earnings and pensionable earnings reported on the T4 slip.
+*
+* Given:
+*
+*   gross: Gross salary or wages for the year, not including benefits
+*
+*   taxable_benefits: The benefits for the year, each tagged with the earnings it is included in
+*
+*   year: The constants for the year, whose maximum insurable earnings and YAMPE cap the insurable and pensionable earnings
+*/
+pub fn earnings_breakdown(gross: f64, taxable_benefits: &[Benefit], year: &YearConstants) -> EarningsBreakdown {
+    let with_benefits = |included: fn(&Benefit) -> bool| {
+        gross + taxable_benefits.iter().filter(|b| included(b)).map(|b| b.amount).sum::<f64>()
+    };
+
+    EarningsBreakdown {
+        employment_income: utils::round(with_benefits(|b| b.taxable)),
+        insurable_earnings: utils::round(utils::clamp(with_benefits(|b| b.insurable), 0.0, year.mie)),
+        pensionable_earnings: utils::round(utils::clamp(with_benefits(|b| b.pensionable), 0.0, year.yampe)),
+    }
+}
+
+/** Sum a year's pay periods into T4 slip boxes.
+*
+*   `periods` and `earnings` hold one entry per pay period in the year, for one employee and one employer; `year` is the constants for the year of the slip, whose maximum insurable earnings and YAMPE cap boxes 24 and 26.
+*/
+pub fn t4_slip_amounts(periods: &[PayrollResult], earnings: &[Earnings], year: &YearConstants) -> T4Slip {
+    let sum = |amount: fn(&PayrollResult) -> f64| utils::round(periods.iter().map(amount).sum());
+    let sum_earnings = |amount: fn(&Earnings) -> f64| utils::round(earnings.iter().map(amount).sum());
+
+    T4Slip {
+        employment_income: sum_earnings(|e| e.employment_income),
+        cpp: sum(|p| p.cpp),
+        cpp2: sum(|p| p.cpp2),
+        ei: sum(|p| p.ei),
+        income_tax: sum(|p| p.tax),
+        insurable_earnings: utils::clamp(sum_earnings(|e| e.insurable), 0.0, year.mie),
+        pensionable_earnings: utils::clamp(sum_earnings(|e| e.pensionable), 0.0, year.yampe),
+    }
+}
+
+/// A T4 slip box that does not match the sum of the pay periods.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct T4Discrepancy {
+    /// The box number, such as "14" or "16A"
+    pub box_number: &'static str,
+    /// The amount on the slip
+    pub slip: f64,
+    /// The sum of the pay periods
+    pub periods: f64,
+}
+
+/** Check the slip boxes against the sum of the pay periods, to within a cent.
+*
+*   Checks boxes 14, 16, 16A, 18 and 22; insurable and pensionable earnings (boxes 24 and 26) are not in the `PayrollResult`s and are not checked. Returns every box that does not match.
+*
+* Given:
+*
+*   slip: The T4 slip amounts to check
+*
+*   periods: The pay periods of the year the slip is for
+*/
+pub fn validate_t4(slip: &T4Slip, periods: &[PayrollResult]) -> Result<(), Vec<T4Discrepancy>> {
+    let sum = |amount: fn(&PayrollResult) -> f64| utils::round(periods.iter().map(amount).sum());
+    let boxes = [
+        ("14", slip.employment_income, sum(|p| p.gross)),
+        ("16", slip.cpp, sum(|p| p.cpp)),
+        ("16A", slip.cpp2, sum(|p| p.cpp2)),
+        ("18", slip.ei, sum(|p| p.ei)),
+        ("22", slip.income_tax, sum(|p| p.tax)),
+    ];
+
+    let discrepancies: Vec<T4Discrepancy> = boxes
+        .iter()
+        .filter(|(_, slip, periods)| (slip - periods).abs() > 0.01 + 1e-9)
+        .map(|&(box_number, slip, periods)| T4Discrepancy { box_number, slip, periods })
+        .collect();
+    if discrepancies.is_empty() {
+        Ok(())
+    } else {
+        Err(discrepancies)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::payroll::{self, PayrollContext, PayrollInput};
+    use crate::test_fixtures;
+    use crate::year::{v2024, v2025};
+
+    /// Run every pay period of the year, carrying the year-to-date amounts forward.
+    fn run_year(ctx: &PayrollContext, input: PayrollInput) -> (Vec<PayrollResult>, Vec<Earnings>) {
+        let mut input = input;
+        let mut results = Vec::new();
+        let mut earnings = Vec::new();
+        for _ in 0..ctx.periods_per_year() {
+            let result = payroll::calculate(ctx, &input).unwrap();
+            input.ytd_cpp += result.cpp;
+            input.ytd_cpp2 += result.cpp2;
+            input.ytd_ei += result.ei;
+            input.ytd_pensionable += input.pensionable_earnings();
+            earnings.push(Earnings {
+                employment_income: input.gross(),
+                pensionable: input.pensionable_earnings(),
+                insurable: input.insurable_earnings(),
+            });
+            results.push(result);
+        }
+        (results, earnings)
+    }
+
+    #[test]
+    fn test_t4_slip_amounts_biweekly_year() {
+        let (ctx, input) = test_fixtures::ontario_biweekly_employee();
+        let (results, earnings) = run_year(&ctx, input);
+        assert_eq!(results.len(), 26);
+
+        let slip = t4_slip_amounts(&results, &earnings, ctx.year);
+        assert_eq!(slip.employment_income, 59999.94);
+        assert_eq!(slip.pensionable_earnings, 59999.94);
+        assert_eq!(slip.insurable_earnings, 59999.94);
+        assert_eq!(slip.cpp, utils::round(26.0 * 129.3));
+        assert_eq!(slip.cpp2, 0.0);
+        assert_eq!(slip.ei, utils::round(26.0 * 37.85));
+        assert_eq!(slip.income_tax, utils::round(26.0 * 340.64));
+    }
+
+    #[test]
+    fn test_t4_slip_caps_insurable_and_pensionable_earnings() {
+        let (ctx, input) = test_fixtures::ontario_biweekly_employee();
+        let (results, earnings) = run_year(&ctx, PayrollInput { cash_income: 4000.0, ..input });
+
+        let slip = t4_slip_amounts(&results, &earnings, ctx.year);
+        assert_eq!(slip.employment_income, 104000.0);
+        assert_eq!(slip.insurable_earnings, v2025::MIE);
+        assert_eq!(slip.pensionable_earnings, v2025::YAMPE);
+        assert_eq!(slip.cpp, 4034.1);
+        assert_eq!(slip.ei, v2025::EI_MAX_CONTRIBUTIONS);
+    }
+
+    #[test]
+    fn test_t4_slip_caps_at_the_maximums_of_the_year() {
+        let earnings = vec![Earnings { employment_income: 4000.0, pensionable: 4000.0, insurable: 4000.0 }; 26];
+        let slip = t4_slip_amounts(&[], &earnings, &v2024::CONSTANTS);
+        assert_eq!(slip.employment_income, 104000.0);
+        assert_eq!(slip.insurable_earnings, 63200.0);
+        assert_eq!(slip.pensionable_earnings, 73200.0);
+    }
+
+    #[test]
+    fn test_earnings_breakdown_with_a_non_insurable_benefit() {
+        // A non-cash benefit, such as a company car, is taxable and pensionable but not insurable
+        let car = Benefit { amount: 3000.0, taxable: true, insurable: false, pensionable: true };
+        let breakdown = earnings_breakdown(50000.0, &[car], &v2025::CONSTANTS);
+        assert_eq!(breakdown.employment_income, 53000.0);
+        assert_eq!(breakdown.insurable_earnings, 50000.0);
+        assert_eq!(breakdown.pensionable_earnings, 53000.0);
+
+        let bonus = Benefit { amount: 5000.0, taxable: true, insurable: true, pensionable: true };
+        let breakdown = earnings_breakdown(62000.0, &[car, bonus], &v2025::CONSTANTS);
+        assert_eq!(breakdown.employment_income, 70000.0);
+        assert_eq!(breakdown.insurable_earnings, v2025::MIE);
+        assert_eq!(breakdown.pensionable_earnings, 70000.0);
+
+        let breakdown = earnings_breakdown(90000.0, &[], &v2025::CONSTANTS);
+        assert_eq!(breakdown.pensionable_earnings, v2025::YAMPE);
+        assert_eq!(breakdown.employment_income, 90000.0);
+    }
+
+    #[test]
+    fn test_earnings_breakdown_caps_at_the_maximums_of_the_year() {
+        let breakdown = earnings_breakdown(80000.0, &[], &v2024::CONSTANTS);
+        assert_eq!(breakdown.employment_income, 80000.0);
+        assert_eq!(breakdown.insurable_earnings, 63200.0);
+        assert_eq!(breakdown.pensionable_earnings, 73200.0);
+    }
+
+    #[test]
+    fn test_validate_t4_reports_a_corrupted_box() {
+        let (ctx, input) = test_fixtures::ontario_biweekly_employee();
+        let (results, earnings) = run_year(&ctx, input);
+        let slip = t4_slip_amounts(&results, &earnings, ctx.year);
+        assert_eq!(validate_t4(&slip, &results), Ok(()));
+
+        // A cent of rounding is tolerated
+        let rounded = T4Slip { income_tax: slip.income_tax + 0.01, ..slip };
+        assert_eq!(validate_t4(&rounded, &results), Ok(()));
+
+        let corrupted = T4Slip { ei: slip.ei + 37.85, ..slip };
+        assert_eq!(
+            validate_t4(&corrupted, &results),
+            Err(vec![T4Discrepancy { box_number: "18", slip: slip.ei + 37.85, periods: slip.ei }])
+        );
+    }
+}
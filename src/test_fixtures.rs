@@ -0,0 +1,112 @@
+//! # Test Fixtures
+//! Realistic employee scenarios for tests, each returning the `PayrollContext` and a fully populated `PayrollInput` for the first pay period of 2025.
+//!
+//! Available to this crate's unit tests, and to integration tests through the `testing` feature.
+
+use crate::basic_personal_income::ZoneType;
+use crate::income_tax::RoundingStage;
+use crate::pay_period::PayPeriod;
+use crate::payroll::{Dependents, FactorOverrides, PayeeType, PayrollContext, PayrollInput, Residency};
+use crate::province::Province;
+use crate::year::v2025;
+
+/// Ontario employee earning $60,000 a year, paid biweekly, claiming the basic amounts.
+pub fn ontario_biweekly_employee() -> (PayrollContext, PayrollInput) {
+    let ctx = PayrollContext {
+        province: Province::Ontario,
+        year: &v2025::CONSTANTS,
+        pay_period: PayPeriod::BiWeekly,
+        residency: Residency::Canada,
+        payee_type: PayeeType::Employee,
+        pay_periods: None,
+    };
+    let input = PayrollInput {
+        cash_income: 2307.69,
+        taxable_benefits: 0.0,
+        non_insurable_benefits: 0.0,
+        retirement_contributions: 0.0,
+        voluntary_deductions: Vec::new(),
+        support_payments: 0.0,
+        union_dues: 0.0,
+        labour_sponsored_shares: 0.0,
+        prescribed_zone: ZoneType::NotPrescribed,
+        prescribed_zone_days: 0,
+        annual_deductions: 0.0,
+        additional_tax: 0.0,
+        federal_claim: v2025::MINIMUM_BASIC_AMT,
+        provincial_claim: Some(v2025::ONTARIO_BASIC_AMT),
+        other_federal_credits: 0.0,
+        other_provincial_credits: 0.0,
+        dependents: Dependents::default(),
+        td1_change: None,
+        contribution_months: 12,
+        pay_date: None,
+        cpt30_election: None,
+        ytd_cpp: 0.0,
+        ytd_cpp2: 0.0,
+        ytd_ei: 0.0,
+        ytd_qpip: 0.0,
+        ytd_pensionable: 0.0,
+        overrides: FactorOverrides::default(),
+        rounding: RoundingStage::AnnualOnly,
+    };
+    (ctx, input)
+}
+
+/// Ontario employee paid $1,150 weekly, with union dues, RRSP contributions and two dependants under 19.
+pub fn ontario_weekly_employee_with_dependents() -> (PayrollContext, PayrollInput) {
+    let (ctx, input) = ontario_biweekly_employee();
+    (
+        PayrollContext { pay_period: PayPeriod::Weekly, ..ctx },
+        PayrollInput {
+            cash_income: 1150.0,
+            retirement_contributions: 50.0,
+            union_dues: 15.0,
+            dependents: Dependents { under_19: 2, disabled: 0 },
+            ..input
+        },
+    )
+}
+
+/// Alberta retiree receiving $3,000 a month of pension income; no CPP or EI is deducted.
+pub fn alberta_monthly_pensioner() -> (PayrollContext, PayrollInput) {
+    let (ctx, input) = ontario_biweekly_employee();
+    (
+        PayrollContext {
+            province: Province::Alberta,
+            pay_period: PayPeriod::Monthly,
+            payee_type: PayeeType::Pensioner,
+            ..ctx
+        },
+        PayrollInput {
+            cash_income: 3000.0,
+            provincial_claim: Some(v2025::ALBERTA_BASIC_AMT),
+            contribution_months: 0,
+            ..input
+        },
+    )
+}
+
+/// Alberta employee earning $95,000 a year, paid semi-monthly, contributing $200 a period to an RRSP.
+pub fn alberta_semi_monthly_employee() -> (PayrollContext, PayrollInput) {
+    let (ctx, input) = ontario_biweekly_employee();
+    (
+        PayrollContext { province: Province::Alberta, pay_period: PayPeriod::SemiMonthly, ..ctx },
+        PayrollInput {
+            cash_income: 3958.33,
+            retirement_contributions: 200.0,
+            provincial_claim: Some(v2025::ALBERTA_BASIC_AMT),
+            ..input
+        },
+    )
+}
+
+/// Every fixture, for tests that should hold across all scenarios.
+pub fn all() -> Vec<(PayrollContext, PayrollInput)> {
+    vec![
+        ontario_biweekly_employee(),
+        ontario_weekly_employee_with_dependents(),
+        alberta_monthly_pensioner(),
+        alberta_semi_monthly_employee(),
+    ]
+}
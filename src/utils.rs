@@ -1,4 +1,86 @@
+use crate::error::PayrollError;
+
+/// Round to the nearest cent. Non-finite values (NaN or infinity) round to zero; the entry points in `payroll` reject non-finite inputs with `PayrollError::NonFiniteInput` before they get here. See `try_round` to reject them instead.
 pub fn round(x: f64) -> f64 {
+    if !x.is_finite() {
+        return 0.0;
+    }
     (x * 100.0).round() / 100.0
 }
 
+/// Round to the nearest cent, or `PayrollError::NonFiniteInput` naming `input` if the value is NaN or infinite.
+pub fn try_round(x: f64, input: &'static str) -> Result<f64, PayrollError> {
+    if !x.is_finite() {
+        return Err(PayrollError::NonFiniteInput(input));
+    }
+    Ok(round(x))
+}
+
+/// Limit a value to the range `min..=max`. `min` must not exceed `max`.
+pub fn clamp(value: f64, min: f64, max: f64) -> f64 {
+    if value < min {
+        return min;
+    }
+    if value > max {
+        return max;
+    }
+    value
+}
+
+/// Floor a value at zero: amounts such as tax and contributions are never negative.
+pub fn floor_zero(value: f64) -> f64 {
+    if value.is_sign_negative() {
+        return 0.0;
+    }
+    value
+}
+
+/// Subtract each credit from a base amount, such as tax less non-refundable credits, flooring the result at zero.
+pub fn saturating_sub_credits(base: f64, credits: &[f64]) -> f64 {
+    floor_zero(credits.iter().fold(base, |remaining, credit| remaining - credit))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_non_finite_is_zero() {
+        assert_eq!(round(f64::NAN), 0.0);
+        assert_eq!(round(f64::INFINITY), 0.0);
+        assert_eq!(round(f64::NEG_INFINITY), 0.0);
+        assert_eq!(round(12.345), 12.35);
+    }
+
+    #[test]
+    fn test_try_round_rejects_non_finite() {
+        assert_eq!(try_round(f64::NAN, "I"), Err(PayrollError::NonFiniteInput("I")));
+        assert_eq!(try_round(f64::INFINITY, "I"), Err(PayrollError::NonFiniteInput("I")));
+        assert_eq!(try_round(12.345, "I"), Ok(12.35));
+    }
+
+    #[test]
+    fn test_clamp() {
+        assert_eq!(clamp(-5.0, 0.0, 10.0), 0.0);
+        assert_eq!(clamp(5.0, 0.0, 10.0), 5.0);
+        assert_eq!(clamp(15.0, 0.0, 10.0), 10.0);
+        assert_eq!(clamp(10.0, 0.0, 10.0), 10.0);
+    }
+
+    #[test]
+    fn test_floor_zero() {
+        assert_eq!(floor_zero(-0.01), 0.0);
+        assert_eq!(floor_zero(0.0), 0.0);
+        assert_eq!(floor_zero(3.5), 3.5);
+    }
+
+    #[test]
+    fn test_saturating_sub_credits() {
+        assert_eq!(saturating_sub_credits(1000.0, &[200.0, 300.0]), 500.0);
+        assert_eq!(saturating_sub_credits(1000.0, &[600.0, 500.0]), 0.0);
+        assert_eq!(saturating_sub_credits(1000.0, &[1000.0]), 0.0);
+        assert_eq!(saturating_sub_credits(250.0, &[]), 250.0);
+        assert_eq!(saturating_sub_credits(-10.0, &[]), 0.0);
+    }
+
+}
@@ -0,0 +1,310 @@
+//! # Year Constants from a Configuration File
+//! Loads `YearConstants` from a file, so the annual update can be made without recompiling.
+//!
+//! The file is a small subset of TOML: `key = value` lines named after the `YearConstants` fields, with the
+//! lowest provincial rates in a `[lowest_provincial_rates]` section keyed by province. Brackets are
+//! `[threshold, rate, constant]`, surtaxes `[threshold, rate]` and health premium bands `[threshold, rate, maximum]`,
+//! as arrays of arrays. `#` starts a comment.
+//!
+//! The provincial and territorial brackets and basic amounts are not `YearConstants` fields, and are not in the file;
+//! they are compiled in for each supported year (see `year::supported_years`). A file can update the constants of a
+//! supported year, and a file for any other year is rejected with `ConfigError::UnsupportedYear`.
+//!
+//! ```toml
+//! year = 2025
+//! cpp_base_rate = 0.0495
+//! federal_brackets = [[0.0, 0.15, 0.0], [57375.0, 0.205, 3156.0]]
+//! pei_surtax = []
+//!
+//! [lowest_provincial_rates]
+//! Ontario = 0.0505
+//! ```
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::sync::Mutex;
+
+use crate::error::ConfigError;
+use crate::province::Province;
+use crate::year::{self, Bracket, YearConstants};
+
+const PROVINCES: [Province; 13] = [
+    Province::Alberta,
+    Province::BritishColumbia,
+    Province::Manitoba,
+    Province::NewBrunswick,
+    Province::NewfoundlandAndLabrador,
+    Province::NorthwestTerritories,
+    Province::NovaScotia,
+    Province::Nunavut,
+    Province::Ontario,
+    Province::PrinceEdwardIsland,
+    Province::Quebec,
+    Province::Saskatchewan,
+    Province::Yukon,
+];
+
+const RATES_SECTION: &str = "lowest_provincial_rates";
+
+// Every top-level key, in the order they are written
+const KEYS: [&str; 29] = [
+    "year",
+    "federal_brackets",
+    "cpp_base_rate",
+    "cpp_first_additional_rate",
+    "cpp_second_additional_rate",
+    "ympe",
+    "yampe",
+    "cpp_basic_exemption",
+    "cpp_max_contributions",
+    "cpp_total_max_contributions",
+    "qpp_rate",
+    "qpp_base_rate",
+    "qpp_max_contributions",
+    "canada_employment_amount",
+    "federal_basic_personal_amount",
+    "federal_reduced_basic_personal_amount",
+    "ei_rate",
+    "ei_max_contributions",
+    "mie",
+    "qpip_rate",
+    "qpip_max_contributions",
+    "ei_quebec_rate",
+    "ei_quebec_max_contributions",
+    "on_surtax",
+    "pei_surtax",
+    "on_reduction_base",
+    "on_per_dependent",
+    "on_health_premium",
+    RATES_SECTION,
+];
+
+// Tables loaded so far, each leaked once to give it the `'static` lifetime of the compiled-in constants
+type Tables<T> = Mutex<Vec<&'static [T]>>;
+static BRACKETS: Tables<Bracket> = Mutex::new(Vec::new());
+static RATES: Tables<(Province, f64)> = Mutex::new(Vec::new());
+static SURTAXES: Tables<(f64, f64)> = Mutex::new(Vec::new());
+static PREMIUMS: Tables<(f64, f64, f64)> = Mutex::new(Vec::new());
+
+// The table equal to `items` loaded earlier, or `items` leaked and added to the tables loaded
+fn intern<T: PartialEq>(loaded: &Tables<T>, items: Vec<T>) -> &'static [T] {
+    let mut loaded = loaded.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    if let Some(table) = loaded.iter().copied().find(|table| *table == &items[..]) {
+        return table;
+    }
+    let table: &'static [T] = Box::leak(items.into_boxed_slice());
+    loaded.push(table);
+    table
+}
+
+/** Load the constants for a year from a configuration file
+*
+*   The brackets, rates and surtaxes have the `'static` lifetime of the compiled-in constants. Each distinct table is allocated once and shared by every later load of it, so loading the same file again does not allocate more memory.
+*
+*   Returns `ConfigError::UnsupportedYear` for a year without compiled-in constants; see the module documentation.
+*
+* Given:
+*
+*   path: The configuration file; see the module documentation for the format
+*/
+pub fn load_year_constants(path: &Path) -> Result<YearConstants, ConfigError> {
+    let text = fs::read_to_string(path).map_err(|e| ConfigError::Io(e.kind()))?;
+    parse_year_constants(&text)
+}
+
+/// Write the constants for a year to a configuration file that `load_year_constants` reads back.
+pub fn save_year_constants(constants: &YearConstants, path: &Path) -> Result<(), ConfigError> {
+    fs::write(path, to_config(constants)).map_err(|e| ConfigError::Io(e.kind()))
+}
+
+/// The constants for a year in the configuration file format.
+pub fn to_config(constants: &YearConstants) -> String {
+    let brackets = |brackets: &[Bracket]| {
+        let rows: Vec<String> = brackets
+            .iter()
+            .map(|b| format!("[{:?}, {:?}, {:?}]", b.threshold, b.rate, b.constant))
+            .collect();
+        format!("[{}]", rows.join(", "))
+    };
+    let surtax = |surtax: &[(f64, f64)]| {
+        let rows: Vec<String> = surtax.iter().map(|(threshold, rate)| format!("[{:?}, {:?}]", threshold, rate)).collect();
+        format!("[{}]", rows.join(", "))
+    };
+
+    let mut text = format!("year = {}\n", constants.year);
+    text += &format!("federal_brackets = {}\n", brackets(constants.federal_brackets));
+    for (key, value) in [
+        ("cpp_base_rate", constants.cpp_base_rate),
+        ("cpp_first_additional_rate", constants.cpp_first_additional_rate),
+        ("cpp_second_additional_rate", constants.cpp_second_additional_rate),
+        ("ympe", constants.ympe),
+        ("yampe", constants.yampe),
+        ("cpp_basic_exemption", constants.cpp_basic_exemption),
+        ("cpp_max_contributions", constants.cpp_max_contributions),
+        ("cpp_total_max_contributions", constants.cpp_total_max_contributions),
+        ("qpp_rate", constants.qpp_rate),
+        ("qpp_base_rate", constants.qpp_base_rate),
+        ("qpp_max_contributions", constants.qpp_max_contributions),
+        ("canada_employment_amount", constants.canada_employment_amount),
+        ("federal_basic_personal_amount", constants.federal_basic_personal_amount),
+        ("federal_reduced_basic_personal_amount", constants.federal_reduced_basic_personal_amount),
+        ("ei_rate", constants.ei_rate),
+        ("ei_max_contributions", constants.ei_max_contributions),
+        ("mie", constants.mie),
+        ("qpip_rate", constants.qpip_rate),
+        ("qpip_max_contributions", constants.qpip_max_contributions),
+        ("ei_quebec_rate", constants.ei_quebec_rate),
+        ("ei_quebec_max_contributions", constants.ei_quebec_max_contributions),
+    ] {
+        text += &format!("{} = {:?}\n", key, value);
+    }
+    text += &format!("on_surtax = {}\n", surtax(constants.on_surtax));
+    text += &format!("pei_surtax = {}\n", surtax(constants.pei_surtax));
+    text += &format!("on_reduction_base = {:?}\n", constants.on_reduction_base);
+    text += &format!("on_per_dependent = {:?}\n", constants.on_per_dependent);
+    let premium: Vec<String> = constants
+        .on_health_premium
+        .iter()
+        .map(|(threshold, rate, maximum)| format!("[{:?}, {:?}, {:?}]", threshold, rate, maximum))
+        .collect();
+    text += &format!("on_health_premium = [{}]\n", premium.join(", "));
+
+    text += &format!("\n[{}]\n", RATES_SECTION);
+    for (province, rate) in constants.lowest_provincial_rates {
+        text += &format!("{:?} = {:?}\n", province, rate);
+    }
+    text
+}
+
+/// Parse the constants for a year from the configuration file format; see `load_year_constants`.
+pub fn parse_year_constants(text: &str) -> Result<YearConstants, ConfigError> {
+    // Each top-level key's value and line number
+    let mut values: HashMap<&str, (usize, &str)> = HashMap::new();
+    let mut rates: Vec<(Province, f64)> = Vec::new();
+    let mut in_rates = false;
+
+    for (index, line) in text.lines().enumerate() {
+        let line_number = index + 1;
+        let line = line.split('#').next().unwrap_or("").trim();
+        if line.is_empty() {
+            continue;
+        }
+        if let Some(section) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            if section.trim() != RATES_SECTION {
+                return Err(ConfigError::UnknownKey { line: line_number, key: section.trim().to_string() });
+            }
+            in_rates = true;
+            continue;
+        }
+
+        let (key, value) = line.split_once('=').ok_or(ConfigError::Syntax { line: line_number })?;
+        let (key, value) = (key.trim(), value.trim());
+        let unknown = || ConfigError::UnknownKey { line: line_number, key: key.to_string() };
+        if in_rates {
+            let province = *PROVINCES.iter().find(|p| format!("{:?}", p) == key).ok_or_else(unknown)?;
+            rates.push((province, number(line_number, key, value)?));
+        } else {
+            let key = *KEYS.iter().find(|k| **k == key && **k != RATES_SECTION).ok_or_else(unknown)?;
+            values.insert(key, (line_number, value));
+        }
+    }
+
+    let get = |key: &'static str| values.get(key).copied().ok_or(ConfigError::MissingKey(key));
+    let number_of = |key: &'static str| get(key).and_then(|(line, value)| number(line, key, value));
+    let rows_of = |key: &'static str, width: usize| get(key).and_then(|(line, value)| rows(line, key, value, width));
+
+    let (year_line, year) = get("year")?;
+    let year = year.parse::<u16>().map_err(|_| ConfigError::InvalidValue { line: year_line, key: "year".to_string() })?;
+    if year::constants(year).is_none() {
+        return Err(ConfigError::UnsupportedYear(year));
+    }
+    let federal_brackets: Vec<Bracket> = rows_of("federal_brackets", 3)?
+        .iter()
+        .map(|row| Bracket { threshold: row[0], rate: row[1], constant: row[2] })
+        .collect();
+    // A bracket table starts at an income of zero, so every income falls in a bracket
+    if federal_brackets.first().map(|b| b.threshold) != Some(0.0) {
+        return Err(ConfigError::InvalidValue { line: get("federal_brackets")?.0, key: "federal_brackets".to_string() });
+    }
+    let surtax = |key: &'static str| -> Result<&'static [(f64, f64)], ConfigError> {
+        let surtax: Vec<(f64, f64)> = rows_of(key, 2)?.iter().map(|row| (row[0], row[1])).collect();
+        Ok(intern(&SURTAXES, surtax))
+    };
+    // The Ontario surtax has two tiers
+    let on_surtax = surtax("on_surtax")?;
+    if on_surtax.len() != 2 {
+        return Err(ConfigError::InvalidValue { line: get("on_surtax")?.0, key: "on_surtax".to_string() });
+    }
+    let on_health_premium: Vec<(f64, f64, f64)> =
+        rows_of("on_health_premium", 3)?.iter().map(|row| (row[0], row[1], row[2])).collect();
+    if rates.is_empty() {
+        return Err(ConfigError::MissingKey(RATES_SECTION));
+    }
+
+    Ok(YearConstants {
+        year,
+        federal_brackets: intern(&BRACKETS, federal_brackets),
+        lowest_provincial_rates: intern(&RATES, rates),
+        cpp_base_rate: number_of("cpp_base_rate")?,
+        cpp_first_additional_rate: number_of("cpp_first_additional_rate")?,
+        cpp_second_additional_rate: number_of("cpp_second_additional_rate")?,
+        ympe: number_of("ympe")?,
+        yampe: number_of("yampe")?,
+        cpp_basic_exemption: number_of("cpp_basic_exemption")?,
+        cpp_max_contributions: number_of("cpp_max_contributions")?,
+        cpp_total_max_contributions: number_of("cpp_total_max_contributions")?,
+        qpp_rate: number_of("qpp_rate")?,
+        qpp_base_rate: number_of("qpp_base_rate")?,
+        qpp_max_contributions: number_of("qpp_max_contributions")?,
+        canada_employment_amount: number_of("canada_employment_amount")?,
+        federal_basic_personal_amount: number_of("federal_basic_personal_amount")?,
+        federal_reduced_basic_personal_amount: number_of("federal_reduced_basic_personal_amount")?,
+        ei_rate: number_of("ei_rate")?,
+        ei_max_contributions: number_of("ei_max_contributions")?,
+        mie: number_of("mie")?,
+        qpip_rate: number_of("qpip_rate")?,
+        qpip_max_contributions: number_of("qpip_max_contributions")?,
+        ei_quebec_rate: number_of("ei_quebec_rate")?,
+        ei_quebec_max_contributions: number_of("ei_quebec_max_contributions")?,
+        on_surtax,
+        pei_surtax: surtax("pei_surtax")?,
+        on_reduction_base: number_of("on_reduction_base")?,
+        on_per_dependent: number_of("on_per_dependent")?,
+        on_health_premium: intern(&PREMIUMS, on_health_premium),
+    })
+}
+
+fn number(line: usize, key: &str, value: &str) -> Result<f64, ConfigError> {
+    value
+        .parse::<f64>()
+        .ok()
+        .filter(|n| n.is_finite())
+        .ok_or_else(|| ConfigError::InvalidValue { line, key: key.to_string() })
+}
+
+// An array of arrays of numbers, each `width` long, such as `[[0.0, 0.15, 0.0], [57375.0, 0.205, 3156.0]]`
+fn rows(line: usize, key: &str, value: &str, width: usize) -> Result<Vec<Vec<f64>>, ConfigError> {
+    let invalid = || ConfigError::InvalidValue { line, key: key.to_string() };
+    let value: String = value.chars().filter(|c| !c.is_whitespace()).collect();
+    let inner = value.strip_prefix('[').and_then(|v| v.strip_suffix(']')).ok_or_else(invalid)?;
+    if inner.is_empty() {
+        return Ok(Vec::new());
+    }
+    let inner = inner.strip_prefix('[').and_then(|v| v.strip_suffix(']')).ok_or_else(invalid)?;
+
+    inner
+        .split("],[")
+        .map(|row| {
+            let row = row
+                .split(',')
+                .map(|n| number(line, key, n))
+                .collect::<Result<Vec<f64>, ConfigError>>()?;
+            if row.len() == width {
+                Ok(row)
+            } else {
+                Err(invalid())
+            }
+        })
+        .collect()
+}
@@ -1 +1,151 @@
+pub mod v2023;
+pub mod v2024;
 pub mod v2025;
+#[cfg(feature = "config")]
+pub mod config;
+
+use crate::province::Province;
+use crate::utils;
+
+/// Rates, thresholds and maximums for one calendar year.
+#[derive(Debug, Clone, PartialEq)]
+pub struct YearConstants {
+    pub year: u16,
+    /// Federal rates (R), income thresholds (A) and constants (K)
+    pub federal_brackets: &'static [Bracket],
+    /// Lowest provincial or territorial tax rate of each province and territory
+    pub lowest_provincial_rates: &'static [(Province, f64)],
+    /// Base CPP contribution rate
+    pub cpp_base_rate: f64,
+    /// First additional CPP contribution rate; the employee rate is the base plus the first additional rate
+    pub cpp_first_additional_rate: f64,
+    /// Second additional CPP contribution rate, on pensionable earnings between the YMPE and the YAMPE; zero before 2024
+    pub cpp_second_additional_rate: f64,
+    /// YMPE: Year's maximum pensionable earnings, the ceiling for the base and first additional contributions
+    pub ympe: f64,
+    /// YAMPE: Year's additional maximum pensionable earnings, the ceiling for the second additional contributions; the YMPE before 2024
+    pub yampe: f64,
+    /// Basic CPP exemption for the year
+    pub cpp_basic_exemption: f64,
+    /// Maximum base CPP contribution
+    pub cpp_max_contributions: f64,
+    /// Maximum CPP contribution (base and first additional)
+    pub cpp_total_max_contributions: f64,
+    /// QPP contribution rate (base and first additional)
+    pub qpp_rate: f64,
+    /// Base QPP contribution rate
+    pub qpp_base_rate: f64,
+    /// Maximum QPP contribution (base and first additional)
+    pub qpp_max_contributions: f64,
+    /// CEA: Canada employment amount
+    pub canada_employment_amount: f64,
+    /// Federal basic personal amount, before it is phased out on higher net incomes
+    pub federal_basic_personal_amount: f64,
+    /// Federal basic personal amount once fully phased out, on net incomes from the highest federal threshold; it is phased out from the one below
+    pub federal_reduced_basic_personal_amount: f64,
+    /// EI premium rate outside Quebec
+    pub ei_rate: f64,
+    /// Maximum EI premium outside Quebec
+    pub ei_max_contributions: f64,
+    /// MIE: Maximum insurable earnings, the ceiling for the EI premiums in and outside Quebec
+    pub mie: f64,
+    /// QPIP premium rate
+    pub qpip_rate: f64,
+    /// Maximum QPIP premium
+    pub qpip_max_contributions: f64,
+    /// EI premium rate in Quebec
+    pub ei_quebec_rate: f64,
+    /// Maximum EI premium in Quebec
+    pub ei_quebec_max_contributions: f64,
+    /// Ontario surtax thresholds and rates on the basic provincial tax (V1)
+    pub on_surtax: &'static [(f64, f64)],
+    /// Prince Edward Island surtax thresholds and rates on the basic provincial tax; none since 2024
+    pub pei_surtax: &'static [(f64, f64)],
+    /// Ontario basic tax reduction amount; factor S allows twice this amount
+    pub on_reduction_base: f64,
+    /// Ontario tax reduction amount for each eligible dependant (factor Y)
+    pub on_per_dependent: f64,
+    /// Ontario Health Premium bands on annual taxable income (V2): threshold, rate and the premium's maximum in the band
+    pub on_health_premium: &'static [(f64, f64, f64)],
+}
+
+/// Calendar years with constants, in ascending order.
+pub fn supported_years() -> &'static [u16] {
+    &[2023, 2024, 2025]
+}
+
+/// The constants for a calendar year, if the year is supported.
+pub fn constants(year: u16) -> Option<&'static YearConstants> {
+    match year {
+        2023 => Some(&v2023::CONSTANTS),
+        2024 => Some(&v2024::CONSTANTS),
+        2025 => Some(&v2025::CONSTANTS),
+        _ => None,
+    }
+}
+
+/** One row of a CRA rates table.
+*
+*   The rate (R or V) and constant (K or KP) apply to annual taxable income above `threshold`.
+*/
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Bracket {
+    pub threshold: f64,
+    pub rate: f64,
+    pub constant: f64,
+}
+
+/** Select the bracket that applies to the annual taxable income
+*
+*   A bracket's upper bound is inclusive; income exactly on a threshold uses the lower bracket.
+*
+* Given:
+*
+*   brackets: Rates table, ordered by ascending threshold, the first threshold being 0
+*
+*   A: Annual taxable income
+*/
+#[allow(non_snake_case)]
+pub fn bracket(brackets: &[Bracket], A: f64) -> &Bracket {
+    brackets
+        .iter()
+        .rev()
+        .find(|b| A > b.threshold)
+        .unwrap_or(&brackets[0])
+}
+
+/** The constant (K or KP) for the bracket that applies to the annual taxable income
+*
+*   The tax saved on the income in the lower brackets by applying the bracket's rate to all of A, rounded to the dollar as in the CRA tables: the sum, over each threshold up to the bracket, of the threshold times the increase in rate at it.
+*
+* Given:
+*
+*   brackets: Rates table, ordered by ascending threshold, the first threshold being 0
+*
+*   A: Annual taxable income
+*/
+#[allow(non_snake_case)]
+pub fn constant(brackets: &[Bracket], A: f64) -> f64 {
+    let applicable = bracket(brackets, A);
+    let constant: f64 = brackets
+        .windows(2)
+        .take_while(|pair| pair[0].threshold < applicable.threshold)
+        .map(|pair| pair[1].threshold * (pair[1].rate - pair[0].rate))
+        .sum();
+    // To the cent first, so a constant ending in exactly 50 cents is not rounded down by the floating-point rate differences
+    utils::round(constant).round()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_supported_years_have_constants() {
+        assert!(supported_years().contains(&2025));
+        for &year in supported_years() {
+            assert_eq!(constants(year).map(|c| c.year), Some(year));
+        }
+        assert_eq!(constants(2022), None);
+    }
+}
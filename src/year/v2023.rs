@@ -0,0 +1,63 @@
+//! 2023 was the last year of the CPP enhancement's first phase: there is no second additional contribution (CPP2).
+
+use crate::province::Province;
+use crate::year::{Bracket, YearConstants};
+
+/// Lowest federal tax rate, used to calculate the federal non-refundable tax credits
+pub const LOWEST_FEDERAL_RATE: f64 = 0.15;
+pub const FEDERAL_BRACKETS: [Bracket; 5] = [
+    Bracket { threshold: 0.0, rate: LOWEST_FEDERAL_RATE, constant: 0.0 },
+    Bracket { threshold: 53359.0, rate: 0.205, constant: 2935.0 },
+    Bracket { threshold: 106717.0, rate: 0.26, constant: 8804.0 },
+    Bracket { threshold: 165430.0, rate: 0.29, constant: 13767.0 },
+    Bracket { threshold: 235675.0, rate: 0.33, constant: 23194.0 },
+];
+
+/// Lowest provincial or territorial tax rate, used for the K1P and K2P credits
+pub const LOWEST_PROVINCIAL_RATES: [(Province, f64); 13] = [
+    (Province::Alberta, 0.1),
+    (Province::BritishColumbia, 0.0506),
+    (Province::Manitoba, 0.108),
+    (Province::NewBrunswick, 0.094),
+    (Province::NewfoundlandAndLabrador, 0.087),
+    (Province::NorthwestTerritories, 0.059),
+    (Province::NovaScotia, 0.0879),
+    (Province::Nunavut, 0.04),
+    (Province::Ontario, 0.0505),
+    (Province::PrinceEdwardIsland, 0.098),
+    (Province::Quebec, 0.14),
+    (Province::Saskatchewan, 0.105),
+    (Province::Yukon, 0.064),
+];
+
+pub const CONSTANTS: YearConstants = YearConstants {
+    year: 2023,
+    federal_brackets: &FEDERAL_BRACKETS,
+    lowest_provincial_rates: &LOWEST_PROVINCIAL_RATES,
+    cpp_base_rate: 0.0495,
+    cpp_first_additional_rate: 0.01,
+    cpp_second_additional_rate: 0.0,
+    ympe: 66600.0,
+    yampe: 66600.0,
+    cpp_basic_exemption: 3500.0,
+    cpp_max_contributions: 3123.45,
+    cpp_total_max_contributions: 3754.45,
+    qpp_rate: 0.064,
+    qpp_base_rate: 0.054,
+    qpp_max_contributions: 4038.4,
+    canada_employment_amount: 1368.0,
+    federal_basic_personal_amount: 15000.0,
+    federal_reduced_basic_personal_amount: 13521.0,
+    ei_rate: 0.0163,
+    ei_max_contributions: 1002.45,
+    mie: 61500.0,
+    qpip_rate: 0.00494,
+    qpip_max_contributions: 449.54,
+    ei_quebec_rate: 0.0127,
+    ei_quebec_max_contributions: 781.05,
+    on_surtax: &[(5315.0, 0.20), (6802.0, 0.36)],
+    pei_surtax: &[(12500.0, 0.10)],
+    on_reduction_base: 257.0,
+    on_per_dependent: 475.0,
+    on_health_premium: &[(20000.0, 0.06, 300.0), (36000.0, 0.06, 450.0), (48000.0, 0.25, 600.0), (72000.0, 0.25, 750.0), (200000.0, 0.25, 900.0)],
+};
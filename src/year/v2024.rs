@@ -0,0 +1,61 @@
+use crate::province::Province;
+use crate::year::{Bracket, YearConstants};
+
+/// Lowest federal tax rate, used to calculate the federal non-refundable tax credits
+pub const LOWEST_FEDERAL_RATE: f64 = 0.15;
+pub const FEDERAL_BRACKETS: [Bracket; 5] = [
+    Bracket { threshold: 0.0, rate: LOWEST_FEDERAL_RATE, constant: 0.0 },
+    Bracket { threshold: 55867.0, rate: 0.205, constant: 3073.0 },
+    Bracket { threshold: 111733.0, rate: 0.26, constant: 9218.0 },
+    Bracket { threshold: 173205.0, rate: 0.29, constant: 14414.0 },
+    Bracket { threshold: 246752.0, rate: 0.33, constant: 24284.0 },
+];
+
+/// Lowest provincial or territorial tax rate, used for the K1P and K2P credits
+pub const LOWEST_PROVINCIAL_RATES: [(Province, f64); 13] = [
+    (Province::Alberta, 0.1),
+    (Province::BritishColumbia, 0.0506),
+    (Province::Manitoba, 0.108),
+    (Province::NewBrunswick, 0.094),
+    (Province::NewfoundlandAndLabrador, 0.087),
+    (Province::NorthwestTerritories, 0.059),
+    (Province::NovaScotia, 0.0879),
+    (Province::Nunavut, 0.04),
+    (Province::Ontario, 0.0505),
+    (Province::PrinceEdwardIsland, 0.0965),
+    (Province::Quebec, 0.14),
+    (Province::Saskatchewan, 0.105),
+    (Province::Yukon, 0.064),
+];
+
+pub const CONSTANTS: YearConstants = YearConstants {
+    year: 2024,
+    federal_brackets: &FEDERAL_BRACKETS,
+    lowest_provincial_rates: &LOWEST_PROVINCIAL_RATES,
+    cpp_base_rate: 0.0495,
+    cpp_first_additional_rate: 0.01,
+    cpp_second_additional_rate: 0.04,
+    ympe: 68500.0,
+    yampe: 73200.0,
+    cpp_basic_exemption: 3500.0,
+    cpp_max_contributions: 3217.5,
+    cpp_total_max_contributions: 3867.5,
+    qpp_rate: 0.064,
+    qpp_base_rate: 0.054,
+    qpp_max_contributions: 4160.0,
+    canada_employment_amount: 1433.0,
+    federal_basic_personal_amount: 15705.0,
+    federal_reduced_basic_personal_amount: 14156.0,
+    ei_rate: 0.0166,
+    ei_max_contributions: 1049.12,
+    mie: 63200.0,
+    qpip_rate: 0.00494,
+    qpip_max_contributions: 464.36,
+    ei_quebec_rate: 0.0132,
+    ei_quebec_max_contributions: 834.24,
+    on_surtax: &[(5554.0, 0.20), (7108.0, 0.36)],
+    pei_surtax: &[],
+    on_reduction_base: 286.0,
+    on_per_dependent: 529.0,
+    on_health_premium: &[(20000.0, 0.06, 300.0), (36000.0, 0.06, 450.0), (48000.0, 0.25, 600.0), (72000.0, 0.25, 750.0), (200000.0, 0.25, 900.0)],
+};
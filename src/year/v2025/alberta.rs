@@ -0,0 +1,17 @@
+//! 2025 Alberta Tax Constants
+
+use crate::year::Bracket;
+
+/// Lowest Alberta tax rate, used for the K1P and K2P credits
+pub const LOWEST_RATE: f64 = 0.10;
+pub const BASIC_AMT: f64 = 22323.0;
+pub const BRACKETS: [Bracket; 5] = [
+    Bracket { threshold: 0.0, rate: LOWEST_RATE, constant: 0.0 },
+    Bracket { threshold: 151234.0, rate: 0.12, constant: 3025.0 },
+    Bracket { threshold: 181481.0, rate: 0.13, constant: 4839.0 },
+    Bracket { threshold: 241974.0, rate: 0.14, constant: 7259.0 },
+    Bracket { threshold: 362961.0, rate: 0.15, constant: 10889.0 },
+];
+
+pub const CLAIM_CODES: [f64; 10] =
+    [22323.0, 26644.0, 30965.0, 35286.0, 39607.0, 43928.0, 48249.0, 52570.0, 56891.0, 61212.0];
@@ -0,0 +1,8 @@
+//! 2025 British Columbia Tax Constants
+
+/// Lowest British Columbia tax rate, used for the K1P and K2P credits
+pub const LOWEST_RATE: f64 = 0.0506;
+/// British Columbia tax reduction amount, and the net income above which it is reduced at the rate
+pub const REDUCTION_BASE: f64 = 562.0;
+pub const REDUCTION_THRESHOLD: f64 = 25020.0;
+pub const REDUCTION_RATE: f64 = 0.0356;
@@ -0,0 +1,42 @@
+//! 2025 Federal Tax Constants
+
+use crate::year::Bracket;
+
+pub const INCOME_THRESHOLD_4: f64 = 177882.0;
+pub const INCOME_THRESHOLD_5: f64 = 253414.0;
+pub const MINIMUM_BASIC_AMT: f64 = 16129.0;
+pub const MAXIMUM_BASIC_AMT: f64 = 14538.0;
+pub const CANADA_EMPLOYMENT_AMT: f64 = 1471.0;
+pub const NORTHERN_RESIDENCY_DAILY_AMT: f64 = 22.0;
+/// Refundable Quebec abatement, as a share of the annual basic federal tax (T3) of Quebec employees
+pub const QUEBEC_ABATEMENT_RATE: f64 = 0.165;
+
+/// Lowest federal tax rate, used to calculate the federal non-refundable tax credits
+pub const LOWEST_RATE: f64 = 0.15;
+pub const BRACKETS: [Bracket; 5] = [
+    Bracket { threshold: 0.0, rate: LOWEST_RATE, constant: 0.0 },
+    Bracket { threshold: 57375.0, rate: 0.205, constant: 3156.0 },
+    Bracket { threshold: 114750.0, rate: 0.26, constant: 9467.0 },
+    Bracket { threshold: INCOME_THRESHOLD_4, rate: 0.29, constant: 14803.0 },
+    Bracket { threshold: INCOME_THRESHOLD_5, rate: 0.33, constant: 24940.0 },
+];
+
+/// Highest total claim amount for each TD1 claim code from 1 to 10; code 1 is up to the basic personal amount, and each code after it a range of the same width
+pub const CLAIM_CODES: [f64; 10] =
+    [16129.0, 19251.0, 22373.0, 25495.0, 28617.0, 31739.0, 34861.0, 37983.0, 41105.0, 44227.0];
+
+/// Lump-sum payments above these amounts are withheld at the second and third flat rates
+pub const LUMP_SUM_THRESHOLD_1: f64 = 5000.0;
+pub const LUMP_SUM_THRESHOLD_2: f64 = 15000.0;
+/// Flat withholding on lump-sum payments, such as retiring allowances, outside Quebec: the rate of the tier on the whole payment, up to and including the next threshold
+pub const LUMP_SUM_TIERS: [Bracket; 3] = [
+    Bracket { threshold: 0.0, rate: 0.10, constant: 0.0 },
+    Bracket { threshold: LUMP_SUM_THRESHOLD_1, rate: 0.20, constant: 0.0 },
+    Bracket { threshold: LUMP_SUM_THRESHOLD_2, rate: 0.30, constant: 0.0 },
+];
+/// Flat federal withholding on lump-sum payments in Quebec; Quebec tax is withheld separately
+pub const QUEBEC_LUMP_SUM_TIERS: [Bracket; 3] = [
+    Bracket { threshold: 0.0, rate: 0.05, constant: 0.0 },
+    Bracket { threshold: LUMP_SUM_THRESHOLD_1, rate: 0.10, constant: 0.0 },
+    Bracket { threshold: LUMP_SUM_THRESHOLD_2, rate: 0.15, constant: 0.0 },
+];
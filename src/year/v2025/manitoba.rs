@@ -0,0 +1,4 @@
+//! 2025 Manitoba Tax Constants
+
+/// Lowest Manitoba tax rate, used for the K1P and K2P credits
+pub const LOWEST_RATE: f64 = 0.108;
@@ -0,0 +1,98 @@
+//! 2025 Constants
+//!
+//! Each jurisdiction's brackets, lowest rate and basic amounts are in its own submodule; the CPP, QPP, EI and QPIP amounts are here. The original top-level names are re-exported.
+
+use crate::province::Province;
+use crate::year::YearConstants;
+
+pub mod alberta;
+pub mod british_columbia;
+pub mod federal;
+pub mod manitoba;
+pub mod ontario;
+pub mod prince_edward_island;
+pub mod quebec;
+
+pub use federal::{
+    BRACKETS as FEDERAL_BRACKETS, CANADA_EMPLOYMENT_AMT, CLAIM_CODES as FEDERAL_CLAIM_CODES, INCOME_THRESHOLD_4,
+    INCOME_THRESHOLD_5, LOWEST_RATE as LOWEST_FEDERAL_RATE, LUMP_SUM_THRESHOLD_1, LUMP_SUM_THRESHOLD_2, LUMP_SUM_TIERS,
+    MAXIMUM_BASIC_AMT, MINIMUM_BASIC_AMT, NORTHERN_RESIDENCY_DAILY_AMT, QUEBEC_ABATEMENT_RATE, QUEBEC_LUMP_SUM_TIERS,
+};
+pub use alberta::{BASIC_AMT as ALBERTA_BASIC_AMT, BRACKETS as ALBERTA_BRACKETS, CLAIM_CODES as ALBERTA_CLAIM_CODES};
+pub use british_columbia::{
+    REDUCTION_BASE as BC_REDUCTION_BASE, REDUCTION_RATE as BC_REDUCTION_RATE, REDUCTION_THRESHOLD as BC_REDUCTION_THRESHOLD,
+};
+pub use ontario::{
+    BASIC_AMT as ONTARIO_BASIC_AMT, BRACKETS as ONTARIO_BRACKETS, CLAIM_CODES as ONTARIO_CLAIM_CODES,
+    HEALTH_PREMIUM as ON_HEALTH_PREMIUM, PER_DEPENDENT as ON_PER_DEPENDENT, REDUCTION_BASE as ON_REDUCTION_BASE,
+    SURTAX as ON_SURTAX,
+};
+pub use prince_edward_island::{
+    REDUCTION_BASE as PEI_REDUCTION_BASE, REDUCTION_RATE as PEI_REDUCTION_RATE, REDUCTION_THRESHOLD as PEI_REDUCTION_THRESHOLD,
+};
+pub use quebec::{BASIC_AMT as QUEBEC_BASIC_AMT, BRACKETS as QUEBEC_BRACKETS};
+
+pub const EI_RATE: f64 = 0.0164;
+pub const EI_MAX_CONTRIBUTIONS: f64 = 1077.48;
+pub const CPP_MAX_CONTRIBUTIONS: f64 = 3356.1;
+pub const YMPE: f64 = 71300.0;
+pub const CPP_BASIC_EXEMPTION: f64 = 3500.0;
+pub const YAMPE: f64 = 81200.0;
+pub const MIE: f64 = 65700.0;
+
+pub const QPP_RATE: f64 = 0.0640;
+pub const QPP_MAX_CONTRIBUTIONS: f64 = 4339.2;
+pub const QPP_BASE_RATE: f64 = 0.054;
+pub const QPIP_RATE: f64 = 0.00494;
+pub const QPIP_MAX_CONTRIBUTIONS: f64 = 484.12;
+pub const EI_QUEBEC_RATE: f64 = 0.0131;
+pub const EI_QUEBEC_MAX_CONTRIBUTIONS: f64 = 860.67;
+
+/// Lowest provincial or territorial tax rate, used for the K1P and K2P credits
+pub const LOWEST_PROVINCIAL_RATES: [(Province, f64); 13] = [
+    (Province::Alberta, alberta::LOWEST_RATE),
+    (Province::BritishColumbia, british_columbia::LOWEST_RATE),
+    (Province::Manitoba, manitoba::LOWEST_RATE),
+    (Province::NewBrunswick, 0.094),
+    (Province::NewfoundlandAndLabrador, 0.087),
+    (Province::NorthwestTerritories, 0.059),
+    (Province::NovaScotia, 0.0879),
+    (Province::Nunavut, 0.04),
+    (Province::Ontario, ontario::LOWEST_RATE),
+    (Province::PrinceEdwardIsland, prince_edward_island::LOWEST_RATE),
+    (Province::Quebec, quebec::LOWEST_RATE),
+    (Province::Saskatchewan, 0.105),
+    (Province::Yukon, 0.064),
+];
+
+pub const CONSTANTS: YearConstants = YearConstants {
+    year: 2025,
+    federal_brackets: &federal::BRACKETS,
+    lowest_provincial_rates: &LOWEST_PROVINCIAL_RATES,
+    cpp_base_rate: 0.0495,
+    cpp_first_additional_rate: 0.01,
+    cpp_second_additional_rate: 0.04,
+    ympe: YMPE,
+    yampe: YAMPE,
+    cpp_basic_exemption: CPP_BASIC_EXEMPTION,
+    cpp_max_contributions: CPP_MAX_CONTRIBUTIONS,
+    cpp_total_max_contributions: 4034.1,
+    qpp_rate: QPP_RATE,
+    qpp_base_rate: QPP_BASE_RATE,
+    qpp_max_contributions: QPP_MAX_CONTRIBUTIONS,
+    canada_employment_amount: federal::CANADA_EMPLOYMENT_AMT,
+    federal_basic_personal_amount: federal::MINIMUM_BASIC_AMT,
+    federal_reduced_basic_personal_amount: federal::MAXIMUM_BASIC_AMT,
+    ei_rate: EI_RATE,
+    ei_max_contributions: EI_MAX_CONTRIBUTIONS,
+    mie: MIE,
+    qpip_rate: QPIP_RATE,
+    qpip_max_contributions: QPIP_MAX_CONTRIBUTIONS,
+    ei_quebec_rate: EI_QUEBEC_RATE,
+    ei_quebec_max_contributions: EI_QUEBEC_MAX_CONTRIBUTIONS,
+    on_surtax: &ontario::SURTAX,
+    pei_surtax: &[],
+    on_reduction_base: ontario::REDUCTION_BASE,
+    on_per_dependent: ontario::PER_DEPENDENT,
+    on_health_premium: &ontario::HEALTH_PREMIUM,
+};
@@ -0,0 +1,26 @@
+//! 2025 Ontario Tax Constants
+
+use crate::year::Bracket;
+
+/// Lowest Ontario tax rate, used for the K1P and K2P credits
+pub const LOWEST_RATE: f64 = 0.0505;
+pub const BASIC_AMT: f64 = 12747.0;
+pub const BRACKETS: [Bracket; 5] = [
+    Bracket { threshold: 0.0, rate: LOWEST_RATE, constant: 0.0 },
+    Bracket { threshold: 52886.0, rate: 0.0915, constant: 2168.0 },
+    Bracket { threshold: 105775.0, rate: 0.1116, constant: 4294.0 },
+    Bracket { threshold: 150000.0, rate: 0.1216, constant: 5794.0 },
+    Bracket { threshold: 220000.0, rate: 0.1316, constant: 7994.0 },
+];
+
+/// Ontario surtax thresholds and rates on the basic provincial tax (T4)
+pub const SURTAX: [(f64, f64); 2] = [(5710.0, 0.20), (7307.0, 0.36)];
+/// Ontario basic tax reduction amount and the additional amount for each eligible dependant
+pub const REDUCTION_BASE: f64 = 294.0;
+pub const PER_DEPENDENT: f64 = 544.0;
+/// Ontario Health Premium thresholds, rates and maximums on annual taxable income; unchanged since 2004
+pub const HEALTH_PREMIUM: [(f64, f64, f64); 5] =
+    [(20000.0, 0.06, 300.0), (36000.0, 0.06, 450.0), (48000.0, 0.25, 600.0), (72000.0, 0.25, 750.0), (200000.0, 0.25, 900.0)];
+
+pub const CLAIM_CODES: [f64; 10] =
+    [12747.0, 15214.0, 17681.0, 20148.0, 22615.0, 25082.0, 27549.0, 30016.0, 32483.0, 34950.0];
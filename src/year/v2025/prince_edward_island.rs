@@ -0,0 +1,8 @@
+//! 2025 Prince Edward Island Tax Constants
+
+/// Lowest Prince Edward Island tax rate, used for the K1P and K2P credits
+pub const LOWEST_RATE: f64 = 0.095;
+/// Prince Edward Island low-income tax reduction amount, and the net income above which it is reduced at the rate
+pub const REDUCTION_BASE: f64 = 350.0;
+pub const REDUCTION_THRESHOLD: f64 = 23000.0;
+pub const REDUCTION_RATE: f64 = 0.05;
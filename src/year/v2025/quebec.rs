@@ -0,0 +1,15 @@
+//! 2025 Quebec Tax Constants
+//!
+//! The Quebec basic personal amount and tax rates are set by Revenu Québec and do not flow through T4.
+
+use crate::year::Bracket;
+
+/// Lowest Quebec tax rate, used for the Quebec personal credit
+pub const LOWEST_RATE: f64 = 0.14;
+pub const BASIC_AMT: f64 = 18571.0;
+pub const BRACKETS: [Bracket; 4] = [
+    Bracket { threshold: 0.0, rate: LOWEST_RATE, constant: 0.0 },
+    Bracket { threshold: 53255.0, rate: 0.19, constant: 2663.0 },
+    Bracket { threshold: 106495.0, rate: 0.24, constant: 7988.0 },
+    Bracket { threshold: 129590.0, rate: 0.2575, constant: 10255.0 },
+];
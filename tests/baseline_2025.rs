@@ -0,0 +1,111 @@
+//! Snapshot of 2025 results for a fixed set of scenarios, compared against `tests/baseline_2025.txt`.
+//!
+//! Any change to a result fails the test. When the change is intentional, regenerate the baseline with
+//! `UPDATE_BASELINE=1 cargo test --test baseline_2025` and commit it with the change.
+
+use std::path::Path;
+
+use cdn_payroll::pay_period::PayPeriod;
+use cdn_payroll::payroll::{self, PayeeType, PayrollContext, PayrollInput, Residency};
+use cdn_payroll::province::Province;
+use cdn_payroll::test_fixtures;
+use cdn_payroll::year::v2025;
+
+fn scenarios() -> Vec<(&'static str, (PayrollContext, PayrollInput))> {
+    let (ctx, input) = test_fixtures::ontario_biweekly_employee();
+    let quebec = || PayrollInput { provincial_claim: Some(v2025::QUEBEC_BASIC_AMT), ..input.clone() };
+    let alberta = || PayrollInput { provincial_claim: Some(v2025::ALBERTA_BASIC_AMT), ..input.clone() };
+
+    vec![
+        ("ontario_biweekly_employee", test_fixtures::ontario_biweekly_employee()),
+        ("ontario_weekly_employee_with_dependents", test_fixtures::ontario_weekly_employee_with_dependents()),
+        ("alberta_monthly_pensioner", test_fixtures::alberta_monthly_pensioner()),
+        ("alberta_semi_monthly_employee", test_fixtures::alberta_semi_monthly_employee()),
+        (
+            "ontario_monthly_high_earner",
+            (
+                PayrollContext { pay_period: PayPeriod::Monthly, ..ctx.clone() },
+                PayrollInput { cash_income: 20000.0, ..input.clone() },
+            ),
+        ),
+        (
+            "ontario_four_weekly_with_benefits",
+            (
+                PayrollContext { pay_period: PayPeriod::FourWeekly, ..ctx.clone() },
+                PayrollInput { cash_income: 4200.0, taxable_benefits: 150.0, ..input.clone() },
+            ),
+        ),
+        (
+            "ontario_biweekly_pensioner",
+            (
+                PayrollContext { payee_type: PayeeType::Pensioner, ..ctx.clone() },
+                PayrollInput { cash_income: 1800.0, contribution_months: 0, ..input.clone() },
+            ),
+        ),
+        (
+            "ontario_biweekly_outside_canada",
+            (PayrollContext { residency: Residency::OutsideCanada, ..ctx.clone() }, input.clone()),
+        ),
+        (
+            "alberta_weekly_low_earner",
+            (
+                PayrollContext { province: Province::Alberta, pay_period: PayPeriod::Weekly, ..ctx.clone() },
+                PayrollInput { cash_income: 450.0, ..alberta() },
+            ),
+        ),
+        (
+            "alberta_biweekly_with_support_and_extra_tax",
+            (
+                PayrollContext { province: Province::Alberta, ..ctx.clone() },
+                PayrollInput { cash_income: 3500.0, support_payments: 100.0, additional_tax: 50.0, ..alberta() },
+            ),
+        ),
+        (
+            "quebec_biweekly_employee",
+            (PayrollContext { province: Province::Quebec, ..ctx.clone() }, quebec()),
+        ),
+        (
+            "quebec_monthly_employee",
+            (
+                PayrollContext { province: Province::Quebec, pay_period: PayPeriod::Monthly, ..ctx.clone() },
+                PayrollInput { cash_income: 7500.0, retirement_contributions: 300.0, ..quebec() },
+            ),
+        ),
+    ]
+}
+
+fn render() -> String {
+    let mut lines = vec!["scenario gross cpp cpp2 ei qpip annual_taxable_income federal_tax provincial_tax tax net".to_string()];
+    for (name, (ctx, input)) in scenarios() {
+        let r = payroll::calculate(&ctx, &input).unwrap_or_else(|e| panic!("{}: {:?}", name, e));
+        lines.push(format!(
+            "{} {:.2} {:.2} {:.2} {:.2} {:.2} {:.2} {:.2} {:.2} {:.2} {:.2}",
+            name, r.gross, r.cpp, r.cpp2, r.ei, r.qpip, r.annual_taxable_income, r.federal_tax, r.provincial_tax, r.tax, r.net
+        ));
+    }
+    lines.join("\n") + "\n"
+}
+
+#[test]
+fn results_match_the_2025_baseline() {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/baseline_2025.txt");
+    let actual = render();
+
+    if std::env::var_os("UPDATE_BASELINE").is_some() {
+        std::fs::write(&path, &actual).unwrap();
+        return;
+    }
+
+    let expected = std::fs::read_to_string(&path).expect("tests/baseline_2025.txt is missing; run with UPDATE_BASELINE=1");
+    let changed: Vec<String> = expected
+        .lines()
+        .zip(actual.lines())
+        .filter(|(e, a)| e != a)
+        .map(|(e, a)| format!("  expected: {}\n    actual: {}", e, a))
+        .collect();
+    assert!(
+        changed.is_empty() && expected.lines().count() == actual.lines().count(),
+        "results differ from the baseline; if intentional, regenerate with UPDATE_BASELINE=1\n{}",
+        changed.join("\n")
+    );
+}
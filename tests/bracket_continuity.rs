@@ -0,0 +1,61 @@
+use cdn_payroll::federal_income_tax;
+use cdn_payroll::provincial_income_tax::provincial_income_tax;
+use cdn_payroll::quebec;
+use cdn_payroll::year::{self, Bracket};
+
+/// The CRA rounds each bracket's constant to the dollar, so the constants either side of a threshold are each up to 50 cents from
+/// the exact ones and the tax can step by up to a dollar, either way, at the threshold.
+const CONSTANT_ROUNDING: f64 = 1.00;
+
+/// The basic tax (T3 or T4, before credits) with the bracket selected for the income, as `payroll::calculate` does.
+fn basic_tax(brackets: &[Bracket], income: f64) -> f64 {
+    let Bracket { rate, constant, .. } = *year::bracket(brackets, income);
+    federal_income_tax::T3(rate, income, constant, 0.0, 0.0, 0.0, 0.0)
+}
+
+/// Each step of a cent either side of a threshold adds a cent at the marginal rate, give or take rounding.
+fn assert_continuous(table: &str, brackets: &[Bracket]) {
+    for pair in brackets.windows(2) {
+        let (below, above) = (&pair[0], &pair[1]);
+        let threshold = above.threshold;
+
+        // On the threshold is still the lower bracket
+        assert_eq!(year::bracket(brackets, threshold), below, "{} at {}", table, threshold);
+        assert_eq!(year::bracket(brackets, threshold + 0.01), above, "{} at {}", table, threshold);
+
+        let step_into = basic_tax(brackets, threshold) - basic_tax(brackets, threshold - 0.01);
+        assert!((step_into - 0.01 * below.rate).abs() <= 0.01 + 1e-9, "{} into {}: {}", table, threshold, step_into);
+
+        let step_over = basic_tax(brackets, threshold + 0.01) - basic_tax(brackets, threshold);
+        assert!(
+            (step_over - 0.01 * above.rate).abs() <= CONSTANT_ROUNDING + 0.01 + 1e-9,
+            "{} over {}: {}",
+            table,
+            threshold,
+            step_over
+        );
+    }
+}
+
+#[test]
+fn federal_tax_is_continuous_at_every_threshold() {
+    for &year in year::supported_years() {
+        let constants = year::constants(year).unwrap();
+        assert_continuous(&format!("federal {}", year), constants.federal_brackets);
+    }
+}
+
+#[test]
+fn provincial_tax_is_continuous_at_every_threshold() {
+    for &year in year::supported_years() {
+        let constants = year::constants(year).unwrap();
+        for &province in provincial_income_tax::supported_provinces() {
+            if let Ok(brackets) = provincial_income_tax::brackets(province, constants) {
+                assert_continuous(&format!("{:?} {}", province, year), brackets);
+            }
+        }
+        if let Ok(brackets) = quebec::brackets(constants) {
+            assert_continuous(&format!("Quebec {}", year), brackets);
+        }
+    }
+}
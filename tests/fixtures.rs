@@ -0,0 +1,46 @@
+use cdn_payroll::payroll::{self, PayeeType};
+use cdn_payroll::test_fixtures;
+use cdn_payroll::year::v2025;
+
+#[test]
+fn every_fixture_produces_sensible_deductions() {
+    for (ctx, input) in test_fixtures::all() {
+        let result = payroll::calculate(&ctx, &input).unwrap();
+        assert!(result.tax > 0.0, "{:?}", input);
+        assert!(result.net > 0.0 && result.net < result.gross, "{:?}", input);
+        assert!(result.cpp <= 0.0595 * input.gross() + 0.005);
+        assert!(result.ei <= 0.0164 * input.gross() + 0.005 && result.ei <= v2025::EI_MAX_CONTRIBUTIONS);
+    }
+}
+
+#[test]
+fn pensioner_pays_no_cpp_or_ei() {
+    let (ctx, input) = test_fixtures::alberta_monthly_pensioner();
+    assert_eq!(ctx.payee_type, PayeeType::Pensioner);
+
+    let result = payroll::calculate(&ctx, &input).unwrap();
+    assert_eq!(result.cpp, 0.0);
+    assert_eq!(result.ei, 0.0);
+    assert_eq!(result.annual_taxable_income, 36000.0);
+}
+
+#[test]
+fn ontario_biweekly_employee() {
+    let (ctx, input) = test_fixtures::ontario_biweekly_employee();
+    let result = payroll::calculate(&ctx, &input).unwrap();
+    assert_eq!(result.cpp, 129.3);
+    assert_eq!(result.ei, 37.85);
+    assert_eq!(result.tax, 340.64);
+    assert_eq!(result.net, 1799.9);
+}
+
+#[test]
+fn dependents_lower_ontario_tax_at_low_income() {
+    let (ctx, with) = test_fixtures::ontario_weekly_employee_with_dependents();
+    let without = payroll::PayrollInput { dependents: Default::default(), cash_income: 700.0, ..with.clone() };
+    let with = payroll::PayrollInput { cash_income: 700.0, ..with };
+
+    let with = payroll::calculate(&ctx, &with).unwrap();
+    let without = payroll::calculate(&ctx, &without).unwrap();
+    assert!(with.provincial_tax < without.provincial_tax);
+}
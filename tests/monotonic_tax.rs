@@ -0,0 +1,74 @@
+use cdn_payroll::payroll::{self, PayrollContext, PayrollInput};
+use cdn_payroll::provincial_income_tax::provincial_income_tax;
+use cdn_payroll::test_fixtures;
+
+/// Small xorshift generator, so the incomes are random but the same on every run.
+struct Incomes(u64);
+
+impl Incomes {
+    /// Gross pay for a biweekly pay period, from $0 to $10,000 ($260,000 a year).
+    fn next(&mut self) -> f64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        (self.0 % 1_000_001) as f64 / 100.0
+    }
+}
+
+fn tax(ctx: &PayrollContext, input: &PayrollInput, cash_income: f64) -> f64 {
+    payroll::calculate(ctx, &PayrollInput { cash_income, ..input.clone() }).unwrap().tax
+}
+
+/// Narrows a pair where the tax decreases to the closest pair of incomes, to the cent, where it still does.
+fn shrink(ctx: &PayrollContext, input: &PayrollInput, mut lower: f64, mut upper: f64) -> (f64, f64) {
+    while upper - lower > 0.01 {
+        let middle = ((lower + upper) * 50.0).round() / 100.0;
+        if middle <= lower || middle >= upper {
+            break;
+        }
+        if tax(ctx, input, lower) > tax(ctx, input, middle) {
+            upper = middle;
+        } else if tax(ctx, input, middle) > tax(ctx, input, upper) {
+            lower = middle;
+        } else {
+            break;
+        }
+    }
+    (lower, upper)
+}
+
+#[test]
+fn tax_never_decreases_as_gross_increases() {
+    // A pensioner's annual taxable income is exactly P × gross, so the thresholds themselves are reached
+    for (ctx, input) in [test_fixtures::ontario_biweekly_employee(), test_fixtures::alberta_monthly_pensioner()] {
+        let periods = ctx.periods_per_year() as f64;
+
+        for &province in provincial_income_tax::supported_provinces() {
+            let ctx = PayrollContext { province, ..ctx.clone() };
+
+            let mut incomes = Incomes(0x9E37_79B9_7F4A_7C15);
+            let mut gross: Vec<f64> = (0..2000).map(|_| incomes.next() * 26.0 / periods).collect();
+            // Either side of the Ontario health premium thresholds, where the bands used to leave gaps
+            for threshold in [20000.0, 36000.0, 48000.0, 72000.0, 200000.0] {
+                let per_period = ((threshold / periods) * 100.0_f64).round() / 100.0;
+                gross.extend([per_period - 0.01, per_period, per_period + 0.01, per_period + 0.05]);
+            }
+            gross.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+            for pair in gross.windows(2) {
+                if tax(&ctx, &input, pair[0]) > tax(&ctx, &input, pair[1]) {
+                    let (lower, upper) = shrink(&ctx, &input, pair[0], pair[1]);
+                    panic!(
+                        "{:?}, {:?}: tax on {} is {}, more than {} on {}",
+                        ctx.payee_type,
+                        province,
+                        lower,
+                        tax(&ctx, &input, lower),
+                        tax(&ctx, &input, upper),
+                        upper
+                    );
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,38 @@
+use cdn_payroll::basic_personal_income::{self, ZoneType};
+use cdn_payroll::payroll::{self, PayrollInput};
+use cdn_payroll::test_fixtures;
+use cdn_payroll::year::v2025;
+
+#[test]
+fn northern_resident_bpa_and_taxable_income_use_the_same_deduction() {
+    let (ctx, southern) = test_fixtures::ontario_biweekly_employee();
+    let southern = PayrollInput { cash_income: 8000.0, ..southern };
+    let northern = PayrollInput { prescribed_zone: ZoneType::Northern, prescribed_zone_days: 365, ..southern.clone() };
+    let deduction = basic_personal_income::prescribed_zone_deduction(ZoneType::Northern, 365);
+    assert_eq!(deduction, 8030.0);
+
+    let southern = payroll::calculate(&ctx, &southern).unwrap();
+    let northern = payroll::calculate(&ctx, &northern).unwrap();
+
+    assert_eq!(northern.annual_taxable_income, southern.annual_taxable_income - deduction);
+    assert_eq!(northern.basic_personal_amount, southern.basic_personal_amount);
+    assert_eq!(
+        northern.basic_personal_amount,
+        basic_personal_income::BPAF(northern.annual_taxable_income, deduction, ctx.year).unwrap()
+    );
+    assert!(northern.basic_personal_amount < v2025::MINIMUM_BASIC_AMT);
+    assert!(northern.basic_personal_amount > v2025::MAXIMUM_BASIC_AMT);
+    assert!(northern.tax < southern.tax);
+}
+
+#[test]
+fn intermediate_zone_claims_half_the_deduction() {
+    let (ctx, input) = test_fixtures::ontario_biweekly_employee();
+    let southern = payroll::calculate(&ctx, &input).unwrap();
+    let intermediate =
+        payroll::calculate(&ctx, &PayrollInput { prescribed_zone: ZoneType::Intermediate, prescribed_zone_days: 365, ..input })
+            .unwrap();
+
+    assert_eq!(intermediate.annual_taxable_income, southern.annual_taxable_income - 4015.0);
+    assert_eq!(intermediate.basic_personal_amount, v2025::MINIMUM_BASIC_AMT);
+}
@@ -0,0 +1,70 @@
+use cdn_payroll::error::PayrollError;
+use cdn_payroll::pay_calendar::NaiveDate;
+use cdn_payroll::pay_period::PayPeriod;
+use cdn_payroll::payroll::{self, PayrollContext, PayrollInput};
+use cdn_payroll::province::Province;
+use cdn_payroll::test_fixtures;
+use cdn_payroll::year::v2025;
+
+fn monthly(cash_income: f64) -> (PayrollContext, PayrollInput) {
+    let (ctx, input) = test_fixtures::ontario_biweekly_employee();
+    (PayrollContext { pay_period: PayPeriod::Monthly, ..ctx }, PayrollInput { cash_income, ..input })
+}
+
+#[test]
+fn six_months_quebec_then_six_months_ontario() {
+    let segments = [(Province::Quebec, 6), (Province::Ontario, 6)];
+    let (ctx, input) = monthly(5000.0);
+    let year = payroll::calculate_segments(&ctx, &input, &segments).unwrap();
+
+    assert_eq!(year.periods.len(), 12);
+    assert!(year.periods[..6].iter().all(|p| p.province == Province::Quebec && p.qpp > 0.0 && p.cpp == 0.0));
+    assert!(year.periods[6..].iter().all(|p| p.province == Province::Ontario && p.cpp > 0.0 && p.qpp == 0.0));
+    assert!(year.qpip > 0.0);
+    assert!(year.transfer_credit.unwrap() > 0.0);
+}
+
+#[test]
+fn combined_cpp_and_qpp_do_not_exceed_the_annual_maximum() {
+    let segments = [(Province::Quebec, 6), (Province::Ontario, 6)];
+    let (ctx, input) = monthly(10000.0);
+    let year = payroll::calculate_segments(&ctx, &input, &segments).unwrap();
+
+    // The QPP counts toward the CPP maximum at the CPP rate, so the CPP stops once the combined amount reaches it
+    let cpp_rate = v2025::CONSTANTS.cpp_base_rate + v2025::CONSTANTS.cpp_first_additional_rate;
+    let combined = year.cpp + year.qpp * (cpp_rate / v2025::QPP_RATE);
+    assert!(year.cpp > 0.0);
+    assert!((combined - v2025::CONSTANTS.cpp_total_max_contributions).abs() <= 0.01, "{}", combined);
+    assert!(year.qpp < v2025::QPP_MAX_CONTRIBUTIONS);
+    assert_eq!(year.ei, v2025::EI_MAX_CONTRIBUTIONS);
+    assert_eq!(year.periods.last().unwrap().cpp, 0.0);
+}
+
+#[test]
+fn the_pay_periods_of_a_full_year_total_the_pay_frequency() {
+    let (ctx, input) = monthly(2000.0);
+    let ctx = PayrollContext { pay_period: PayPeriod::BiWeekly, ..ctx };
+    let segments = [(Province::Quebec, 3), (Province::Ontario, 3), (Province::Quebec, 3), (Province::Ontario, 3)];
+    let year = payroll::calculate_segments(&ctx, &input, &segments).unwrap();
+
+    assert_eq!(year.periods.len(), 26);
+    assert_eq!(year.periods.iter().filter(|p| p.province == Province::Quebec).count(), 14);
+}
+
+#[test]
+fn a_cpt30_election_in_an_earlier_year_stops_the_cpp_and_qpp() {
+    let segments = [(Province::Quebec, 6), (Province::Ontario, 6)];
+    let (ctx, input) = monthly(5000.0);
+    let input = PayrollInput { cpt30_election: NaiveDate::from_ymd_opt(2024, 3, 1), ..input };
+    let year = payroll::calculate_segments(&ctx, &input, &segments).unwrap();
+
+    assert_eq!((year.cpp, year.qpp), (0.0, 0.0));
+    assert!(year.ei > 0.0);
+}
+
+#[test]
+fn segments_longer_than_a_year_are_rejected() {
+    let segments = [(Province::Quebec, 8), (Province::Ontario, 6)];
+    let (ctx, input) = monthly(5000.0);
+    assert_eq!(payroll::calculate_segments(&ctx, &input, &segments), Err(PayrollError::InvalidSegments));
+}
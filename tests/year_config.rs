@@ -0,0 +1,82 @@
+#![cfg(feature = "config")]
+
+use std::fs;
+
+use cdn_payroll::error::ConfigError;
+use cdn_payroll::year::config::{load_year_constants, parse_year_constants, save_year_constants};
+use cdn_payroll::year::v2025;
+
+#[test]
+fn constants_round_trip_through_a_config_file() {
+    let path = std::env::temp_dir().join(format!("cdn-payroll-{}-2025.toml", std::process::id()));
+    save_year_constants(&v2025::CONSTANTS, &path).unwrap();
+    let loaded = load_year_constants(&path);
+    fs::remove_file(&path).unwrap();
+
+    assert_eq!(loaded, Ok(v2025::CONSTANTS));
+}
+
+#[test]
+fn loading_a_file_again_shares_its_tables() {
+    let text = cdn_payroll::year::config::to_config(&v2025::CONSTANTS);
+    let first = parse_year_constants(&text).unwrap();
+    let second = parse_year_constants(&text).unwrap();
+
+    assert!(std::ptr::eq(first.federal_brackets, second.federal_brackets));
+    assert!(std::ptr::eq(first.lowest_provincial_rates, second.lowest_provincial_rates));
+    assert!(std::ptr::eq(first.on_surtax, second.on_surtax));
+}
+
+#[test]
+fn years_without_compiled_in_tables_are_rejected() {
+    let text = cdn_payroll::year::config::to_config(&v2025::CONSTANTS).replace("year = 2025", "year = 2026");
+    assert_eq!(parse_year_constants(&text), Err(ConfigError::UnsupportedYear(2026)));
+
+    let text = cdn_payroll::year::config::to_config(&cdn_payroll::year::v2024::CONSTANTS);
+    assert_eq!(parse_year_constants(&text), Ok(cdn_payroll::year::v2024::CONSTANTS));
+}
+
+#[test]
+fn config_errors_name_the_line_and_key() {
+    assert_eq!(
+        parse_year_constants("year = 2025\nei_rat = 0.0164\n"),
+        Err(ConfigError::UnknownKey { line: 2, key: "ei_rat".to_string() })
+    );
+    assert_eq!(
+        parse_year_constants("# 2025\nfederal_brackets = [[0.0, 0.15]]\n"),
+        Err(ConfigError::MissingKey("year"))
+    );
+    assert_eq!(
+        parse_year_constants("year = 2025\nfederal_brackets = [[0.0, 0.15]]\n"),
+        Err(ConfigError::InvalidValue { line: 2, key: "federal_brackets".to_string() })
+    );
+    assert_eq!(parse_year_constants("year 2025\n"), Err(ConfigError::Syntax { line: 1 }));
+    assert_eq!(load_year_constants(std::path::Path::new("/nonexistent/2025.toml")), Err(ConfigError::Io(std::io::ErrorKind::NotFound)));
+}
+
+// The 2025 configuration file with one key's value replaced, and that key's line number
+fn with_2025_value(key: &str, value: &str) -> (String, usize) {
+    let text = cdn_payroll::year::config::to_config(&v2025::CONSTANTS);
+    let line = text.lines().position(|l| l.starts_with(&format!("{} =", key))).unwrap() + 1;
+    let lines: Vec<String> =
+        text.lines().map(|l| if l.starts_with(&format!("{} =", key)) { format!("{} = {}", key, value) } else { l.to_string() }).collect();
+    (lines.join("\n"), line)
+}
+
+#[test]
+fn bracket_tables_must_start_at_zero() {
+    for brackets in ["[]", "[[57375.0, 0.205, 3156.0]]"] {
+        let (text, line) = with_2025_value("federal_brackets", brackets);
+        assert_eq!(parse_year_constants(&text), Err(ConfigError::InvalidValue { line, key: "federal_brackets".to_string() }));
+    }
+    let (text, _) = with_2025_value("federal_brackets", "[[0.0, 0.15, 0.0]]");
+    assert!(parse_year_constants(&text).is_ok());
+}
+
+#[test]
+fn the_ontario_surtax_has_two_tiers() {
+    for surtax in ["[]", "[[5710.0, 0.2]]"] {
+        let (text, line) = with_2025_value("on_surtax", surtax);
+        assert_eq!(parse_year_constants(&text), Err(ConfigError::InvalidValue { line, key: "on_surtax".to_string() }));
+    }
+}